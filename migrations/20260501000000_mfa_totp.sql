@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS shs_iam_user_mfa (
+    id               BIGSERIAL PRIMARY KEY,
+    user_id          BIGINT NOT NULL UNIQUE,
+    secret_encrypted TEXT NOT NULL,
+    confirmed_at     TIMESTAMPTZ,
+    last_used_step   BIGINT,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_mfa_recovery_code (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    BIGINT NOT NULL,
+    code_hash  VARCHAR(255) NOT NULL,
+    used_at    TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_mfa_recovery_code_user_id ON shs_iam_mfa_recovery_code(user_id);
+
+COMMENT ON TABLE  shs_iam_user_mfa IS '사용자의 TOTP 2단계 인증 정보입니다.';
+COMMENT ON COLUMN shs_iam_user_mfa.user_id IS '연관된 User의 PK (FK)';
+COMMENT ON COLUMN shs_iam_user_mfa.secret_encrypted IS '암호화된 TOTP secret (AES-256-GCM, base64)';
+COMMENT ON COLUMN shs_iam_user_mfa.confirmed_at IS '등록 확인 코드 검증 일시. NULL 이면 등록 진행 중';
+COMMENT ON COLUMN shs_iam_user_mfa.last_used_step IS '마지막으로 사용된 TOTP step (같은 코드 재사용 방지)';
+
+COMMENT ON TABLE  shs_iam_mfa_recovery_code IS 'TOTP 기기를 잃어버린 경우 사용하는 1회용 복구 코드입니다.';
+COMMENT ON COLUMN shs_iam_mfa_recovery_code.code_hash IS 'PasswordService 로 해시한 복구 코드';
+COMMENT ON COLUMN shs_iam_mfa_recovery_code.used_at IS '사용 일시. NULL 이면 미사용';
@@ -0,0 +1,26 @@
+ALTER TABLE shs_iam_user_identity ADD COLUMN IF NOT EXISTS credential_public_key TEXT;
+ALTER TABLE shs_iam_user_identity ADD COLUMN IF NOT EXISTS sign_count BIGINT;
+
+-- passkey credential id 는 사용자와 관계없이 유일해야 한다.
+CREATE UNIQUE INDEX IF NOT EXISTS shs_iam_user_identity_passkey_idx
+ON shs_iam_user_identity (provider_uid)
+WHERE provider = 'passkey';
+
+CREATE TABLE IF NOT EXISTS shs_iam_webauthn_challenge (
+    id         BIGSERIAL PRIMARY KEY,
+    challenge  VARCHAR(255) NOT NULL UNIQUE,
+    user_id    BIGINT,
+    ceremony   TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webauthn_challenge_expires_at ON shs_iam_webauthn_challenge(expires_at);
+
+COMMENT ON COLUMN shs_iam_user_identity.credential_public_key IS '(Passkey 전용) COSE 형식 공개키 (base64url)';
+COMMENT ON COLUMN shs_iam_user_identity.sign_count IS '(Passkey 전용) authenticator 의 서명 카운터. 줄어들면 복제된 credential 로 본다';
+
+COMMENT ON TABLE  shs_iam_webauthn_challenge IS 'WebAuthn 등록/인증 ceremony 에 발급한 1회용 challenge 입니다.';
+COMMENT ON COLUMN shs_iam_webauthn_challenge.challenge IS 'base64url challenge';
+COMMENT ON COLUMN shs_iam_webauthn_challenge.user_id IS '대상 User의 PK. passwordless 로그인은 NULL';
+COMMENT ON COLUMN shs_iam_webauthn_challenge.ceremony IS 'registration | authentication';
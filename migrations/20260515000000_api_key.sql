@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS shs_iam_api_key (
+    id           BIGSERIAL PRIMARY KEY,
+    user_id      BIGINT NOT NULL,
+    name         VARCHAR(255) NOT NULL,
+    prefix       VARCHAR(32) NOT NULL UNIQUE,
+    key_hash     VARCHAR(255) NOT NULL UNIQUE,
+    permissions  TEXT NOT NULL DEFAULT '',
+    expires_at   TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_key_user_id ON shs_iam_api_key(user_id);
+
+COMMENT ON TABLE  shs_iam_api_key IS '스크립트, CI 등 machine client 가 사용하는 사용자 API key 입니다.';
+COMMENT ON COLUMN shs_iam_api_key.user_id IS '키를 발급한 User의 PK (FK)';
+COMMENT ON COLUMN shs_iam_api_key.prefix IS '키를 구분하기 위한 공개 prefix (예: shs_abcd1234efgh)';
+COMMENT ON COLUMN shs_iam_api_key.key_hash IS '전체 키의 sha256 (hex). 평문은 저장하지 않음';
+COMMENT ON COLUMN shs_iam_api_key.permissions IS '공백으로 구분된 권한 코드. 발급자가 가진 권한의 부분집합';
+COMMENT ON COLUMN shs_iam_api_key.expires_at IS '만료 일시. NULL 이면 만료 없음';
+COMMENT ON COLUMN shs_iam_api_key.last_used_at IS '마지막 사용 일시 (분 단위로 갱신)';
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS shs_iam_oauth_client (
+    id                 BIGSERIAL PRIMARY KEY,
+    client_id          VARCHAR(64) NOT NULL UNIQUE,
+    client_secret_hash VARCHAR(255) NOT NULL,
+    name               VARCHAR(255) NOT NULL,
+    scopes             TEXT NOT NULL DEFAULT '',
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE  shs_iam_oauth_client IS 'shinespark 에서 토큰을 발급받는 등록된 OAuth2 client 입니다.';
+COMMENT ON COLUMN shs_iam_oauth_client.client_id IS '공개 client 식별자';
+COMMENT ON COLUMN shs_iam_oauth_client.client_secret_hash IS 'client secret 의 sha256 (hex). 평문은 저장하지 않음';
+COMMENT ON COLUMN shs_iam_oauth_client.scopes IS '공백으로 구분된 허용 scope (권한 코드)';
+
+INSERT INTO shs_iam_permission (code, description)
+SELECT * FROM (
+    SELECT 'oauth_client.create.all' as code, 'OAuth client 등록' as description
+) AS tmp
+WHERE NOT EXISTS (
+    SELECT 1 FROM shs_iam_permission WHERE code = tmp.code
+);
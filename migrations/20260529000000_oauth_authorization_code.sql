@@ -0,0 +1,25 @@
+ALTER TABLE shs_iam_oauth_client ADD COLUMN IF NOT EXISTS redirect_uris TEXT NOT NULL DEFAULT '';
+
+CREATE TABLE IF NOT EXISTS shs_iam_oauth_authorization_code (
+    id             BIGSERIAL PRIMARY KEY,
+    code_hash      VARCHAR(255) NOT NULL UNIQUE,
+    client_id      VARCHAR(64) NOT NULL,
+    user_id        BIGINT NOT NULL,
+    redirect_uri   TEXT NOT NULL,
+    scope          TEXT NOT NULL,
+    code_challenge VARCHAR(255) NOT NULL,
+    nonce          VARCHAR(255),
+    expires_at     TIMESTAMPTZ NOT NULL,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_authorization_code_expires_at ON shs_iam_oauth_authorization_code(expires_at);
+
+COMMENT ON COLUMN shs_iam_oauth_client.redirect_uris IS '공백으로 구분된 허용 redirect_uri. authorization_code grant 에서 정확히 일치해야 한다';
+
+COMMENT ON TABLE  shs_iam_oauth_authorization_code IS 'authorization_code grant 의 1회용 code 입니다.';
+COMMENT ON COLUMN shs_iam_oauth_authorization_code.code_hash IS 'code 의 sha256 (hex). 평문은 저장하지 않음';
+COMMENT ON COLUMN shs_iam_oauth_authorization_code.user_id IS '동의한 User의 PK (FK)';
+COMMENT ON COLUMN shs_iam_oauth_authorization_code.scope IS '공백으로 구분된 승인된 scope';
+COMMENT ON COLUMN shs_iam_oauth_authorization_code.code_challenge IS 'PKCE S256 code_challenge';
+COMMENT ON COLUMN shs_iam_oauth_authorization_code.nonce IS 'OIDC nonce. ID token 에 그대로 담는다';
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS shs_iam_revoked_token (
+    id          BIGSERIAL PRIMARY KEY,
+    token_hash  VARCHAR(255) NOT NULL UNIQUE,
+    expires_at  TIMESTAMPTZ NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_token_expires_at ON shs_iam_revoked_token(expires_at);
+
+COMMENT ON TABLE  shs_iam_revoked_token IS '만료 전에 폐기된 access token 의 denylist 입니다. (RFC 7009)';
+COMMENT ON COLUMN shs_iam_revoked_token.token_hash IS 'access token 의 sha256 (hex)';
+COMMENT ON COLUMN shs_iam_revoked_token.expires_at IS '토큰의 원래 만료 시각. 이후에는 목록에서 지워도 된다';
@@ -0,0 +1,7 @@
+INSERT INTO shs_iam_permission (code, description)
+SELECT * FROM (
+    SELECT 'user.impersonate.all' as code, '다른 사용자로 대리 로그인' as description
+) AS tmp
+WHERE NOT EXISTS (
+    SELECT 1 FROM shs_iam_permission WHERE code = tmp.code
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS shs_http_session (
+    id           VARCHAR(64) PRIMARY KEY,
+    data         JSONB NOT NULL,
+    expiry_date  TIMESTAMPTZ NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_http_session_expiry_date ON shs_http_session(expiry_date);
+
+COMMENT ON TABLE  shs_http_session IS 'tower-sessions 세션 저장소입니다. 재시작과 여러 인스턴스 사이에서 세션을 공유합니다.';
+COMMENT ON COLUMN shs_http_session.id IS 'SID 쿠키 값 (session id)';
+COMMENT ON COLUMN shs_http_session.data IS '세션 데이터. 로그인 사용자는 uid 만 저장한다';
+COMMENT ON COLUMN shs_http_session.expiry_date IS '이 시각 이후의 세션은 읽지 않고, 정리 작업이 지운다';
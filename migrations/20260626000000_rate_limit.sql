@@ -0,0 +1,13 @@
+CREATE UNLOGGED TABLE IF NOT EXISTS shs_http_rate_limit (
+    bucket_key  VARCHAR(255) PRIMARY KEY,
+    tokens      DOUBLE PRECISION NOT NULL,
+    allowed     BOOLEAN NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_http_rate_limit_updated_at ON shs_http_rate_limit(updated_at);
+
+COMMENT ON TABLE  shs_http_rate_limit IS 'rate_limit.store = "postgres" 일 때 여러 인스턴스가 공유하는 token bucket 입니다.';
+COMMENT ON COLUMN shs_http_rate_limit.bucket_key IS '{group}:ip:{addr} 또는 {group}:sub:{subject}';
+COMMENT ON COLUMN shs_http_rate_limit.tokens IS 'updated_at 시점에 남은 token 수';
+COMMENT ON COLUMN shs_http_rate_limit.allowed IS '마지막 요청의 허용 여부';
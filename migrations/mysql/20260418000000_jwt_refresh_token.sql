@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS shs_iam_refresh_token (
+    id          BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_uid    BINARY(16) NOT NULL,
+    token_hash  VARCHAR(255) NOT NULL UNIQUE,
+    expires_at  DATETIME(6) NOT NULL,
+    created_at  DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_refresh_token_user_uid (user_uid)
+);
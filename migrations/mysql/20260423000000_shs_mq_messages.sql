@@ -0,0 +1,15 @@
+-- id 는 gen_random_uuid() 대신 MySqlMessageQueue 가 만들어 넣는다.
+-- 부분 인덱스가 없으므로 status 를 인덱스 앞에 둔다.
+CREATE TABLE IF NOT EXISTS shs_mq_messages (
+    id           BINARY(16)  PRIMARY KEY,
+    topic        VARCHAR(255) NOT NULL,
+    payload      JSON        NOT NULL,
+    status       VARCHAR(32) NOT NULL DEFAULT 'pending',
+    attempts     INT         NOT NULL DEFAULT 0,
+    max_attempts INT         NOT NULL DEFAULT 3,
+    locked_at    DATETIME(6),
+    done_at      DATETIME(6),
+    created_at   DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at   DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_shs_mq_pending (status, topic, created_at)
+);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS shs_iam_user_mfa (
+    id               BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id          BIGINT NOT NULL UNIQUE,
+    secret_encrypted TEXT NOT NULL,
+    confirmed_at     DATETIME(6),
+    last_used_step   BIGINT,
+    created_at       DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at       DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_mfa_recovery_code (
+    id         BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id    BIGINT NOT NULL,
+    code_hash  VARCHAR(255) NOT NULL,
+    used_at    DATETIME(6),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_mfa_recovery_code_user_id (user_id)
+);
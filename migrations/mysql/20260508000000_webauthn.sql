@@ -0,0 +1,16 @@
+ALTER TABLE shs_iam_user_identity ADD COLUMN credential_public_key TEXT;
+ALTER TABLE shs_iam_user_identity ADD COLUMN sign_count BIGINT;
+
+-- passkey credential id 는 사용자와 관계없이 유일해야 한다.
+CREATE UNIQUE INDEX shs_iam_user_identity_passkey_idx
+ON shs_iam_user_identity ((CASE WHEN provider = 'passkey' THEN provider_uid END));
+
+CREATE TABLE IF NOT EXISTS shs_iam_webauthn_challenge (
+    id         BIGINT AUTO_INCREMENT PRIMARY KEY,
+    challenge  VARCHAR(255) NOT NULL UNIQUE,
+    user_id    BIGINT,
+    ceremony   VARCHAR(32) NOT NULL,
+    expires_at DATETIME(6) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_webauthn_challenge_expires_at (expires_at)
+);
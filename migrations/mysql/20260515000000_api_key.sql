@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS shs_iam_api_key (
+    id           BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id      BIGINT NOT NULL,
+    name         VARCHAR(255) NOT NULL,
+    prefix       VARCHAR(32) NOT NULL UNIQUE,
+    key_hash     VARCHAR(255) NOT NULL UNIQUE,
+    permissions  TEXT NOT NULL DEFAULT (''),
+    expires_at   DATETIME(6),
+    last_used_at DATETIME(6),
+    created_at   DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_api_key_user_id (user_id)
+);
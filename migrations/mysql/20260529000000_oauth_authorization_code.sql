@@ -0,0 +1,15 @@
+ALTER TABLE shs_iam_oauth_client ADD COLUMN redirect_uris TEXT NOT NULL DEFAULT ('');
+
+CREATE TABLE IF NOT EXISTS shs_iam_oauth_authorization_code (
+    id             BIGINT AUTO_INCREMENT PRIMARY KEY,
+    code_hash      VARCHAR(255) NOT NULL UNIQUE,
+    client_id      VARCHAR(64) NOT NULL,
+    user_id        BIGINT NOT NULL,
+    redirect_uri   TEXT NOT NULL,
+    scope          TEXT NOT NULL,
+    code_challenge VARCHAR(255) NOT NULL,
+    nonce          VARCHAR(255),
+    expires_at     DATETIME(6) NOT NULL,
+    created_at     DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_oauth_authorization_code_expires_at (expires_at)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS shs_iam_revoked_token (
+    id          BIGINT AUTO_INCREMENT PRIMARY KEY,
+    token_hash  VARCHAR(255) NOT NULL UNIQUE,
+    expires_at  DATETIME(6) NOT NULL,
+    created_at  DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_revoked_token_expires_at (expires_at)
+);
@@ -0,0 +1,9 @@
+-- expiry_date 는 unix timestamp(초) 로 저장한다.
+CREATE TABLE IF NOT EXISTS shs_http_session (
+    id           VARCHAR(64) PRIMARY KEY,
+    data         JSON NOT NULL,
+    expiry_date  BIGINT NOT NULL,
+    created_at   DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at   DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    INDEX idx_http_session_expiry_date (expiry_date)
+);
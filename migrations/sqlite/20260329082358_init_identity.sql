@@ -0,0 +1,122 @@
+-- postgres 마이그레이션(../20260329082358_init_identity.sql)의 sqlite 버전.
+-- 테이블/컬럼 설명은 postgres 쪽 COMMENT ON 을 참고한다.
+--   BIGSERIAL   -> INTEGER PRIMARY KEY AUTOINCREMENT
+--   UUID        -> BLOB (sqlx 가 16 byte 로 저장)
+--   TIMESTAMPTZ -> TEXT (UTC)
+
+--------------------------------------------------------------------------------
+-- 1. Create Tables
+--------------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS shs_iam_user (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    uid BLOB NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    status TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 삭제된 사용자를 제외한 이메일 중복 방지
+CREATE UNIQUE INDEX IF NOT EXISTS shs_iam_user_email_active_idx
+ON shs_iam_user (email)
+WHERE status != 'deleted';
+
+CREATE TABLE IF NOT EXISTS shs_iam_user_identity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BIGINT NOT NULL,
+    provider TEXT NOT NULL,
+    provider_uid VARCHAR(255) NOT NULL,
+    credential_hash VARCHAR(255),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, provider, provider_uid)
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_user_audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BIGINT NOT NULL,
+    action TEXT NOT NULL,
+    description VARCHAR(255),
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    is_success BOOLEAN NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_permission (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code VARCHAR(255) NOT NULL UNIQUE,
+    description VARCHAR(255) NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_role (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    description VARCHAR(255) NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_role_permission (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    role_id BIGINT NOT NULL,
+    permission_id BIGINT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (role_id, permission_id)
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_user_role (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BIGINT NOT NULL,
+    role_id BIGINT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, role_id)
+);
+
+--------------------------------------------------------------------------------
+-- 2. Add Default Data
+--------------------------------------------------------------------------------
+
+INSERT INTO shs_iam_role (name, description)
+SELECT * FROM (
+    SELECT 'admin' as name, '관리자'
+    UNION ALL
+    SELECT 'user' as name, '사용자'
+) AS tmp
+WHERE NOT EXISTS (
+    SELECT 1 FROM shs_iam_role WHERE name = tmp.name
+);
+
+
+INSERT INTO shs_iam_permission (code, description)
+SELECT * FROM (
+    SELECT '*.*.all' as code, '모든 시스템 전체 권한' as description
+    UNION ALL SELECT 'user.read.all' as code, '모든 사용자 조회' as description
+    UNION ALL SELECT 'user.create.all' as code, '모든 사용자 생성' as description
+    UNION ALL SELECT 'user.update.all' as code, '모든 사용자 수정' as description
+    UNION ALL SELECT 'user.delete.all' as code, '모든 사용자 삭제' as description
+    UNION ALL SELECT 'user.read.own' as code, '본인 정보 조회' as description
+    UNION ALL SELECT 'user.update.own' as code, '본인 정보 수정' as description
+    UNION ALL SELECT 'user.delete.own' as code, '본인 계정 탈퇴' as description
+) AS tmp
+WHERE NOT EXISTS (
+    SELECT 1 FROM shs_iam_permission WHERE code = tmp.code
+);
+
+INSERT INTO shs_iam_role_permission (role_id, permission_id)
+SELECT * FROM (
+    SELECT sir.id as role_id, sip.id as permission_id
+    FROM shs_iam_role sir, shs_iam_permission sip
+    WHERE sir.name = 'admin'
+        AND sip.code = '*.*.all'
+    UNION ALL
+    SELECT sir.id as role_id, sip.id as permission_id
+    FROM shs_iam_role sir, shs_iam_permission sip
+    WHERE sir.name = 'user'
+        AND sip.code IN ('user.read.own', 'user.update.own', 'user.delete.own')
+) AS tmp
+WHERE NOT EXISTS (
+    SELECT 1 FROM shs_iam_role_permission WHERE role_id = tmp.role_id AND permission_id = tmp.permission_id
+);
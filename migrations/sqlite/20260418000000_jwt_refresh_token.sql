@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS shs_iam_refresh_token (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_uid    BLOB NOT NULL,
+    token_hash  VARCHAR(255) NOT NULL UNIQUE,
+    expires_at  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_token_user_uid ON shs_iam_refresh_token(user_uid);
@@ -0,0 +1,17 @@
+-- id 는 gen_random_uuid() 대신 SqliteMessageQueue 가 만들어 넣는다.
+CREATE TABLE IF NOT EXISTS shs_mq_messages (
+    id           BLOB    PRIMARY KEY,
+    topic        TEXT    NOT NULL,
+    payload      TEXT    NOT NULL,
+    status       TEXT    NOT NULL DEFAULT 'pending',
+    attempts     INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    locked_at    TEXT,
+    done_at      TEXT,
+    created_at   TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at   TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_shs_mq_pending
+    ON shs_mq_messages (topic, created_at)
+    WHERE status = 'pending';
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS shs_iam_user_mfa (
+    id               INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id          BIGINT NOT NULL UNIQUE,
+    secret_encrypted TEXT NOT NULL,
+    confirmed_at     TEXT,
+    last_used_step   BIGINT,
+    created_at       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS shs_iam_mfa_recovery_code (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id    BIGINT NOT NULL,
+    code_hash  VARCHAR(255) NOT NULL,
+    used_at    TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_mfa_recovery_code_user_id ON shs_iam_mfa_recovery_code(user_id);
@@ -0,0 +1,18 @@
+ALTER TABLE shs_iam_user_identity ADD COLUMN credential_public_key TEXT;
+ALTER TABLE shs_iam_user_identity ADD COLUMN sign_count BIGINT;
+
+-- passkey credential id 는 사용자와 관계없이 유일해야 한다.
+CREATE UNIQUE INDEX IF NOT EXISTS shs_iam_user_identity_passkey_idx
+ON shs_iam_user_identity (provider_uid)
+WHERE provider = 'passkey';
+
+CREATE TABLE IF NOT EXISTS shs_iam_webauthn_challenge (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    challenge  VARCHAR(255) NOT NULL UNIQUE,
+    user_id    BIGINT,
+    ceremony   TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_webauthn_challenge_expires_at ON shs_iam_webauthn_challenge(expires_at);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS shs_iam_api_key (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id      BIGINT NOT NULL,
+    name         VARCHAR(255) NOT NULL,
+    prefix       VARCHAR(32) NOT NULL UNIQUE,
+    key_hash     VARCHAR(255) NOT NULL UNIQUE,
+    permissions  TEXT NOT NULL DEFAULT '',
+    expires_at   TEXT,
+    last_used_at TEXT,
+    created_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_key_user_id ON shs_iam_api_key(user_id);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS shs_iam_oauth_client (
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    client_id          VARCHAR(64) NOT NULL UNIQUE,
+    client_secret_hash VARCHAR(255) NOT NULL,
+    name               VARCHAR(255) NOT NULL,
+    scopes             TEXT NOT NULL DEFAULT '',
+    created_at         TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO shs_iam_permission (code, description)
+SELECT * FROM (
+    SELECT 'oauth_client.create.all' as code, 'OAuth client 등록' as description
+) AS tmp
+WHERE NOT EXISTS (
+    SELECT 1 FROM shs_iam_permission WHERE code = tmp.code
+);
@@ -0,0 +1,16 @@
+ALTER TABLE shs_iam_oauth_client ADD COLUMN redirect_uris TEXT NOT NULL DEFAULT '';
+
+CREATE TABLE IF NOT EXISTS shs_iam_oauth_authorization_code (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    code_hash      VARCHAR(255) NOT NULL UNIQUE,
+    client_id      VARCHAR(64) NOT NULL,
+    user_id        BIGINT NOT NULL,
+    redirect_uri   TEXT NOT NULL,
+    scope          TEXT NOT NULL,
+    code_challenge VARCHAR(255) NOT NULL,
+    nonce          VARCHAR(255),
+    expires_at     TEXT NOT NULL,
+    created_at     TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_authorization_code_expires_at ON shs_iam_oauth_authorization_code(expires_at);
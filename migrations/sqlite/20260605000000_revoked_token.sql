@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS shs_iam_revoked_token (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_hash  VARCHAR(255) NOT NULL UNIQUE,
+    expires_at  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_token_expires_at ON shs_iam_revoked_token(expires_at);
@@ -0,0 +1,10 @@
+-- expiry_date 는 unix timestamp(초) 로 저장한다.
+CREATE TABLE IF NOT EXISTS shs_http_session (
+    id           VARCHAR(64) PRIMARY KEY,
+    data         TEXT NOT NULL,
+    expiry_date  INTEGER NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_http_session_expiry_date ON shs_http_session(expiry_date);
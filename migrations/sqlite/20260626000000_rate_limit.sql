@@ -0,0 +1,9 @@
+-- updated_at 은 refill 계산을 위해 소수점이 있는 unix timestamp(초) 로 저장한다.
+CREATE TABLE IF NOT EXISTS shs_http_rate_limit (
+    bucket_key  VARCHAR(255) PRIMARY KEY,
+    tokens      REAL NOT NULL,
+    allowed     BOOLEAN NOT NULL,
+    updated_at  REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_http_rate_limit_updated_at ON shs_http_rate_limit(updated_at);
@@ -0,0 +1,8 @@
+-- ON DUPLICATE KEY UPDATE 는 앞에서 바꾼 컬럼 값을 뒤의 식에서 읽으므로 allowed 를 먼저 계산하고
+-- updated_at 을 마지막에 바꾼다. RETURNING 이 없어 결과는 같은 트랜잭션에서 find_bucket.sql 로 읽는다.
+INSERT INTO shs_http_rate_limit (bucket_key, tokens, allowed, updated_at)
+VALUES (?, ? - 1, TRUE, UNIX_TIMESTAMP(NOW(6)))
+ON DUPLICATE KEY UPDATE
+    allowed = LEAST(?, tokens + (UNIX_TIMESTAMP(NOW(6)) - updated_at) * ?) >= 1,
+    tokens = LEAST(?, tokens + (UNIX_TIMESTAMP(NOW(6)) - updated_at) * ?) - IF(allowed, 1, 0),
+    updated_at = UNIX_TIMESTAMP(NOW(6))
@@ -0,0 +1 @@
+DELETE FROM shs_http_rate_limit WHERE updated_at < UNIX_TIMESTAMP(NOW(6)) - ?
\ No newline at end of file
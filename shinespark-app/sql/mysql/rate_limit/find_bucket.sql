@@ -0,0 +1 @@
+SELECT tokens, allowed FROM shs_http_rate_limit WHERE bucket_key = ?
\ No newline at end of file
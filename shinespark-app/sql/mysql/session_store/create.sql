@@ -0,0 +1,2 @@
+INSERT IGNORE INTO shs_http_session (id, data, expiry_date)
+VALUES (?, ?, ?)
\ No newline at end of file
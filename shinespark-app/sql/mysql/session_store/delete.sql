@@ -0,0 +1 @@
+DELETE FROM shs_http_session WHERE id = ?
\ No newline at end of file
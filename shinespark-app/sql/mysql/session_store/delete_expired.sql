@@ -0,0 +1 @@
+DELETE FROM shs_http_session WHERE expiry_date <= UNIX_TIMESTAMP()
\ No newline at end of file
@@ -0,0 +1,5 @@
+SELECT data, expiry_date
+FROM shs_http_session
+WHERE 1=1
+  AND id = ?
+  AND expiry_date > UNIX_TIMESTAMP()
\ No newline at end of file
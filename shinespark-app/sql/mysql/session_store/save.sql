@@ -0,0 +1,4 @@
+INSERT INTO shs_http_session (id, data, expiry_date)
+VALUES (?, ?, ?) AS new
+ON DUPLICATE KEY UPDATE
+    data = new.data, expiry_date = new.expiry_date, updated_at = CURRENT_TIMESTAMP(6)
\ No newline at end of file
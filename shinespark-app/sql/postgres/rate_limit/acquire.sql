@@ -0,0 +1,15 @@
+INSERT INTO shs_http_rate_limit (bucket_key, tokens, allowed, updated_at)
+VALUES ($1, $2 - 1, TRUE, NOW())
+ON CONFLICT (bucket_key) DO UPDATE
+SET tokens = CASE
+        WHEN LEAST($2, shs_http_rate_limit.tokens
+            + EXTRACT(EPOCH FROM NOW() - shs_http_rate_limit.updated_at) * $3) >= 1
+        THEN LEAST($2, shs_http_rate_limit.tokens
+            + EXTRACT(EPOCH FROM NOW() - shs_http_rate_limit.updated_at) * $3) - 1
+        ELSE LEAST($2, shs_http_rate_limit.tokens
+            + EXTRACT(EPOCH FROM NOW() - shs_http_rate_limit.updated_at) * $3)
+    END,
+    allowed = LEAST($2, shs_http_rate_limit.tokens
+        + EXTRACT(EPOCH FROM NOW() - shs_http_rate_limit.updated_at) * $3) >= 1,
+    updated_at = NOW()
+RETURNING tokens::DOUBLE PRECISION AS tokens, allowed
\ No newline at end of file
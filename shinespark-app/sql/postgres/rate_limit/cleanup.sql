@@ -0,0 +1 @@
+DELETE FROM shs_http_rate_limit WHERE updated_at < NOW() - make_interval(secs => $1)
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_http_session WHERE expiry_date <= NOW()
\ No newline at end of file
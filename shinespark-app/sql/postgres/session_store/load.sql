@@ -0,0 +1,5 @@
+SELECT data, EXTRACT(EPOCH FROM expiry_date)::BIGINT AS expiry_date
+FROM shs_http_session
+WHERE 1=1
+  AND id = $1
+  AND expiry_date > NOW()
\ No newline at end of file
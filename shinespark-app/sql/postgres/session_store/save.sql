@@ -0,0 +1,4 @@
+INSERT INTO shs_http_session (id, data, expiry_date)
+VALUES ($1, $2, to_timestamp($3))
+ON CONFLICT (id) DO UPDATE
+SET data = EXCLUDED.data, expiry_date = EXCLUDED.expiry_date, updated_at = NOW()
\ No newline at end of file
@@ -0,0 +1,16 @@
+-- updated_at 은 unix timestamp(초). julianday 로 소수점 이하까지 계산한다.
+INSERT INTO shs_http_rate_limit (bucket_key, tokens, allowed, updated_at)
+VALUES ($1, $2 - 1, TRUE, (julianday('now') - 2440587.5) * 86400.0)
+ON CONFLICT (bucket_key) DO UPDATE
+SET tokens = CASE
+        WHEN MIN($2, shs_http_rate_limit.tokens
+            + ((julianday('now') - 2440587.5) * 86400.0 - shs_http_rate_limit.updated_at) * $3) >= 1
+        THEN MIN($2, shs_http_rate_limit.tokens
+            + ((julianday('now') - 2440587.5) * 86400.0 - shs_http_rate_limit.updated_at) * $3) - 1
+        ELSE MIN($2, shs_http_rate_limit.tokens
+            + ((julianday('now') - 2440587.5) * 86400.0 - shs_http_rate_limit.updated_at) * $3)
+    END,
+    allowed = MIN($2, shs_http_rate_limit.tokens
+        + ((julianday('now') - 2440587.5) * 86400.0 - shs_http_rate_limit.updated_at) * $3) >= 1,
+    updated_at = (julianday('now') - 2440587.5) * 86400.0
+RETURNING tokens, allowed
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_http_rate_limit WHERE updated_at < (julianday('now') - 2440587.5) * 86400.0 - $1
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO shs_http_session (id, data, expiry_date)
+VALUES ($1, $2, $3)
+ON CONFLICT (id) DO NOTHING
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_http_session WHERE expiry_date <= CAST(strftime('%s', 'now') AS INTEGER)
\ No newline at end of file
@@ -0,0 +1,5 @@
+SELECT data, expiry_date
+FROM shs_http_session
+WHERE 1=1
+  AND id = $1
+  AND expiry_date > CAST(strftime('%s', 'now') AS INTEGER)
\ No newline at end of file
@@ -1,8 +1,12 @@
+pub mod api_key;
 mod api_response;
 pub mod cookie_jwt;
+pub mod csrf;
 pub mod jwt;
 pub mod middleware;
+pub mod oauth2_state;
 pub mod session;
+pub mod session_store;
 pub mod template;
 
 pub mod routes;
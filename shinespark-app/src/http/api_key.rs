@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use shinespark_identity::infra::JwtClaims;
+
+use crate::AppContainer;
+use crate::http::ApiError;
+use crate::http::jwt::ScopedJwtUser;
+
+/// `Authorization: ApiKey <key>` 로 인증된 machine client.
+/// claims 는 `JwtUser` 와 같은 모양이며 `token_type` 이 "api_key" 이다.
+pub struct ApiKeyUser(pub JwtClaims);
+
+impl<S> FromRequestParts<S> for ApiKeyUser
+where
+    S: Send + Sync,
+    Arc<AppContainer>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let container = Arc::<AppContainer>::from_ref(state);
+
+        let key = parts
+            .headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("ApiKey "))
+            .ok_or_else(|| ApiError::from(shinespark::Error::UnAuthorized))?;
+
+        let claims = container
+            .api_key_usecase
+            .authenticate(&mut container.db.handle(), key.trim())
+            .await
+            .map_err(|_| ApiError::from(shinespark::Error::UnAuthorized))?;
+
+        Ok(ApiKeyUser(claims))
+    }
+}
+
+/// Bearer access token(OAuth client 에 발급된 토큰 포함) 또는 API key 중 하나로 인증된 사용자.
+/// 권한은 `JwtClaims::has_perm` 으로 확인한다.
+/// 사람이 로그인해야 하는 작업(키 발급, 2 단계 인증 설정 등)에는 `JwtUser` 를 사용한다.
+pub struct AuthUser(pub JwtClaims);
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+    Arc<AppContainer>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let is_api_key = parts
+            .headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("ApiKey "));
+
+        if is_api_key {
+            ApiKeyUser::from_request_parts(parts, state).await.map(|u| AuthUser(u.0))
+        } else {
+            ScopedJwtUser::from_request_parts(parts, state).await.map(|u| AuthUser(u.0))
+        }
+    }
+}
@@ -51,7 +51,8 @@ impl From<shinespark::Error> for ApiError {
             | shinespark::Error::NotImplemented => StatusCode::INTERNAL_SERVER_ERROR,
             shinespark::Error::NotFound
             | shinespark::Error::AlreadyExists
-            | shinespark::Error::InvalidCredentials => StatusCode::BAD_REQUEST,
+            | shinespark::Error::InvalidCredentials
+            | shinespark::Error::InvalidArgument(_) => StatusCode::BAD_REQUEST,
             shinespark::Error::UnAuthorized => StatusCode::UNAUTHORIZED,
         };
         Self {
@@ -61,3 +62,21 @@ impl From<shinespark::Error> for ApiError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::{Cursor, Sort};
+
+    use super::*;
+
+    #[test]
+    fn test_invalid_query_input_is_bad_request() {
+        let sort = Sort::parse("password", &[("name", "name")]).unwrap_err();
+        let cursor = Cursor::decode("not a cursor").unwrap_err();
+        for error in [sort, cursor] {
+            let error = ApiError::from(error);
+            assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+            assert_eq!(error.code, "INVALID_ARGUMENT");
+        }
+    }
+}
@@ -19,14 +19,17 @@ enum TokenStatus {
     Invalid,
 }
 
-fn check_access_token(container: &AppContainer, jar: &CookieJar) -> TokenStatus {
+async fn check_access_token(container: &AppContainer, jar: &CookieJar) -> TokenStatus {
     let Some(cookie) = jar.get("access_token") else {
         return TokenStatus::TryRefresh;
     };
     let token = cookie.value();
-    match container.jwt_service.verify(token) {
-        Ok(claims) if claims.token_type == "access" => TokenStatus::Valid(claims),
-        Err(_) if container.jwt_service.is_expired(token) => TokenStatus::TryRefresh,
+    if container.jwt_service.is_expired(token) {
+        return TokenStatus::TryRefresh;
+    }
+    // 쿠키는 1st party 로그인만 쓴다. OAuth client 에 위임된 토큰은 받지 않는다.
+    match container.jwt_ident_usecase.authenticate(&mut container.db.handle(), token).await {
+        Ok(claims) if claims.client_id.is_none() => TokenStatus::Valid(claims),
         _ => TokenStatus::Invalid,
     }
 }
@@ -42,18 +45,28 @@ async fn try_refresh(
     Some((claims, pair))
 }
 
+/// 로그인 후 원래 요청으로 돌아오도록 `return_to` 를 붙인다. (`/oauth2/authorize` 처럼 query 가 필요한 경로)
+fn login_redirect(req: &Request) -> Response {
+    let return_to = req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    if return_to == "/" {
+        return Redirect::to("/auth/login").into_response();
+    }
+    let query = serde_urlencoded::to_string([("return_to", return_to)]).unwrap_or_default();
+    Redirect::to(&format!("/auth/login?{}", query)).into_response()
+}
+
 pub async fn auth_middleware(
     State(container): State<Arc<AppContainer>>,
     jar: CookieJar,
     mut req: Request,
     next: Next,
 ) -> Response {
-    match check_access_token(&container, &jar) {
+    match check_access_token(&container, &jar).await {
         TokenStatus::Valid(claims) => {
             req.extensions_mut().insert(claims);
             return next.run(req).await;
         }
-        TokenStatus::Invalid => return Redirect::to("/auth/login").into_response(),
+        TokenStatus::Invalid => return login_redirect(&req),
         TokenStatus::TryRefresh => {}
     }
 
@@ -63,11 +76,10 @@ pub async fn auth_middleware(
             let res = next.run(req).await;
             (CookieJarJwt::build(&pair, &container.config.jwt), res).into_response()
         }
-        None => Redirect::to("/auth/login").into_response(),
+        None => login_redirect(&req),
     }
 }
 
-#[allow(dead_code)]
 pub struct CookieJwtUser(pub JwtClaims);
 
 impl<S: Send + Sync> FromRequestParts<S> for CookieJwtUser {
@@ -109,6 +121,25 @@ impl CookieJarJwt {
             ))
     }
 
+    /// 대리 로그인 토큰은 access_token 쿠키만 덮어쓴다.
+    /// 만료되거나 종료하면 남아 있는 관리자의 refresh_token 으로 원래 세션에 돌아간다.
+    pub fn impersonation(access_token: &str, ttl: i64, jwt_config: &JwtConfig) -> CookieJar {
+        CookieJar::new().add(
+            Cookie::build(("access_token", access_token.to_owned()))
+                .http_only(true)
+                .same_site(SameSite::Lax)
+                .path("/")
+                .max_age(Duration::seconds(ttl))
+                .secure(jwt_config.secure_cookie)
+                .build(),
+        )
+    }
+
+    pub fn clear_access_token() -> CookieJar {
+        CookieJar::new()
+            .add(Cookie::build(("access_token", "")).path("/").max_age(Duration::ZERO).build())
+    }
+
     pub fn clear() -> CookieJar {
         CookieJar::new()
             .add(Cookie::build(("access_token", "")).path("/").max_age(Duration::ZERO).build())
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderName, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use minijinja::Value;
+use shinespark::config::CsrfConfig;
+
+use crate::{AppContainer, http::ApiError};
+
+/// double-submit 쿠키. JS 에서 읽어 헤더로 보낼 수 있도록 HttpOnly 가 아니다.
+pub const CSRF_COOKIE: &str = "csrf_token";
+pub const CSRF_HEADER: HeaderName = HeaderName::from_static("x-csrf-token");
+pub const CSRF_FORM_FIELD: &str = "_csrf";
+
+/// 브라우저가 자동으로 붙이는 인증 쿠키. 이 쿠키가 있으면 JSON 요청도 검사한다.
+const CREDENTIAL_COOKIES: [&str; 3] = ["SID", "access_token", "refresh_token"];
+const MAX_FORM_BYTES: usize = 64 * 1024;
+
+tokio::task_local! {
+    static CURRENT_CSRF_TOKEN: String;
+}
+
+/// 템플릿의 `csrf_token()` 이 사용하는 현재 요청의 토큰
+pub fn current_token() -> Option<String> {
+    CURRENT_CSRF_TOKEN.try_with(|token| token.clone()).ok()
+}
+
+/// minijinja 에 `csrf_token()`, `csrf_field()` 함수를 등록한다.
+pub fn register_template_functions(env: &mut minijinja::Environment<'static>) {
+    env.add_function("csrf_token", || current_token().unwrap_or_default());
+    env.add_function("csrf_field", || {
+        Value::from_safe_string(format!(
+            r#"<input type="hidden" name="{}" value="{}">"#,
+            CSRF_FORM_FIELD,
+            current_token().unwrap_or_default()
+        ))
+    });
+}
+
+fn forbidden(message: &str) -> ApiError {
+    ApiError {
+        status_code: StatusCode::FORBIDDEN,
+        code: "CSRF_FAILED",
+        message: message.to_string(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn is_form(req: &Request) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/x-www-form-urlencoded"))
+}
+
+/// cross-site 에서 보낼 수 있는 요청인지 판단한다.
+///
+/// `Authorization` 헤더를 쓰는 bearer / API key 요청과 설정의 `exempt_paths` 는 검사하지 않는다.
+/// 그 외에 form 으로 보낼 수 있는 content type 이거나 인증 쿠키가 붙은 변경 요청을 검사한다.
+fn requires_check(req: &Request, jar: &CookieJar, config: &CsrfConfig) -> bool {
+    if matches!(
+        *req.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    ) {
+        return false;
+    }
+    if req.headers().contains_key(header::AUTHORIZATION) {
+        return false;
+    }
+    let path = req.uri().path();
+    if config.exempt_paths.iter().any(|exempt| path.starts_with(exempt.as_str())) {
+        return false;
+    }
+    let simple_content_type =
+        req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).is_none_or(|v| {
+            v.starts_with("application/x-www-form-urlencoded")
+                || v.starts_with("multipart/form-data")
+                || v.starts_with("text/plain")
+        });
+    simple_content_type || CREDENTIAL_COOKIES.iter().any(|name| jar.get(name).is_some())
+}
+
+/// 헤더나 form 의 `_csrf` 가 쿠키와 같은지 확인한다. form body 를 읽었다면 다시 채워 돌려준다.
+async fn verify(req: Request, expected: &str) -> Result<Request, ApiError> {
+    let invalid = || forbidden("invalid csrf token");
+
+    if let Some(submitted) = req.headers().get(CSRF_HEADER) {
+        return match constant_time_eq(submitted.as_bytes(), expected.as_bytes()) {
+            true => Ok(req),
+            false => Err(invalid()),
+        };
+    }
+    if !is_form(&req) {
+        return Err(invalid());
+    }
+
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_FORM_BYTES).await.map_err(|_| invalid())?;
+    let fields: Vec<(String, String)> = serde_urlencoded::from_bytes(&bytes).unwrap_or_default();
+    let submitted = fields.iter().find(|(k, _)| k == CSRF_FORM_FIELD).map(|(_, v)| v.as_str());
+    match submitted {
+        Some(submitted) if constant_time_eq(submitted.as_bytes(), expected.as_bytes()) => {
+            Ok(Request::from_parts(parts, Body::from(bytes)))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn build_cookie(token: String, config: &CsrfConfig) -> Cookie<'static> {
+    Cookie::build((CSRF_COOKIE, token))
+        .same_site(SameSite::Lax)
+        .path("/")
+        .secure(config.secure_cookie)
+        .build()
+}
+
+/// cookie / session 인증을 쓰는 요청의 CSRF 를 double-submit 쿠키로 막는다.
+///
+/// 쿠키가 없으면 새 토큰을 발급하고, 템플릿은 같은 요청 안에서 그 토큰을 렌더링한다.
+pub async fn csrf_middleware(
+    State(container): State<Arc<AppContainer>>,
+    jar: CookieJar,
+    req: Request,
+    next: Next,
+) -> Response {
+    let config = &container.config.csrf;
+    let existing = jar.get(CSRF_COOKIE).map(|c| c.value().to_owned()).filter(|v| !v.is_empty());
+
+    let req = match &existing {
+        _ if !config.enabled || !requires_check(&req, &jar, config) => req,
+        Some(expected) => match verify(req, expected).await {
+            Ok(req) => req,
+            Err(e) => return e.into_response(),
+        },
+        None => return forbidden("missing csrf token").into_response(),
+    };
+
+    let token = existing.clone().unwrap_or_else(shinespark::crypto::pkce::generate_code_verifier);
+    let response = CURRENT_CSRF_TOKEN.scope(token.clone(), next.run(req)).await;
+    match existing {
+        Some(_) => response,
+        None => (CookieJar::new().add(build_cookie(token, config)), response).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, content_type: Option<&str>) -> Request {
+        let mut builder = Request::builder().method(method).uri("/auth/login");
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_requires_check() {
+        let config = CsrfConfig::default();
+        let jar = CookieJar::new();
+        let form = Some("application/x-www-form-urlencoded");
+
+        assert!(!requires_check(&request(Method::GET, None), &jar, &config));
+        assert!(requires_check(&request(Method::POST, form), &jar, &config));
+        // 쿠키 없는 JSON 요청은 cross-site form 으로 보낼 수 없다.
+        assert!(!requires_check(
+            &request(Method::POST, Some("application/json")),
+            &jar,
+            &config
+        ));
+        let with_session = CookieJar::new().add(Cookie::new("SID", "x"));
+        assert!(requires_check(
+            &request(Method::POST, Some("application/json")),
+            &with_session,
+            &config
+        ));
+
+        let mut bearer = request(Method::POST, form);
+        bearer.headers_mut().insert(header::AUTHORIZATION, "Bearer t".parse().unwrap());
+        assert!(!requires_check(&bearer, &with_session, &config));
+
+        let token = Request::builder()
+            .method(Method::POST)
+            .uri("/oauth2/token")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::empty())
+            .unwrap();
+        assert!(!requires_check(&token, &jar, &config));
+    }
+
+    #[tokio::test]
+    async fn test_template_functions() {
+        let mut env = minijinja::Environment::new();
+        register_template_functions(&mut env);
+        let html = CURRENT_CSRF_TOKEN
+            .scope("token".to_string(), async {
+                env.render_str("{{ csrf_field() }}", ()).unwrap()
+            })
+            .await;
+        assert_eq!(html, r#"<input type="hidden" name="_csrf" value="token">"#);
+    }
+
+    #[tokio::test]
+    async fn test_verify_form_field() {
+        let form = |body: &'static str| {
+            Request::builder()
+                .method(Method::POST)
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let req = verify(form("email=a%40b.c&_csrf=token"), "token").await.unwrap();
+        // 핸들러의 Form extractor 가 다시 읽을 수 있어야 한다.
+        let body = axum::body::to_bytes(req.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"email=a%40b.c&_csrf=token");
+
+        assert!(verify(form("email=a%40b.c&_csrf=other"), "token").await.is_err());
+        assert!(verify(form("email=a%40b.c"), "token").await.is_err());
+    }
+}
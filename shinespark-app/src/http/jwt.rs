@@ -1,15 +1,26 @@
 use std::sync::Arc;
 
 use axum::extract::{FromRef, FromRequestParts};
+use axum::http::HeaderMap;
 use axum::http::request::Parts;
 use shinespark_identity::infra::JwtClaims;
 
 use crate::AppContainer;
 use crate::http::ApiError;
 
-pub struct JwtUser(pub JwtClaims);
+/// `Authorization: Bearer <token>` 의 token
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
 
-impl<S> FromRequestParts<S> for JwtUser
+/// OAuth client 에 발급된 토큰(client_credentials, authorization_code)까지 받는 access token 사용자.
+/// 권한은 승인된 scope 가 반영되는 `JwtClaims::has_perm` 으로만 확인해야 한다.
+pub struct ScopedJwtUser(pub JwtClaims);
+
+impl<S> FromRequestParts<S> for ScopedJwtUser
 where
     S: Send + Sync,
     Arc<AppContainer>: FromRef<S>,
@@ -19,21 +30,155 @@ where
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let container = Arc::<AppContainer>::from_ref(state);
 
-        let auth_header = parts
-            .headers
-            .get(axum::http::header::AUTHORIZATION)
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| ApiError::from(shinespark::Error::UnAuthorized))?;
-
-        let token = auth_header
-            .strip_prefix("Bearer ")
+        let token = bearer_token(&parts.headers)
             .ok_or_else(|| ApiError::from(shinespark::Error::UnAuthorized))?;
 
+        // 서명, token_type 과 함께 폐기(/oauth2/revoke, 대리 로그인 종료)된 토큰인지 확인한다.
         let claims = container
-            .jwt_service
-            .verify(token)
+            .jwt_ident_usecase
+            .authenticate(&mut container.db.handle(), token)
+            .await
             .map_err(|_| ApiError::from(shinespark::Error::UnAuthorized))?;
 
+        Ok(ScopedJwtUser(claims))
+    }
+}
+
+/// 1st party 로그인으로 발급된 access token 사용자.
+/// OAuth client 에 위임된 토큰은 사용자의 role 을 모두 갖지 않으므로 거부한다.
+pub struct JwtUser(pub JwtClaims);
+
+impl<S> FromRequestParts<S> for JwtUser
+where
+    S: Send + Sync,
+    Arc<AppContainer>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ScopedJwtUser(claims) = ScopedJwtUser::from_request_parts(parts, state).await?;
+        if claims.client_id.is_some() {
+            return Err(ApiError::from(shinespark::Error::UnAuthorized));
+        }
         Ok(JwtUser(claims))
     }
 }
+
+/// 대리 로그인(impersonation) 토큰을 거부하는 `JwtUser`.
+/// 비밀번호 변경, 2 단계 인증 설정, 키 발급처럼 본인만 해야 하는 작업에 사용한다.
+pub struct SelfJwtUser(pub JwtClaims);
+
+impl<S> FromRequestParts<S> for SelfJwtUser
+where
+    S: Send + Sync,
+    Arc<AppContainer>: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let JwtUser(claims) = JwtUser::from_request_parts(parts, state).await?;
+        if claims.is_impersonated() {
+            return Err(ApiError::from(shinespark::Error::IllegalState(
+                "not allowed while impersonating".into(),
+            )));
+        }
+        Ok(SelfJwtUser(claims))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode, header};
+    use shinespark_identity::entities::{User, UserAggregate, UserStatus};
+    use shinespark_identity::usecases::{CreateUserCommand, InitialCredentials};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn me(token: &str) -> Request<Body> {
+        Request::builder()
+            .uri("/identity/jwt/me")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_revoked_access_token_is_rejected() {
+        let container = AppContainer::for_test().await;
+        let app = crate::http::routes::identity::routes().with_state(container.clone());
+        let aggregate = UserAggregate {
+            user: User::new(
+                "test".into(),
+                "revoked@example.com".into(),
+                UserStatus::Active,
+            ),
+            role_ids: vec![],
+            identities: vec![],
+        };
+        let pair = container.jwt_service.create(&aggregate).unwrap();
+
+        let res = app.clone().oneshot(me(&pair.access_token)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // 폐기한 access token 은 만료 전이어도 거부된다.
+        container
+            .jwt_ident_usecase
+            .revoke(&mut container.db.handle(), &pair.access_token, "shs_client")
+            .await
+            .unwrap();
+        let res = app.oneshot(me(&pair.access_token)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn create_api_key(token: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/identity/api-keys")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"name":"ci","permissions":[]}"#))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_delegated_token_cannot_create_api_key() {
+        let container = AppContainer::for_test().await;
+        let app = crate::http::routes::identity::routes().with_state(container.clone());
+        let created = container
+            .user_usecase
+            .create_user(
+                &mut container.db.handle(),
+                CreateUserCommand {
+                    name: "delegated".into(),
+                    email: format!("{}@example.com", uuid::Uuid::new_v4()),
+                    credentials: InitialCredentials::Local {
+                        password: "pw".into(),
+                    },
+                    status: UserStatus::Active,
+                },
+            )
+            .await
+            .unwrap();
+        let aggregate = UserAggregate {
+            user: created.user,
+            role_ids: vec![],
+            identities: vec![],
+        };
+
+        // 외부 앱에 위임된 토큰은 1st party 전용 API 를 호출할 수 없다.
+        let delegated = container
+            .jwt_service
+            .create_delegated_token(&aggregate, "shs_client", &["openid".to_string()], 300)
+            .unwrap();
+        let res = app.clone().oneshot(create_api_key(&delegated)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let pair = container.jwt_service.create(&aggregate).unwrap();
+        let res = app.oneshot(create_api_key(&pair.access_token)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
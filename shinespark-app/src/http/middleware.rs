@@ -9,6 +9,9 @@ use axum::{
 use sqlx::types::chrono::Utc;
 use tracing::{Instrument, info_span};
 
+mod rate_limit;
+pub use rate_limit::*;
+
 const TRACE_ID_HEADER: HeaderName = HeaderName::from_static("x-trace-id");
 const SPAN_NAME: &str = "http.request";
 
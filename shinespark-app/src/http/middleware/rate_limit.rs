@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use shinespark::config::{RateLimitConfig, RateLimitGroupConfig, RateLimitKey, RateLimitStoreKind};
+use shinespark::db::{Database, SqlStatement};
+use shinespark_identity::infra::JwtService;
+
+use crate::http::ApiError;
+
+const FORWARDED_FOR: &str = "x-forwarded-for";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited { retry_after_secs: u64 },
+}
+
+/// token bucket 한 개의 한도
+#[derive(Debug, Clone, Copy)]
+pub struct BucketRule {
+    pub capacity: f64,
+    /// 초당 채워지는 token 수
+    pub refill_per_sec: f64,
+}
+
+impl BucketRule {
+    fn from_group(group: &RateLimitGroupConfig) -> Self {
+        Self {
+            capacity: group.capacity as f64,
+            refill_per_sec: group.refill_per_minute as f64 / 60.0,
+        }
+    }
+
+    /// token 이 1 개 찰 때까지 기다려야 하는 시간
+    fn retry_after(&self, tokens: f64) -> RateLimitDecision {
+        let secs = match self.refill_per_sec > 0.0 {
+            true => ((1.0 - tokens) / self.refill_per_sec).ceil() as u64,
+            false => u64::MAX,
+        };
+        RateLimitDecision::Limited {
+            retry_after_secs: secs.max(1),
+        }
+    }
+
+    /// 빈 bucket 이 가득 찰 때까지 걸리는 시간. 이보다 오래 쓰지 않은 bucket 은 지워도 된다.
+    fn full_refill(&self) -> Duration {
+        match self.refill_per_sec > 0.0 {
+            true => Duration::from_secs_f64(self.capacity / self.refill_per_sec),
+            false => Duration::from_secs(86400),
+        }
+    }
+}
+
+// ==========================================
+// Store
+// ==========================================
+#[async_trait::async_trait]
+pub trait RateLimitStore: Send + Sync + 'static {
+    /// `key` 의 bucket 에서 token 하나를 쓴다.
+    async fn acquire(&self, key: &str, rule: BucketRule) -> shinespark::Result<RateLimitDecision>;
+
+    /// `idle` 보다 오래 쓰지 않은 bucket 을 지운다.
+    async fn cleanup(&self, idle: Duration) -> shinespark::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn full(rule: BucketRule, now: Instant) -> Self {
+        Self {
+            tokens: rule.capacity,
+            updated_at: now,
+        }
+    }
+
+    fn take(&mut self, rule: BucketRule, now: Instant) -> RateLimitDecision {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rule.refill_per_sec).min(rule.capacity);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            RateLimitDecision::Allowed
+        } else {
+            rule.retry_after(self.tokens)
+        }
+    }
+}
+
+/// 프로세스 메모리에 bucket 을 둔다. 여러 인스턴스로 띄우면 인스턴스마다 따로 센다.
+#[derive(Default)]
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn acquire(&self, key: &str, rule: BucketRule) -> shinespark::Result<RateLimitDecision> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key.to_string()).or_insert_with(|| Bucket::full(rule, now));
+        Ok(bucket.take(rule, now))
+    }
+
+    async fn cleanup(&self, idle: Duration) -> shinespark::Result<()> {
+        let now = Instant::now();
+        self.buckets
+            .lock()
+            .unwrap()
+            .retain(|_, bucket| now.saturating_duration_since(bucket.updated_at) < idle);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum RateLimitQuery {
+    Acquire,
+    #[cfg(feature = "db-driver-mysql")]
+    FindBucket,
+    Cleanup,
+}
+
+impl SqlStatement for RateLimitQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitQuery::Acquire => shinespark::include_sql!("rate_limit/acquire.sql"),
+            #[cfg(feature = "db-driver-mysql")]
+            RateLimitQuery::FindBucket => shinespark::include_sql!("rate_limit/find_bucket.sql"),
+            RateLimitQuery::Cleanup => shinespark::include_sql!("rate_limit/cleanup.sql"),
+        }
+    }
+}
+
+/// `shs_http_rate_limit` 테이블을 여러 인스턴스가 공유한다. 한 번의 upsert 로 채우고 쓴다.
+/// mysql 은 `RETURNING` 이 없어 upsert 한 행을 같은 트랜잭션에서 다시 읽는다.
+pub struct SqlxRateLimitStore {
+    db: Database,
+}
+
+impl SqlxRateLimitStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct BucketRow {
+    tokens: f64,
+    allowed: bool,
+}
+
+impl SqlxRateLimitStore {
+    #[cfg(not(feature = "db-driver-mysql"))]
+    async fn take_token(&self, key: &str, rule: BucketRule) -> shinespark::Result<BucketRow> {
+        RateLimitQuery::Acquire
+            .as_query_as::<BucketRow>()
+            .bind(key)
+            .bind(rule.capacity)
+            .bind(rule.refill_per_sec)
+            .fetch_one(&self.db.inner)
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    #[cfg(feature = "db-driver-mysql")]
+    async fn take_token(&self, key: &str, rule: BucketRule) -> shinespark::Result<BucketRow> {
+        let mut handle = self.db.handle();
+        let mut tx = handle.begin().await?;
+        RateLimitQuery::Acquire
+            .as_query()
+            .bind(key)
+            .bind(rule.capacity)
+            .bind(rule.capacity)
+            .bind(rule.refill_per_sec)
+            .bind(rule.capacity)
+            .bind(rule.refill_per_sec)
+            .execute(tx.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        let row = RateLimitQuery::FindBucket
+            .as_query_as::<BucketRow>()
+            .bind(key)
+            .fetch_one(tx.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        tx.commit().await?;
+        Ok(row)
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimitStore for SqlxRateLimitStore {
+    async fn acquire(&self, key: &str, rule: BucketRule) -> shinespark::Result<RateLimitDecision> {
+        let row = self.take_token(key, rule).await?;
+        Ok(match row.allowed {
+            true => RateLimitDecision::Allowed,
+            false => rule.retry_after(row.tokens),
+        })
+    }
+
+    async fn cleanup(&self, idle: Duration) -> shinespark::Result<()> {
+        RateLimitQuery::Cleanup
+            .as_query()
+            .bind(idle.as_secs_f64())
+            .execute(&self.db.inner)
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+}
+
+// ==========================================
+// Limiter
+// ==========================================
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+    jwt_service: Arc<dyn JwtService>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        config: &RateLimitConfig,
+        store: Arc<dyn RateLimitStore>,
+        jwt_service: Arc<dyn JwtService>,
+    ) -> Self {
+        Self {
+            config: config.clone(),
+            store,
+            jwt_service,
+        }
+    }
+
+    /// 설정의 `store` 에 맞는 저장소로 만든다.
+    pub fn from_config(
+        config: &RateLimitConfig,
+        db: Database,
+        jwt_service: Arc<dyn JwtService>,
+    ) -> Self {
+        let store: Arc<dyn RateLimitStore> = match config.store {
+            RateLimitStoreKind::Memory => Arc::new(MemoryRateLimitStore::default()),
+            RateLimitStoreKind::Database => Arc::new(SqlxRateLimitStore::new(db)),
+        };
+        Self::new(config, store, jwt_service)
+    }
+
+    fn group(&self, path: &str) -> Option<&RateLimitGroupConfig> {
+        self.config
+            .groups
+            .iter()
+            .find(|group| group.paths.iter().any(|prefix| path.starts_with(prefix.as_str())))
+    }
+
+    /// 신뢰하는 proxy 를 거쳐 온 요청이면 `X-Forwarded-For` 를 오른쪽부터 읽어
+    /// 처음 나오는 신뢰하지 않는 주소를 클라이언트로 본다.
+    fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        let peer = req.extensions().get::<ConnectInfo<SocketAddr>>()?.0.ip();
+        if !self.config.trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
+        let forwarded = req
+            .headers()
+            .get_all(FORWARDED_FOR)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .collect::<Vec<_>>();
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !self.config.trusted_proxies.contains(ip))
+            .or(forwarded.first())
+            .copied()
+            .or(Some(peer))
+    }
+
+    /// bearer token 이나 `access_token` 쿠키의 subject
+    fn subject(&self, req: &Request) -> Option<String> {
+        let bearer = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::to_string);
+        let token = bearer.or_else(|| {
+            CookieJar::from_headers(req.headers()).get("access_token").map(|c| c.value().to_owned())
+        })?;
+        self.jwt_service.verify(&token).ok().map(|claims| claims.sub)
+    }
+
+    fn bucket_key(&self, group: &RateLimitGroupConfig, req: &Request) -> String {
+        let subject = match group.key {
+            RateLimitKey::Subject => self.subject(req).map(|sub| format!("sub:{}", sub)),
+            RateLimitKey::Ip => None,
+        };
+        let client = subject.unwrap_or_else(|| match self.client_ip(req) {
+            Some(ip) => format!("ip:{}", ip),
+            None => "ip:unknown".to_string(),
+        });
+        format!("{}:{}", group.name, client)
+    }
+
+    /// 요청이 속한 bucket. 어느 그룹에도 속하지 않으면 `None`
+    fn bucket(&self, req: &Request) -> Option<(String, BucketRule)> {
+        let group = self.group(req.uri().path())?;
+        Some((self.bucket_key(group, req), BucketRule::from_group(group)))
+    }
+
+    async fn acquire(&self, key: &str, rule: BucketRule) -> RateLimitDecision {
+        match self.store.acquire(key, rule).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                // 저장소 장애로 로그인까지 막히지 않도록 통과시킨다.
+                tracing::warn!("rate limit store failed: {}", e);
+                RateLimitDecision::Allowed
+            }
+        }
+    }
+
+    /// 오래 쓰지 않은 bucket 을 `period` 마다 지우는 백그라운드 작업을 시작한다.
+    pub fn spawn_cleanup(self: &Arc<Self>, period: Duration) -> tokio::task::JoinHandle<()> {
+        let limiter = self.clone();
+        let idle = limiter
+            .config
+            .groups
+            .iter()
+            .map(|group| BucketRule::from_group(group).full_refill())
+            .max()
+            .unwrap_or_default();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = limiter.store.cleanup(idle).await {
+                    tracing::warn!("rate limit cleanup failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// 설정의 route group 별 token bucket 한도를 넘으면 `429 Too Many Requests` 와 `Retry-After` 를 돌려준다.
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !limiter.config.enabled {
+        return next.run(req).await;
+    }
+    let Some((key, rule)) = limiter.bucket(&req) else {
+        return next.run(req).await;
+    };
+    match limiter.acquire(&key, rule).await {
+        RateLimitDecision::Allowed => next.run(req).await,
+        RateLimitDecision::Limited { retry_after_secs } => {
+            let mut response = ApiError {
+                status_code: StatusCode::TOO_MANY_REQUESTS,
+                code: "TOO_MANY_REQUESTS",
+                message: format!(
+                    "too many requests, retry after {} seconds",
+                    retry_after_secs
+                ),
+            }
+            .into_response();
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, routing::post};
+    use shinespark::config::JwtConfig;
+    use shinespark::db::check_statements;
+    use shinespark_identity::infra::HS256JwtService;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn config(trusted_proxies: Vec<IpAddr>) -> RateLimitConfig {
+        RateLimitConfig {
+            enabled: true,
+            store: RateLimitStoreKind::Memory,
+            trusted_proxies,
+            groups: vec![RateLimitGroupConfig {
+                name: "login".to_string(),
+                paths: vec!["/auth/login".to_string()],
+                key: RateLimitKey::Ip,
+                capacity: 2,
+                refill_per_minute: 6,
+            }],
+        }
+    }
+
+    fn limiter(config: RateLimitConfig) -> Arc<RateLimiter> {
+        let jwt_service = Arc::new(HS256JwtService::new(&JwtConfig {
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        }));
+        Arc::new(RateLimiter::new(
+            &config,
+            Arc::new(MemoryRateLimitStore::default()),
+            jwt_service,
+        ))
+    }
+
+    fn request(path: &str, peer: &str, forwarded_for: Option<&str>) -> Request {
+        let mut req = Request::builder().method("POST").uri(path).body(Body::empty()).unwrap();
+        if let Some(forwarded_for) = forwarded_for {
+            req.headers_mut().insert(FORWARDED_FOR, forwarded_for.parse().unwrap());
+        }
+        req.extensions_mut().insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 1234)));
+        req
+    }
+
+    #[test]
+    fn test_bucket_refill() {
+        let rule = BucketRule {
+            capacity: 2.0,
+            refill_per_sec: 0.1,
+        };
+        let now = Instant::now();
+        let mut bucket = Bucket::full(rule, now);
+        assert_eq!(bucket.take(rule, now), RateLimitDecision::Allowed);
+        assert_eq!(bucket.take(rule, now), RateLimitDecision::Allowed);
+        assert_eq!(
+            bucket.take(rule, now),
+            RateLimitDecision::Limited {
+                retry_after_secs: 10
+            }
+        );
+        // 10 초 뒤 token 하나가 다시 찬다.
+        assert_eq!(
+            bucket.take(rule, now + Duration::from_secs(10)),
+            RateLimitDecision::Allowed
+        );
+    }
+
+    #[test]
+    fn test_client_ip_behind_trusted_proxy() {
+        let limiter = limiter(config(vec!["10.0.0.1".parse().unwrap()]));
+        let ip = |req: Request| limiter.client_ip(&req).unwrap().to_string();
+
+        assert_eq!(
+            ip(request("/", "203.0.113.9", Some("198.51.100.1"))),
+            "203.0.113.9"
+        );
+        assert_eq!(
+            ip(request("/", "10.0.0.1", Some("198.51.100.1"))),
+            "198.51.100.1"
+        );
+        // 클라이언트가 보낸 값은 앞에 붙으므로 오른쪽의 신뢰하지 않는 주소를 쓴다.
+        assert_eq!(
+            ip(request(
+                "/",
+                "10.0.0.1",
+                Some("1.1.1.1, 198.51.100.1, 10.0.0.1")
+            )),
+            "198.51.100.1"
+        );
+        assert_eq!(ip(request("/", "10.0.0.1", None)), "10.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware() {
+        let limiter = limiter(config(vec![]));
+        let app = axum::Router::new()
+            .route("/auth/login", post(|| async { "ok" }))
+            .route("/other", post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                limiter,
+                rate_limit_middleware,
+            ));
+
+        for _ in 0..2 {
+            let res =
+                app.clone().oneshot(request("/auth/login", "203.0.113.9", None)).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        let res = app.clone().oneshot(request("/auth/login", "203.0.113.9", None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "10");
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "TOO_MANY_REQUESTS");
+
+        // 다른 IP 와 그룹에 속하지 않는 경로는 영향이 없다.
+        let res = app.clone().oneshot(request("/auth/login", "203.0.113.10", None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app.oneshot(request("/other", "203.0.113.9", None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                RateLimitQuery::Acquire,
+                #[cfg(feature = "db-driver-mysql")]
+                RateLimitQuery::FindBucket,
+                RateLimitQuery::Cleanup,
+            ],
+            |statement, check| match statement {
+                #[cfg(not(feature = "db-driver-mysql"))]
+                RateLimitQuery::Acquire => check.params(3).returns::<BucketRow>(),
+                #[cfg(feature = "db-driver-mysql")]
+                RateLimitQuery::Acquire => check.params(6),
+                #[cfg(feature = "db-driver-mysql")]
+                RateLimitQuery::FindBucket => check.params(1).returns::<BucketRow>(),
+                RateLimitQuery::Cleanup => check.params(1),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
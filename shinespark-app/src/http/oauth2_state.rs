@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use shinespark_identity::usecases::PendingSocialLink;
+use sqlx::types::chrono::Utc;
+
+use crate::http::session::Session;
+
+pub const OAUTH2_STATE_SESSION_KEY: &str = "oauth2_state";
+pub const OAUTH2_LINK_SESSION_KEY: &str = "oauth2_pending_link";
+
+/// 발급한 state 의 유효 시간 (provider 로그인 화면에 머무를 수 있는 시간)
+const OAUTH2_STATE_TTL_SECS: i64 = 600;
+/// 계정 연결 비밀번호 확인을 기다리는 시간
+const OAUTH2_LINK_TTL_SECS: i64 = 600;
+/// 계정 연결 비밀번호 확인 허용 횟수. 넘으면 social login 부터 다시 해야 한다.
+const OAUTH2_LINK_MAX_ATTEMPTS: u32 = 5;
+
+/// social login 시작 시 세션에 보관하고 callback 에서 꺼내 검증하는 값.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuth2PendingState {
+    pub provider: String,
+    pub state: String,
+    pub code_verifier: String,
+    pub return_to: Option<String>,
+    pub expires_at: i64,
+}
+
+impl OAuth2PendingState {
+    pub fn new(provider: &str, return_to: Option<String>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            state: uuid::Uuid::new_v4().to_string(),
+            code_verifier: shinespark::crypto::pkce::generate_code_verifier(),
+            return_to: sanitize_return_to(return_to),
+            expires_at: Utc::now().timestamp() + OAUTH2_STATE_TTL_SECS,
+        }
+    }
+
+    pub fn code_challenge(&self) -> String {
+        shinespark::crypto::pkce::code_challenge_s256(&self.code_verifier)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now().timestamp()
+    }
+
+    pub async fn save(&self, session: &Session) -> shinespark::Result<()> {
+        session.insert(OAUTH2_STATE_SESSION_KEY, self).await.map_err(|e| {
+            shinespark::Error::Internal(anyhow::anyhow!(e).context("save oauth2 state failed"))
+        })
+    }
+
+    /// 세션에서 state 를 꺼낸다(1회용). 없거나 만료되었거나 provider 가 다르면 `UnAuthorized`.
+    pub async fn take(session: &Session, provider: &str) -> shinespark::Result<Self> {
+        let pending = session
+            .remove::<Self>(OAUTH2_STATE_SESSION_KEY)
+            .await
+            .map_err(|e| {
+                shinespark::Error::Internal(anyhow::anyhow!(e).context("load oauth2 state failed"))
+            })?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        if pending.provider != provider || pending.is_expired() {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        Ok(pending)
+    }
+}
+
+/// social login 후 기존 계정의 비밀번호 확인을 기다리는 연결 정보.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuth2PendingLink {
+    pub link: PendingSocialLink,
+    pub return_to: Option<String>,
+    pub attempts: u32,
+    pub expires_at: i64,
+}
+
+impl OAuth2PendingLink {
+    pub fn new(link: PendingSocialLink, return_to: Option<String>) -> Self {
+        Self {
+            link,
+            return_to,
+            attempts: 0,
+            expires_at: Utc::now().timestamp() + OAUTH2_LINK_TTL_SECS,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now().timestamp()
+    }
+
+    pub async fn save(&self, session: &Session) -> shinespark::Result<()> {
+        session.insert(OAUTH2_LINK_SESSION_KEY, self).await.map_err(|e| {
+            shinespark::Error::Internal(anyhow::anyhow!(e).context("save oauth2 link failed"))
+        })
+    }
+
+    /// 세션의 연결 정보를 읽는다. 없거나 만료되었으면 `UnAuthorized`.
+    pub async fn load(session: &Session) -> shinespark::Result<Self> {
+        let pending = session
+            .get::<Self>(OAUTH2_LINK_SESSION_KEY)
+            .await
+            .map_err(|e| {
+                shinespark::Error::Internal(anyhow::anyhow!(e).context("load oauth2 link failed"))
+            })?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        if pending.is_expired() {
+            Self::clear(session).await?;
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        Ok(pending)
+    }
+
+    pub async fn clear(session: &Session) -> shinespark::Result<()> {
+        session.remove_value(OAUTH2_LINK_SESSION_KEY).await.map_err(|e| {
+            shinespark::Error::Internal(anyhow::anyhow!(e).context("clear oauth2 link failed"))
+        })?;
+        Ok(())
+    }
+
+    /// 비밀번호 확인 실패를 기록한다. 허용 횟수를 넘으면 연결 정보를 지우고 `false`.
+    pub async fn record_failure(mut self, session: &Session) -> shinespark::Result<bool> {
+        self.attempts += 1;
+        if self.attempts >= OAUTH2_LINK_MAX_ATTEMPTS {
+            Self::clear(session).await?;
+            return Ok(false);
+        }
+        self.save(session).await?;
+        Ok(true)
+    }
+}
+
+/// open redirect 방지 — 같은 origin 의 절대 경로(`/...`)만 허용한다.
+pub fn sanitize_return_to(return_to: Option<String>) -> Option<String> {
+    return_to
+        .filter(|path| path.starts_with('/') && !path.starts_with("//") && !path.contains('\\'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_return_to() {
+        assert_eq!(
+            sanitize_return_to(Some("/admin/users?page=2".into())),
+            Some("/admin/users?page=2".into())
+        );
+        assert_eq!(
+            sanitize_return_to(Some("https://evil.example".into())),
+            None
+        );
+        assert_eq!(sanitize_return_to(Some("//evil.example".into())), None);
+        assert_eq!(sanitize_return_to(Some("/\\evil.example".into())), None);
+        assert_eq!(sanitize_return_to(None), None);
+    }
+
+    #[test]
+    fn test_pending_state_expiry() {
+        let mut pending = OAuth2PendingState::new("google", None);
+        assert!(!pending.is_expired());
+        assert_eq!(pending.code_challenge().len(), 43);
+
+        pending.expires_at = Utc::now().timestamp() - 1;
+        assert!(pending.is_expired());
+    }
+}
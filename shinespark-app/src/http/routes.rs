@@ -1,11 +1,106 @@
 pub mod identity {
     pub mod dto {
+        use shinespark_identity::entities::{
+            AuthProvider, User, UserAggregate, UserIdentity, UserStatus,
+        };
+        use sqlx::types::chrono::{DateTime, Utc};
 
         #[derive(Debug, serde::Deserialize)]
         pub struct LoginRequest {
             pub email: String,
             pub password: String,
         }
+
+        /// 로그인 응답이 `mfa_required` 일 때 challenge_token 과 OTP(또는 복구 코드)를 보낸다.
+        /// passkey 로 인증하려면 `/identity/webauthn/mfa/*` 를 사용한다.
+        #[derive(Debug, serde::Deserialize)]
+        pub struct MfaLoginRequest {
+            pub challenge_token: String,
+            pub code: String,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct MfaCodeRequest {
+            pub code: String,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct MfaChallengeRequest {
+            pub challenge_token: String,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct PasskeyMfaRequest {
+            pub challenge_token: String,
+            pub credential: shinespark_identity::usecases::AssertionCredential,
+        }
+
+        /// 응답에 쓰는 사용자 정보. 내부 PK(`id`)는 내보내지 않는다.
+        #[derive(Debug, serde::Serialize)]
+        pub struct UserResponse {
+            pub uid: uuid::Uuid,
+            pub name: String,
+            pub email: String,
+            pub status: UserStatus,
+            pub created_at: DateTime<Utc>,
+            pub updated_at: DateTime<Utc>,
+        }
+
+        impl From<User> for UserResponse {
+            fn from(user: User) -> Self {
+                Self {
+                    uid: user.uid,
+                    name: user.name,
+                    email: user.email,
+                    status: user.status,
+                    created_at: user.created_at,
+                    updated_at: user.updated_at,
+                }
+            }
+        }
+
+        /// 응답에 쓰는 인증 수단 정보. 비밀번호 해시, passkey 공개키와 서명 카운터,
+        /// 소셜 provider 의 계정 id 는 내보내지 않는다.
+        #[derive(Debug, serde::Serialize)]
+        pub struct IdentityResponse {
+            pub provider: AuthProvider,
+            /// passkey 의 credential id. 브라우저의 `allowCredentials` 와 같은 값이다.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub credential_id: Option<String>,
+            pub created_at: DateTime<Utc>,
+            pub updated_at: DateTime<Utc>,
+        }
+
+        impl From<UserIdentity> for IdentityResponse {
+            fn from(identity: UserIdentity) -> Self {
+                let credential_id =
+                    (identity.provider == AuthProvider::Passkey).then_some(identity.provider_uid);
+                Self {
+                    provider: identity.provider,
+                    credential_id,
+                    created_at: identity.created_at,
+                    updated_at: identity.updated_at,
+                }
+            }
+        }
+
+        #[derive(Debug, serde::Serialize)]
+        pub struct UserAggregateResponse {
+            #[serde(flatten)]
+            pub user: UserResponse,
+            pub roles: Vec<i64>,
+            pub identities: Vec<IdentityResponse>,
+        }
+
+        impl From<UserAggregate> for UserAggregateResponse {
+            fn from(aggregate: UserAggregate) -> Self {
+                Self {
+                    user: aggregate.user.into(),
+                    roles: aggregate.role_ids,
+                    identities: aggregate.identities.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
     }
 
     use std::sync::Arc;
@@ -18,24 +113,37 @@ pub mod identity {
         use std::sync::Arc;
 
         use axum::{Json, Router, extract::State};
-        use shinespark_identity::{entities::UserAggregate, usecases::LoginCommand};
+        use serde::Serialize;
+        use shinespark_identity::{
+            entities::UserAggregate,
+            usecases::{LoginCommand, LoginOutcome, MfaChallenge, MfaFactor, MfaLoginCommand},
+        };
 
+        use super::dto::UserAggregateResponse;
         use crate::{
             AppContainer,
             http::{
-                ApiResponse, ApiResult,
+                ApiResponse, ApiResult, csrf,
                 session::{CurrentUser, Session, USER_SESSION_KEY},
             },
         };
 
+        #[derive(Debug, Serialize)]
+        #[serde(tag = "status", rename_all = "snake_case")]
+        pub enum SessionLoginResponse {
+            Authenticated(UserAggregateResponse),
+            /// `/identity/session/mfa` 로 코드를 보내야 세션이 만들어진다.
+            MfaRequired(MfaChallenge),
+        }
+
         async fn login(
             State(container): State<Arc<AppContainer>>,
             session: Session,
             Json(command): Json<super::dto::LoginRequest>,
-        ) -> ApiResult<UserAggregate> {
-            let user_aggregate = container
+        ) -> ApiResult<SessionLoginResponse> {
+            let outcome = container
                 .login_usecase
-                .login(
+                .authenticate(
                     &mut container.db.handle(),
                     LoginCommand::Local {
                         email: command.email,
@@ -43,30 +151,88 @@ pub mod identity {
                     },
                 )
                 .await?;
-            session
-                .insert(USER_SESSION_KEY, user_aggregate.clone())
-                .await
-                .map_err(|e| shinespark::Error::Internal(anyhow::anyhow!(e).context("context")))?;
-            Ok(ApiResponse::new(user_aggregate))
+            let user_aggregate = match outcome {
+                LoginOutcome::Authenticated(user) => user,
+                LoginOutcome::MfaRequired(challenge) => {
+                    return Ok(ApiResponse::new(SessionLoginResponse::MfaRequired(
+                        challenge,
+                    )));
+                }
+            };
+            establish(&session, &user_aggregate).await?;
+            Ok(ApiResponse::new(SessionLoginResponse::Authenticated(
+                user_aggregate.into(),
+            )))
+        }
+
+        /// 2 단계 인증 완료
+        async fn mfa(
+            State(container): State<Arc<AppContainer>>,
+            session: Session,
+            Json(body): Json<super::dto::MfaLoginRequest>,
+        ) -> ApiResult<UserAggregateResponse> {
+            let user_aggregate = container
+                .login_usecase
+                .complete_mfa(
+                    &mut container.db.handle(),
+                    MfaLoginCommand {
+                        challenge_token: body.challenge_token,
+                        factor: MfaFactor::Code(body.code),
+                    },
+                )
+                .await?;
+            establish(&session, &user_aggregate).await?;
+            Ok(ApiResponse::new(user_aggregate.into()))
         }
 
-        async fn logout(session: Session, _user: CurrentUser) -> ApiResult<()> {
+        /// 세션 고정 공격을 막기 위해 id 를 새로 발급하고 uid 만 저장한다.
+        async fn establish(session: &Session, user: &UserAggregate) -> shinespark::Result<()> {
+            let map_err = |e: tower_sessions::session::Error| {
+                shinespark::Error::Internal(anyhow::anyhow!(e).context("session failed"))
+            };
+            session.cycle_id().await.map_err(map_err)?;
+            session.insert(USER_SESSION_KEY, user.user.uid).await.map_err(map_err)
+        }
+
+        async fn logout(
+            State(container): State<Arc<AppContainer>>,
+            session: Session,
+            user: CurrentUser,
+        ) -> ApiResult<()> {
+            container.session_user_cache.invalidate(&user.user.uid);
             session
-                .remove_value(USER_SESSION_KEY)
+                .flush()
                 .await
                 .map_err(|e| shinespark::Error::Internal(anyhow::anyhow!(e).context("context")))?;
             Ok(ApiResponse::new(()))
         }
 
-        async fn me(user: CurrentUser) -> ApiResult<UserAggregate> {
-            Ok(ApiResponse::new(user.0))
+        async fn me(user: CurrentUser) -> ApiResult<UserAggregateResponse> {
+            Ok(ApiResponse::new(user.0.into()))
+        }
+
+        #[derive(Debug, Serialize)]
+        pub struct CsrfTokenResponse {
+            pub token: String,
+        }
+
+        /// CSRF 토큰
+        ///
+        /// 세션 쿠키로 인증하는 변경 요청은 이 값을 `X-CSRF-Token` 헤더로 보내야 합니다.
+        async fn csrf() -> ApiResult<CsrfTokenResponse> {
+            let token = csrf::current_token().ok_or(shinespark::Error::IllegalState(
+                "csrf middleware is not installed".into(),
+            ))?;
+            Ok(ApiResponse::new(CsrfTokenResponse { token }))
         }
 
         pub fn routes() -> Router<Arc<AppContainer>> {
             Router::new()
                 .route("/identity/session/login", axum::routing::post(login))
+                .route("/identity/session/mfa", axum::routing::post(mfa))
                 .route("/identity/session/logout", axum::routing::post(logout))
                 .route("/identity/session/me", axum::routing::get(me))
+                .route("/identity/session/csrf", axum::routing::get(csrf))
         }
     }
 
@@ -75,12 +241,15 @@ pub mod identity {
 
         use axum::{Json, Router, extract::State};
         use serde::{Deserialize, Serialize};
-        use shinespark_identity::{infra::JwtClaims, usecases::LoginCommand};
+        use shinespark_identity::{
+            infra::{JwtClaims, JwtTokenPair},
+            usecases::{JwtLoginOutcome, LoginCommand, MfaChallenge, MfaFactor, MfaLoginCommand},
+        };
         use tracing::info;
 
         use crate::{
             AppContainer,
-            http::{ApiResponse, ApiResult, jwt::JwtUser},
+            http::{ApiResponse, ApiResult, api_key::AuthUser, jwt::SelfJwtUser},
         };
 
         #[derive(Debug, Serialize)]
@@ -89,6 +258,23 @@ pub mod identity {
             pub refresh_token: String,
         }
 
+        impl From<JwtTokenPair> for JwtTokenResponse {
+            fn from(pair: JwtTokenPair) -> Self {
+                Self {
+                    access_token: pair.access_token,
+                    refresh_token: pair.refresh_token,
+                }
+            }
+        }
+
+        #[derive(Debug, Serialize)]
+        #[serde(tag = "status", rename_all = "snake_case")]
+        pub enum JwtLoginResponse {
+            Authenticated(JwtTokenResponse),
+            /// `/identity/jwt/mfa` 로 코드를 보내야 토큰이 발급된다.
+            MfaRequired(MfaChallenge),
+        }
+
         #[derive(Debug, Deserialize)]
         pub struct JwtRefreshRequest {
             pub refresh_token: String,
@@ -97,24 +283,43 @@ pub mod identity {
         /// 로그인 처리
         ///
         /// 이메일과 비밀번호를 사용하여 로그인하고, access_token과 refresh_token을 발급합니다.
+        /// 2단계 인증을 사용하는 계정이면 토큰 대신 challenge_token 을 반환합니다.
         async fn login(
             State(container): State<Arc<AppContainer>>,
             Json(command): Json<super::dto::LoginRequest>,
+        ) -> ApiResult<JwtLoginResponse> {
+            let command = LoginCommand::Local {
+                email: command.email,
+                password: command.password,
+            };
+            let outcome = container
+                .db
+                .transaction(|h| Box::pin(container.jwt_ident_usecase.login(h, command.clone())))
+                .await?;
+            Ok(ApiResponse::new(match outcome {
+                JwtLoginOutcome::Issued(pair) => JwtLoginResponse::Authenticated(pair.into()),
+                JwtLoginOutcome::MfaRequired(challenge) => JwtLoginResponse::MfaRequired(challenge),
+            }))
+        }
+
+        /// 2단계 인증 완료
+        ///
+        /// 로그인에서 받은 challenge_token 과 OTP(또는 복구 코드)를 검증하고 토큰을 발급합니다.
+        async fn mfa(
+            State(container): State<Arc<AppContainer>>,
+            Json(body): Json<super::dto::MfaLoginRequest>,
         ) -> ApiResult<JwtTokenResponse> {
+            let command = MfaLoginCommand {
+                challenge_token: body.challenge_token,
+                factor: MfaFactor::Code(body.code),
+            };
             let pair = container
-                .jwt_ident_usecase
-                .login(
-                    &mut container.db.handle(),
-                    LoginCommand::Local {
-                        email: command.email,
-                        password: command.password,
-                    },
-                )
+                .db
+                .transaction(|h| {
+                    Box::pin(container.jwt_ident_usecase.complete_mfa(h, command.clone()))
+                })
                 .await?;
-            Ok(ApiResponse::new(JwtTokenResponse {
-                access_token: pair.access_token,
-                refresh_token: pair.refresh_token,
-            }))
+            Ok(ApiResponse::new(pair.into()))
         }
 
         /// 로그아웃 처리
@@ -124,7 +329,7 @@ pub mod identity {
         /// 즉시 접근을 차단하는 실시간(real-time) 로그아웃이 필요한 경우, 별도의 블랙리스트(blacklist)를 통해 관리해야 합니다.
         async fn logout(
             State(container): State<Arc<AppContainer>>,
-            user: JwtUser,
+            user: SelfJwtUser,
         ) -> ApiResult<()> {
             container.jwt_ident_usecase.logout(&mut container.db.handle(), &user.0.sub).await?;
             Ok(ApiResponse::new(()))
@@ -142,16 +347,13 @@ pub mod identity {
                 .jwt_ident_usecase
                 .refresh(&mut container.db.handle(), &body.refresh_token)
                 .await?;
-            Ok(ApiResponse::new(JwtTokenResponse {
-                access_token: pair.access_token,
-                refresh_token: pair.refresh_token,
-            }))
+            Ok(ApiResponse::new(pair.into()))
         }
 
         /// 현재 토큰 정보 조회
         ///
-        /// access_token에 포함된 정보를 반환합니다.
-        async fn me(user: JwtUser) -> ApiResult<JwtClaims> {
+        /// access_token 또는 API key 에 포함된 정보를 반환합니다.
+        async fn me(user: AuthUser) -> ApiResult<JwtClaims> {
             info!("me: {}", user.0.sub);
             Ok(ApiResponse::new(user.0))
         }
@@ -159,6 +361,7 @@ pub mod identity {
         pub fn routes() -> Router<Arc<AppContainer>> {
             Router::new()
                 .route("/identity/jwt/login", axum::routing::post(login))
+                .route("/identity/jwt/mfa", axum::routing::post(mfa))
                 .route("/identity/jwt/logout", axum::routing::post(logout))
                 .route("/identity/jwt/refresh", axum::routing::post(refresh_token))
                 .route("/identity/jwt/me", axum::routing::get(me))
@@ -169,25 +372,57 @@ pub mod identity {
         use std::sync::Arc;
 
         use axum::{
-            Router,
+            Json, Router,
             extract::{Path, Query, State},
             response::Redirect,
         };
         use serde::{Deserialize, Serialize};
         use shinespark_identity::{
             entities::AuthProvider,
-            usecases::{LoginCommand, SocialCallbackCommand, SocialLoginCommand},
+            usecases::{
+                ConfirmSocialLinkCommand, JwtLoginOutcome, LoginCommand, MfaMethod,
+                SocialCallbackCommand, SocialLoginCommand, SocialLoginOutcome,
+            },
         };
 
         use crate::{
             AppContainer,
-            http::{ApiResponse, ApiResult, api_response::ApiError},
+            http::{
+                ApiResponse, ApiResult,
+                api_response::ApiError,
+                oauth2_state::{OAuth2PendingLink, OAuth2PendingState},
+                session::Session,
+            },
         };
 
         #[derive(Debug, Serialize)]
         pub struct OAuthTokenResponse {
             pub access_token: String,
             pub refresh_token: String,
+            pub return_to: Option<String>,
+        }
+
+        /// `link_required` 이면 `/identity/oauth2/link` 로 기존 계정의 비밀번호를 보내 연결을 마친다.
+        /// `mfa_required` 이면 `/identity/jwt/mfa` 로 코드를 보내 토큰을 받는다.
+        #[derive(Debug, Serialize)]
+        #[serde(tag = "status", rename_all = "snake_case")]
+        pub enum OAuthCallbackResponse {
+            Authenticated(OAuthTokenResponse),
+            LinkRequired {
+                provider: String,
+                email: String,
+            },
+            MfaRequired {
+                challenge_token: String,
+                expires_in: i64,
+                methods: Vec<MfaMethod>,
+                return_to: Option<String>,
+            },
+        }
+
+        #[derive(Debug, Deserialize)]
+        pub struct LoginParams {
+            pub return_to: Option<String>,
         }
 
         #[derive(Debug, Deserialize)]
@@ -196,34 +431,71 @@ pub mod identity {
             pub state: String,
         }
 
+        #[derive(Debug, Deserialize)]
+        pub struct LinkRequest {
+            pub password: String,
+        }
+
+        /// social login 시작
+        ///
+        /// state 와 PKCE code_verifier 를 세션에 보관한 뒤 provider 인증 화면으로 redirect 합니다.
+        /// `return_to` 는 로그인 완료 후 이동할 같은 origin 의 경로입니다.
         async fn login(
             State(container): State<Arc<AppContainer>>,
             Path(provider): Path<String>,
+            Query(params): Query<LoginParams>,
+            session: Session,
         ) -> Result<Redirect, ApiError> {
             let usecase = provider_usecase(&container, &provider)?;
-            let state = uuid::Uuid::new_v4().to_string();
-            let url = usecase.login(SocialLoginCommand { state }).await?;
+            let pending = OAuth2PendingState::new(&provider, params.return_to);
+            pending.save(&session).await?;
+            let url = usecase
+                .login(SocialLoginCommand {
+                    state: pending.state.clone(),
+                    code_challenge: pending.code_challenge(),
+                })
+                .await?;
             Ok(Redirect::temporary(&url))
         }
 
+        /// social login callback
+        ///
+        /// 같은 email 의 기존 계정이 있고 provider 가 email 을 확인해 주지 않았다면
+        /// 토큰 대신 `link_required` 를 반환합니다.
         async fn callback(
             State(container): State<Arc<AppContainer>>,
             Path(provider): Path<String>,
             Query(params): Query<CallbackParams>,
-        ) -> ApiResult<OAuthTokenResponse> {
+            session: Session,
+        ) -> ApiResult<OAuthCallbackResponse> {
             let usecase = provider_usecase(&container, &provider)?;
+            let pending = OAuth2PendingState::take(&session, &provider).await?;
 
-            let user = usecase
-                .callback(
-                    &mut container.db.handle(),
-                    SocialCallbackCommand {
-                        code: params.code,
-                        state: params.state,
-                    },
-                )
-                .await?;
+            let command = SocialCallbackCommand {
+                code: params.code,
+                state: params.state,
+                expected_state: pending.state,
+                code_verifier: pending.code_verifier,
+            };
+            // provider 와의 HTTP 통신은 트랜잭션 밖에서 끝낸다.
+            let profile = usecase.authenticate(command).await?;
+            // 처음 로그인하면 user, identity, role 을 함께 만든다.
+            let outcome =
+                container.db.transaction(|h| Box::pin(usecase.resolve(h, profile.clone()))).await?;
+
+            let user = match outcome {
+                SocialLoginOutcome::LoggedIn(user) => user,
+                SocialLoginOutcome::LinkRequired(link) => {
+                    let response = OAuthCallbackResponse::LinkRequired {
+                        provider: link.provider.as_str().to_string(),
+                        email: link.email.clone(),
+                    };
+                    OAuth2PendingLink::new(link, pending.return_to).save(&session).await?;
+                    return Ok(ApiResponse::new(response));
+                }
+            };
 
-            let auth_provider = parse_provider(&provider)?;
+            let auth_provider = usecase.provider();
             let provider_uid = user
                 .identities
                 .iter()
@@ -231,39 +503,86 @@ pub mod identity {
                 .map(|i| i.provider_uid.clone())
                 .ok_or_else(|| shinespark::Error::IllegalState("identity not found".into()))?;
 
-            let pair = container
-                .jwt_ident_usecase
-                .login(
+            let response =
+                issue_token(&container, auth_provider, provider_uid, pending.return_to).await?;
+            Ok(ApiResponse::new(response))
+        }
+
+        /// 계정 연결 확인
+        ///
+        /// callback 에서 `link_required` 를 받은 뒤 기존 계정의 비밀번호로 social identity 를 연결하고
+        /// 토큰을 발급합니다.
+        async fn link(
+            State(container): State<Arc<AppContainer>>,
+            session: Session,
+            Json(body): Json<LinkRequest>,
+        ) -> ApiResult<OAuthCallbackResponse> {
+            let pending = OAuth2PendingLink::load(&session).await?;
+            let result = container
+                .social_link_usecase
+                .confirm_link(
                     &mut container.db.handle(),
-                    LoginCommand::Social {
-                        provider: auth_provider,
-                        provider_uid,
+                    ConfirmSocialLinkCommand {
+                        link: pending.link.clone(),
+                        password: body.password,
                     },
                 )
-                .await?;
+                .await;
+            if let Err(e) = result {
+                pending.record_failure(&session).await?;
+                return Err(e.into());
+            }
+            OAuth2PendingLink::clear(&session).await?;
 
-            Ok(ApiResponse::new(OAuthTokenResponse {
-                access_token: pair.access_token,
-                refresh_token: pair.refresh_token,
-            }))
+            let response = issue_token(
+                &container,
+                pending.link.provider,
+                pending.link.provider_uid,
+                pending.return_to,
+            )
+            .await?;
+            Ok(ApiResponse::new(response))
+        }
+
+        async fn issue_token(
+            container: &Arc<AppContainer>,
+            provider: AuthProvider,
+            provider_uid: String,
+            return_to: Option<String>,
+        ) -> shinespark::Result<OAuthCallbackResponse> {
+            let command = LoginCommand::Social {
+                provider,
+                provider_uid,
+            };
+            let outcome = container
+                .db
+                .transaction(|h| Box::pin(container.jwt_ident_usecase.login(h, command.clone())))
+                .await?;
+            Ok(match outcome {
+                JwtLoginOutcome::Issued(pair) => {
+                    OAuthCallbackResponse::Authenticated(OAuthTokenResponse {
+                        access_token: pair.access_token,
+                        refresh_token: pair.refresh_token,
+                        return_to,
+                    })
+                }
+                JwtLoginOutcome::MfaRequired(challenge) => OAuthCallbackResponse::MfaRequired {
+                    challenge_token: challenge.challenge_token,
+                    expires_in: challenge.expires_in,
+                    methods: challenge.methods,
+                    return_to,
+                },
+            })
         }
 
         fn provider_usecase(
             container: &Arc<AppContainer>,
             provider: &str,
         ) -> Result<Arc<dyn shinespark_identity::usecases::SocialLoginUsecase>, ApiError> {
-            match provider {
-                "google" => Ok(container.google_login_usecase.clone()),
-                _ => Err(shinespark::Error::NotImplemented.into()),
-            }
-        }
-
-        fn parse_provider(provider: &str) -> shinespark::Result<AuthProvider> {
-            match provider {
-                "google" => Ok(AuthProvider::Google),
-                "apple" => Ok(AuthProvider::Apple),
-                _ => Err(shinespark::Error::NotFound),
-            }
+            container
+                .social_login_registry
+                .find(provider)
+                .ok_or_else(|| shinespark::Error::NotImplemented.into())
         }
 
         pub fn routes() -> Router<Arc<AppContainer>> {
@@ -276,129 +595,1319 @@ pub mod identity {
                     "/identity/oauth2/{provider}/callback",
                     axum::routing::get(callback),
                 )
+                .route("/identity/oauth2/link", axum::routing::post(link))
         }
     }
 
-    pub fn routes() -> Router<Arc<AppContainer>> {
-        Router::new().merge(session::routes()).merge(jwt::routes()).merge(oauth2::routes())
-    }
-}
+    mod mfa {
+        use std::sync::Arc;
 
-pub mod web {
-    use std::sync::Arc;
+        use axum::{Json, Router, extract::State};
+        use serde::Serialize;
+        use shinespark_identity::{
+            entities::UserAggregate,
+            infra::JwtClaims,
+            usecases::{FindUserQuery, MfaEnrollment},
+        };
 
-    use axum::{Router, extract::State};
-    use minijinja::context;
+        use crate::{
+            AppContainer,
+            http::{ApiResponse, ApiResult, jwt::SelfJwtUser},
+        };
 
-    use crate::{
-        AppContainer,
-        http::{ApiError, cookie_jwt::auth_middleware, template::TemplateResponse},
-    };
+        #[derive(Debug, Serialize)]
+        pub struct RecoveryCodesResponse {
+            /// 한 번만 보여주는 복구 코드. 서버에는 해시만 저장된다.
+            pub recovery_codes: Vec<String>,
+        }
 
-    mod auth {
+        pub(super) async fn current_user(
+            container: &AppContainer,
+            claims: &JwtClaims,
+        ) -> shinespark::Result<UserAggregate> {
+            let uid =
+                uuid::Uuid::parse_str(&claims.sub).map_err(|_| shinespark::Error::UnAuthorized)?;
+            container
+                .user_usecase
+                .find_user(&mut container.db.handle(), FindUserQuery::new().uid(uid))
+                .await?
+                .ok_or(shinespark::Error::UnAuthorized)
+        }
+
+        /// TOTP 등록 시작
+        ///
+        /// 새 secret 과 otpauth URI 를 발급합니다. `/identity/mfa/totp/confirm` 으로 코드를 확인하기 전까지는
+        /// 로그인에 적용되지 않습니다.
+        async fn enroll(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+        ) -> ApiResult<MfaEnrollment> {
+            let user = current_user(&container, &user.0).await?;
+            let enrollment =
+                container.mfa_usecase.begin_enrollment(&mut container.db.handle(), &user).await?;
+            Ok(ApiResponse::new(enrollment))
+        }
+
+        /// TOTP 등록 확인
+        ///
+        /// 인증 앱의 코드를 확인하고 복구 코드를 발급합니다.
+        async fn confirm(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            Json(body): Json<super::dto::MfaCodeRequest>,
+        ) -> ApiResult<RecoveryCodesResponse> {
+            let user = current_user(&container, &user.0).await?;
+            let recovery_codes = container
+                .mfa_usecase
+                .confirm_enrollment(&mut container.db.handle(), user.user.id, &body.code)
+                .await?;
+            Ok(ApiResponse::new(RecoveryCodesResponse { recovery_codes }))
+        }
+
+        /// TOTP 해제
+        async fn disable(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            Json(body): Json<super::dto::MfaCodeRequest>,
+        ) -> ApiResult<()> {
+            let user = current_user(&container, &user.0).await?;
+            container
+                .mfa_usecase
+                .disable(&mut container.db.handle(), user.user.id, &body.code)
+                .await?;
+            Ok(ApiResponse::new(()))
+        }
+
+        /// 복구 코드 재발급
+        ///
+        /// 기존 복구 코드는 모두 무효화됩니다.
+        async fn regenerate_recovery_codes(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            Json(body): Json<super::dto::MfaCodeRequest>,
+        ) -> ApiResult<RecoveryCodesResponse> {
+            let user = current_user(&container, &user.0).await?;
+            let recovery_codes = container
+                .mfa_usecase
+                .regenerate_recovery_codes(&mut container.db.handle(), user.user.id, &body.code)
+                .await?;
+            Ok(ApiResponse::new(RecoveryCodesResponse { recovery_codes }))
+        }
+
+        pub fn routes() -> Router<Arc<AppContainer>> {
+            Router::new()
+                .route("/identity/mfa/totp/enroll", axum::routing::post(enroll))
+                .route("/identity/mfa/totp/confirm", axum::routing::post(confirm))
+                .route("/identity/mfa/totp/disable", axum::routing::post(disable))
+                .route(
+                    "/identity/mfa/recovery-codes",
+                    axum::routing::post(regenerate_recovery_codes),
+                )
+        }
+    }
+
+    mod webauthn {
         use std::sync::Arc;
 
-        use axum::{
-            Form, Router,
-            extract::{Path, Query, State},
-            response::{IntoResponse, Redirect},
-        };
-        use axum_extra::extract::cookie::CookieJar;
-        use minijinja::context;
-        use serde::Deserialize;
-        use shinespark_identity::{
-            entities::AuthProvider,
-            usecases::{LoginCommand, SocialCallbackCommand},
+        use axum::{Json, Router, extract::State};
+        use shinespark_identity::usecases::{
+            AssertionCredential, CredentialCreationOptions, CredentialRequestOptions,
+            JwtLoginOutcome, LoginCommand, MfaFactor, MfaLoginCommand, RegistrationCredential,
         };
 
+        use super::{dto::IdentityResponse, jwt::JwtTokenResponse};
         use crate::{
             AppContainer,
-            http::{ApiError, cookie_jwt::CookieJarJwt, template::TemplateResponse},
+            http::{ApiResponse, ApiResult, jwt::SelfJwtUser},
         };
 
-        #[derive(Deserialize)]
-        pub struct LoginForm {
-            pub email: String,
-            pub password: String,
+        /// passkey 등록 시작
+        ///
+        /// `navigator.credentials.create()` 에 넘길 옵션을 반환합니다.
+        async fn register_start(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+        ) -> ApiResult<CredentialCreationOptions> {
+            let user = super::mfa::current_user(&container, &user.0).await?;
+            let options = container
+                .webauthn_usecase
+                .start_registration(&mut container.db.handle(), &user)
+                .await?;
+            Ok(ApiResponse::new(options))
         }
 
-        #[derive(Deserialize)]
-        pub struct OAuthCallbackQuery {
-            pub code: String,
-            pub state: String,
+        /// passkey 등록 완료
+        ///
+        /// attestation 을 검증하고 credential 을 `passkey` identity 로 저장합니다.
+        async fn register_finish(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            Json(credential): Json<RegistrationCredential>,
+        ) -> ApiResult<IdentityResponse> {
+            let user = super::mfa::current_user(&container, &user.0).await?;
+            let identity = container
+                .webauthn_usecase
+                .finish_registration(&mut container.db.handle(), &user, credential)
+                .await?;
+            Ok(ApiResponse::new(identity.into()))
         }
 
-        async fn login_page(
+        /// passwordless 로그인 시작
+        ///
+        /// `allowCredentials` 가 비어 있으므로 브라우저가 discoverable credential 을 고르게 합니다.
+        async fn login_start(
             State(container): State<Arc<AppContainer>>,
-        ) -> Result<TemplateResponse, ApiError> {
-            let html = container
-                .template_env
-                .render("auth/login.html", context! { error => "" })
-                .map_err(|e| shinespark::Error::Internal(anyhow::anyhow!(e)))?;
-            Ok(TemplateResponse(html))
+        ) -> ApiResult<CredentialRequestOptions> {
+            let options = container
+                .webauthn_usecase
+                .start_authentication(&mut container.db.handle(), None)
+                .await?;
+            Ok(ApiResponse::new(options))
         }
 
-        async fn login(
+        /// passwordless 로그인 완료
+        ///
+        /// 서명을 검증하고 access_token 과 refresh_token 을 발급합니다.
+        async fn login_finish(
             State(container): State<Arc<AppContainer>>,
-            Form(form): Form<LoginForm>,
-        ) -> impl IntoResponse {
-            match container
-                .jwt_ident_usecase
-                .login(
-                    &mut container.db.handle(),
-                    LoginCommand::Local {
-                        email: form.email,
-                        password: form.password,
-                    },
-                )
-                .await
-            {
-                Ok(pair) => (
-                    CookieJarJwt::build(&pair, &container.config.jwt),
-                    Redirect::to("/"),
+            Json(credential): Json<AssertionCredential>,
+        ) -> ApiResult<JwtTokenResponse> {
+            let command = LoginCommand::Passkey { credential };
+            let outcome = container
+                .db
+                .transaction(|h| Box::pin(container.jwt_ident_usecase.login(h, command.clone())))
+                .await?;
+            match outcome {
+                JwtLoginOutcome::Issued(pair) => Ok(ApiResponse::new(pair.into())),
+                // passkey 로그인은 2 단계 인증을 요구하지 않는다.
+                JwtLoginOutcome::MfaRequired(_) => Err(shinespark::Error::IllegalState(
+                    "unexpected mfa challenge for passkey login".into(),
                 )
-                    .into_response(),
-                Err(_) => match container.template_env.render(
-                    "auth/login.html",
-                    context! { error => "이메일 또는 비밀번호가 올바르지 않습니다." },
-                ) {
-                    Ok(html) => TemplateResponse(html).into_response(),
-                    Err(e) => ApiError::from(shinespark::Error::Internal(anyhow::anyhow!(e)))
-                        .into_response(),
-                },
+                .into()),
             }
         }
 
-        async fn oauth2_callback(
+        /// 2 단계 인증 passkey ceremony 시작
+        ///
+        /// 로그인에서 받은 challenge_token 의 사용자가 등록한 passkey 만 허용합니다.
+        async fn mfa_start(
             State(container): State<Arc<AppContainer>>,
-            Path(provider): Path<String>,
-            Query(params): Query<OAuthCallbackQuery>,
-        ) -> impl IntoResponse {
-            let usecase = match provider.as_str() {
-                "google" => container.google_login_usecase.clone(),
-                _ => {
-                    return ApiError::from(shinespark::Error::NotImplemented).into_response();
-                }
+            Json(body): Json<super::dto::MfaChallengeRequest>,
+        ) -> ApiResult<CredentialRequestOptions> {
+            let mut handle = container.db.handle();
+            let user =
+                container.mfa_usecase.challenge_user(&mut handle, &body.challenge_token).await?;
+            let options =
+                container.webauthn_usecase.start_authentication(&mut handle, Some(&user)).await?;
+            Ok(ApiResponse::new(options))
+        }
+
+        /// 2 단계 인증 passkey ceremony 완료
+        async fn mfa_finish(
+            State(container): State<Arc<AppContainer>>,
+            Json(body): Json<super::dto::PasskeyMfaRequest>,
+        ) -> ApiResult<JwtTokenResponse> {
+            let command = MfaLoginCommand {
+                challenge_token: body.challenge_token,
+                factor: MfaFactor::Passkey(body.credential),
             };
+            let pair = container
+                .db
+                .transaction(|h| {
+                    Box::pin(container.jwt_ident_usecase.complete_mfa(h, command.clone()))
+                })
+                .await?;
+            Ok(ApiResponse::new(pair.into()))
+        }
+
+        pub fn routes() -> Router<Arc<AppContainer>> {
+            Router::new()
+                .route(
+                    "/identity/webauthn/register/start",
+                    axum::routing::post(register_start),
+                )
+                .route(
+                    "/identity/webauthn/register/finish",
+                    axum::routing::post(register_finish),
+                )
+                .route(
+                    "/identity/webauthn/login/start",
+                    axum::routing::post(login_start),
+                )
+                .route(
+                    "/identity/webauthn/login/finish",
+                    axum::routing::post(login_finish),
+                )
+                .route(
+                    "/identity/webauthn/mfa/start",
+                    axum::routing::post(mfa_start),
+                )
+                .route(
+                    "/identity/webauthn/mfa/finish",
+                    axum::routing::post(mfa_finish),
+                )
+        }
+    }
+
+    mod api_keys {
+        use std::sync::Arc;
+
+        use axum::{
+            Json, Router,
+            extract::{Path, State},
+        };
+        use serde::{Deserialize, Serialize};
+        use shinespark_identity::{
+            entities::ApiKey,
+            usecases::{CreateApiKeyCommand, IssuedApiKey},
+        };
+        use sqlx::types::chrono::{DateTime, Utc};
+
+        use crate::{
+            AppContainer,
+            http::{
+                ApiResponse, ApiResult,
+                jwt::{JwtUser, SelfJwtUser},
+            },
+        };
+
+        #[derive(Debug, Deserialize)]
+        pub struct CreateApiKeyRequest {
+            pub name: String,
+            #[serde(default)]
+            pub permissions: Vec<String>,
+            pub expires_at: Option<DateTime<Utc>>,
+        }
+
+        /// 응답에 쓰는 API key 정보. 내부 PK 와 user_id 대신 공개 `prefix` 로 키를 가리킨다.
+        #[derive(Debug, Serialize)]
+        pub struct ApiKeyResponse {
+            pub prefix: String,
+            pub name: String,
+            pub permissions: Vec<String>,
+            pub expires_at: Option<DateTime<Utc>>,
+            pub last_used_at: Option<DateTime<Utc>>,
+            pub created_at: DateTime<Utc>,
+        }
+
+        impl From<ApiKey> for ApiKeyResponse {
+            fn from(api_key: ApiKey) -> Self {
+                Self {
+                    permissions: api_key.permission_list(),
+                    prefix: api_key.prefix,
+                    name: api_key.name,
+                    expires_at: api_key.expires_at,
+                    last_used_at: api_key.last_used_at,
+                    created_at: api_key.created_at,
+                }
+            }
+        }
+
+        #[derive(Debug, Serialize)]
+        pub struct IssuedApiKeyResponse {
+            #[serde(flatten)]
+            pub api_key: ApiKeyResponse,
+            /// 평문 키. 다시 조회할 수 없다.
+            pub key: String,
+        }
+
+        impl From<IssuedApiKey> for IssuedApiKeyResponse {
+            fn from(issued: IssuedApiKey) -> Self {
+                Self {
+                    api_key: issued.api_key.into(),
+                    key: issued.key,
+                }
+            }
+        }
+
+        /// API key 발급
+        ///
+        /// 평문 키는 이 응답에서만 확인할 수 있습니다. 요청한 권한은 모두 현재 사용자가 가진 권한이어야 합니다.
+        async fn create(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            Json(body): Json<CreateApiKeyRequest>,
+        ) -> ApiResult<IssuedApiKeyResponse> {
+            let issued = container
+                .api_key_usecase
+                .create_api_key(
+                    &mut container.db.handle(),
+                    &user.0,
+                    CreateApiKeyCommand {
+                        name: body.name,
+                        permissions: body.permissions,
+                        expires_at: body.expires_at,
+                    },
+                )
+                .await?;
+            Ok(ApiResponse::new(issued.into()))
+        }
+
+        /// 발급한 API key 목록
+        async fn list(
+            State(container): State<Arc<AppContainer>>,
+            user: JwtUser,
+        ) -> ApiResult<Vec<ApiKeyResponse>> {
+            let user = super::mfa::current_user(&container, &user.0).await?;
+            let keys = container
+                .api_key_usecase
+                .list_api_keys(&mut container.db.handle(), user.user.id)
+                .await?;
+            Ok(ApiResponse::new(keys.into_iter().map(Into::into).collect()))
+        }
+
+        /// API key 폐기
+        ///
+        /// 목록의 `prefix` 로 키를 지정합니다.
+        async fn revoke(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            Path(prefix): Path<String>,
+        ) -> ApiResult<()> {
+            let user = super::mfa::current_user(&container, &user.0).await?;
+            container
+                .api_key_usecase
+                .revoke_api_key(&mut container.db.handle(), user.user.id, &prefix)
+                .await?;
+            Ok(ApiResponse::new(()))
+        }
+
+        pub fn routes() -> Router<Arc<AppContainer>> {
+            Router::new()
+                .route("/identity/api-keys", axum::routing::get(list).post(create))
+                .route("/identity/api-keys/{prefix}", axum::routing::delete(revoke))
+        }
+    }
+
+    mod impersonation {
+        use std::sync::Arc;
 
-            let user = match usecase
-                .callback(
+        use axum::{
+            Json, Router,
+            extract::State,
+            http::{HeaderMap, header},
+        };
+        use serde::{Deserialize, Serialize};
+        use shinespark_identity::usecases::{ImpersonateCommand, ImpersonationToken};
+
+        use super::dto::UserResponse;
+
+        use crate::{
+            AppContainer,
+            http::{
+                ApiResponse, ApiResult,
+                jwt::{JwtUser, SelfJwtUser, bearer_token},
+            },
+        };
+
+        #[derive(Debug, Deserialize)]
+        pub struct ImpersonateRequest {
+            pub target_uid: uuid::Uuid,
+            pub reason: String,
+        }
+
+        #[derive(Debug, Serialize)]
+        pub struct ImpersonationResponse {
+            pub access_token: String,
+            pub expires_in: i64,
+            pub target: UserResponse,
+        }
+
+        impl From<ImpersonationToken> for ImpersonationResponse {
+            fn from(token: ImpersonationToken) -> Self {
+                Self {
+                    access_token: token.access_token,
+                    expires_in: token.expires_in,
+                    target: token.target.into(),
+                }
+            }
+        }
+
+        pub(crate) fn user_agent(headers: &HeaderMap) -> Option<String> {
+            headers.get(header::USER_AGENT).and_then(|v| v.to_str().ok()).map(str::to_string)
+        }
+
+        /// 대리 로그인 시작
+        ///
+        /// `user.impersonate.all` 권한이 필요합니다. 발급된 토큰은 refresh 할 수 없고,
+        /// 비밀번호 변경, 2 단계 인증 설정, API key 발급에는 사용할 수 없습니다.
+        async fn start(
+            State(container): State<Arc<AppContainer>>,
+            user: SelfJwtUser,
+            headers: HeaderMap,
+            Json(body): Json<ImpersonateRequest>,
+        ) -> ApiResult<ImpersonationResponse> {
+            let token = container
+                .impersonation_usecase
+                .impersonate(
                     &mut container.db.handle(),
-                    SocialCallbackCommand {
-                        code: params.code,
-                        state: params.state,
+                    &user.0,
+                    ImpersonateCommand {
+                        target_uid: body.target_uid,
+                        reason: body.reason,
+                        user_agent: user_agent(&headers),
                     },
                 )
+                .await?;
+            Ok(ApiResponse::new(token.into()))
+        }
+
+        /// 대리 로그인 종료
+        ///
+        /// 종료를 감사 로그에 남기고 대리 로그인 토큰을 폐기합니다.
+        async fn stop(
+            State(container): State<Arc<AppContainer>>,
+            _user: JwtUser,
+            headers: HeaderMap,
+        ) -> ApiResult<()> {
+            let token = bearer_token(&headers).unwrap_or_default();
+            container
+                .impersonation_usecase
+                .stop(&mut container.db.handle(), token, user_agent(&headers))
+                .await?;
+            Ok(ApiResponse::new(()))
+        }
+
+        pub fn routes() -> Router<Arc<AppContainer>> {
+            Router::new()
+                .route("/identity/impersonation", axum::routing::post(start))
+                .route("/identity/impersonation/stop", axum::routing::post(stop))
+        }
+    }
+
+    pub fn routes() -> Router<Arc<AppContainer>> {
+        Router::new()
+            .merge(session::routes())
+            .merge(jwt::routes())
+            .merge(oauth2::routes())
+            .merge(mfa::routes())
+            .merge(webauthn::routes())
+            .merge(api_keys::routes())
+            .merge(impersonation::routes())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use shinespark_identity::entities::{
+            ApiKey, AuthProvider, User, UserAggregate, UserIdentity, UserStatus,
+        };
+        use shinespark_identity::usecases::{ImpersonationToken, IssuedApiKey};
+        use sqlx::types::chrono::Utc;
+
+        use super::api_keys::{ApiKeyResponse, IssuedApiKeyResponse};
+        use super::dto::{IdentityResponse, UserAggregateResponse};
+        use super::impersonation::ImpersonationResponse;
+        use super::session::SessionLoginResponse;
+
+        const SECRETS: [&str; 4] = [
+            "argon2-password-hash",
+            "cose-public-key",
+            "google-sub-1234",
+            "api-key-hash",
+        ];
+
+        fn aggregate() -> UserAggregate {
+            let mut user = User::new(
+                "tester".into(),
+                "tester@example.com".into(),
+                UserStatus::Active,
+            );
+            user.id = 4242;
+            UserAggregate {
+                user,
+                role_ids: vec![1],
+                identities: vec![
+                    UserIdentity::new(
+                        4242,
+                        AuthProvider::Local,
+                        "tester@example.com".into(),
+                        Some(SECRETS[0].into()),
+                    ),
+                    UserIdentity::passkey(4242, "credential-id".into(), SECRETS[1].into(), 7),
+                    UserIdentity::new(4242, AuthProvider::Google, SECRETS[2].into(), None),
+                ],
+            }
+        }
+
+        fn api_key() -> ApiKey {
+            ApiKey {
+                id: 4242,
+                user_id: 4242,
+                name: "ci".into(),
+                prefix: "shs_abcd1234efgh".into(),
+                key_hash: SECRETS[3].into(),
+                permissions: "user.read.own".into(),
+                expires_at: None,
+                last_used_at: None,
+                created_at: Utc::now(),
+            }
+        }
+
+        fn assert_no_leak<T: serde::Serialize>(response: &T) {
+            let json = serde_json::to_string(response).unwrap();
+            for secret in SECRETS {
+                assert!(!json.contains(secret), "{} leaked: {}", secret, json);
+            }
+            assert!(!json.contains("4242"), "internal id leaked: {}", json);
+        }
+
+        #[test]
+        fn test_identity_responses_do_not_leak_credentials() {
+            assert_no_leak(&SessionLoginResponse::Authenticated(aggregate().into()));
+            assert_no_leak(&UserAggregateResponse::from(aggregate()));
+            for identity in aggregate().identities {
+                assert_no_leak(&IdentityResponse::from(identity));
+            }
+            assert_no_leak(&ApiKeyResponse::from(api_key()));
+            assert_no_leak(&IssuedApiKeyResponse::from(IssuedApiKey {
+                api_key: api_key(),
+                key: "shs_abcd1234efgh.secret".into(),
+            }));
+            assert_no_leak(&ImpersonationResponse::from(ImpersonationToken {
+                access_token: "token".into(),
+                expires_in: 900,
+                target: aggregate().user,
+            }));
+
+            // entity 를 실수로 그대로 내보내도 비밀번호 해시는 직렬화되지 않는다.
+            assert!(!serde_json::to_string(&aggregate()).unwrap().contains(SECRETS[0]));
+        }
+    }
+}
+
+/// shinespark 가 발급하는 OAuth2 토큰. 응답 형식은 다른 라이브러리와 호환되도록 RFC 6749 를 따른다.
+pub mod oauth2 {
+    use std::sync::Arc;
+
+    use axum::{
+        Form, Json, Router,
+        extract::{Query, State},
+        http::{HeaderMap, StatusCode, header},
+        response::{IntoResponse, Redirect, Response},
+    };
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use minijinja::context;
+    use serde::{Deserialize, Serialize};
+    use shinespark_identity::usecases::{
+        AuthorizationCodeCommand, AuthorizationRequest, ClientCredentialsCommand, FindUserQuery,
+        OpenIdConfiguration, RegisterClientCommand, RegisteredClient, TokenIntrospection, UserInfo,
+        ValidatedAuthorization,
+    };
+
+    use crate::{
+        AppContainer,
+        http::{
+            ApiError, ApiResponse, ApiResult,
+            cookie_jwt::{CookieJwtUser, auth_middleware},
+            jwt::ScopedJwtUser,
+            template::TemplateResponse,
+        },
+    };
+
+    /// RFC 6749 5.2 error 응답
+    #[derive(Debug, Serialize)]
+    pub struct OAuthError {
+        #[serde(skip)]
+        pub status_code: StatusCode,
+        pub error: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub error_description: Option<String>,
+    }
+
+    impl OAuthError {
+        pub fn new(error: &'static str) -> Self {
+            let status_code = match error {
+                "invalid_client" => StatusCode::UNAUTHORIZED,
+                "server_error" => StatusCode::INTERNAL_SERVER_ERROR,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            Self {
+                status_code,
+                error,
+                error_description: None,
+            }
+        }
+
+        pub fn description(mut self, description: impl Into<String>) -> Self {
+            self.error_description = Some(description.into());
+            self
+        }
+    }
+
+    impl IntoResponse for OAuthError {
+        fn into_response(self) -> axum::response::Response {
+            let mut response = (self.status_code, Json(&self)).into_response();
+            if self.status_code == StatusCode::UNAUTHORIZED {
+                response.headers_mut().insert(header::WWW_AUTHENTICATE, "Basic".parse().unwrap());
+            }
+            response
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct TokenRequest {
+        pub grant_type: String,
+        pub scope: Option<String>,
+        pub client_id: Option<String>,
+        pub client_secret: Option<String>,
+        // authorization_code grant
+        pub code: Option<String>,
+        pub redirect_uri: Option<String>,
+        pub code_verifier: Option<String>,
+    }
+
+    /// `application/x-www-form-urlencoded` 로 인코딩된 값을 되돌린다. (`+` 는 공백)
+    fn form_urldecode(value: &str) -> Option<String> {
+        percent_encoding::percent_decode_str(&value.replace('+', " "))
+            .decode_utf8()
+            .ok()
+            .map(|v| v.into_owned())
+    }
+
+    /// `Authorization: Basic` (client_secret_basic) 또는 form body (client_secret_post) 의 client 인증 정보
+    ///
+    /// Basic 의 client_id, client_secret 은 base64 로 감싸기 전에 form-urlencode 되어 있다. (RFC 6749 2.3.1)
+    pub fn client_credentials(
+        headers: &HeaderMap,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+    ) -> Result<(String, String), OAuthError> {
+        let basic = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Basic "));
+        if let Some(encoded) = basic {
+            let decoded = STANDARD
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|b| String::from_utf8(b).ok())
+                .ok_or_else(|| OAuthError::new("invalid_client"))?;
+            let (id, secret) = decoded
+                .split_once(':')
+                .and_then(|(id, secret)| Some((form_urldecode(id)?, form_urldecode(secret)?)))
+                .ok_or_else(|| OAuthError::new("invalid_client"))?;
+            return Ok((id, secret));
+        }
+        match (client_id, client_secret) {
+            (Some(id), Some(secret)) => Ok((id, secret)),
+            _ => Err(OAuthError::new("invalid_client")),
+        }
+    }
+
+    /// 토큰 발급
+    ///
+    /// `client_credentials` grant 로 발급된 토큰의 `sub` 는 client_id 이고,
+    /// `authorization_code` grant 로 발급된 토큰의 `sub` 는 동의한 사용자입니다.
+    async fn token(
+        State(container): State<Arc<AppContainer>>,
+        headers: HeaderMap,
+        Form(form): Form<TokenRequest>,
+    ) -> Result<Response, OAuthError> {
+        let (client_id, client_secret) =
+            client_credentials(&headers, form.client_id, form.client_secret)?;
+
+        match form.grant_type.as_str() {
+            "client_credentials" => {
+                let token = container
+                    .oauth_client_usecase
+                    .issue_client_token(
+                        &mut container.db.handle(),
+                        ClientCredentialsCommand {
+                            client_id,
+                            client_secret,
+                            scope: form.scope,
+                        },
+                    )
+                    .await
+                    .map_err(|e| match e {
+                        shinespark::Error::InvalidCredentials => OAuthError::new("invalid_client"),
+                        shinespark::Error::UnAuthorized => OAuthError::new("invalid_scope"),
+                        e => server_error(e),
+                    })?;
+                Ok(Json(token).into_response())
+            }
+            "authorization_code" => {
+                let (Some(code), Some(redirect_uri), Some(code_verifier)) =
+                    (form.code, form.redirect_uri, form.code_verifier)
+                else {
+                    return Err(OAuthError::new("invalid_request")
+                        .description("code, redirect_uri and code_verifier are required"));
+                };
+                let token = container
+                    .authorization_server_usecase
+                    .exchange_code(
+                        &mut container.db.handle(),
+                        AuthorizationCodeCommand {
+                            client_id,
+                            client_secret,
+                            code,
+                            redirect_uri,
+                            code_verifier,
+                        },
+                    )
+                    .await
+                    .map_err(|e| match e {
+                        shinespark::Error::InvalidCredentials => OAuthError::new("invalid_client"),
+                        shinespark::Error::UnAuthorized => OAuthError::new("invalid_grant"),
+                        e => server_error(e),
+                    })?;
+                Ok(Json(token).into_response())
+            }
+            _ => Err(OAuthError::new("unsupported_grant_type")),
+        }
+    }
+
+    /// introspection / revocation 요청 (RFC 7662 2.1, RFC 7009 2.1)
+    #[derive(Debug, Deserialize)]
+    pub struct TokenActionRequest {
+        /// `token_type_hint` 는 받지 않는다. 토큰 자체의 token_type 으로 판단한다.
+        pub token: String,
+        pub client_id: Option<String>,
+        pub client_secret: Option<String>,
+    }
+
+    /// 내부 오류는 서버 로그에만 남기고 client 에는 `server_error` 만 돌려준다.
+    fn server_error(e: shinespark::Error) -> OAuthError {
+        tracing::error!(error = %e, "oauth2 request failed");
+        OAuthError::new("server_error")
+    }
+
+    async fn authenticate_client(
+        container: &AppContainer,
+        headers: &HeaderMap,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+    ) -> Result<String, OAuthError> {
+        let (client_id, client_secret) = client_credentials(headers, client_id, client_secret)?;
+        container
+            .oauth_client_usecase
+            .authenticate_client(&mut container.db.handle(), &client_id, &client_secret)
+            .await
+            .map(|client| client.client_id)
+            .map_err(|e| match e {
+                shinespark::Error::InvalidCredentials => OAuthError::new("invalid_client"),
+                e => server_error(e),
+            })
+    }
+
+    /// 토큰 검사
+    ///
+    /// resource server 가 secret 을 공유하지 않고 토큰을 검증할 때 씁니다.
+    /// 폐기되었거나 만료된 토큰은 `{"active": false}` 입니다.
+    async fn introspect(
+        State(container): State<Arc<AppContainer>>,
+        headers: HeaderMap,
+        Form(form): Form<TokenActionRequest>,
+    ) -> Result<Json<TokenIntrospection>, OAuthError> {
+        authenticate_client(&container, &headers, form.client_id, form.client_secret).await?;
+        let introspection = container
+            .jwt_ident_usecase
+            .introspect(&mut container.db.handle(), &form.token)
+            .await
+            .map_err(server_error)?;
+        Ok(Json(introspection))
+    }
+
+    /// 토큰 폐기
+    ///
+    /// 유효하지 않은 토큰이어도 200 을 돌려줍니다. (RFC 7009 2.2)
+    async fn revoke(
+        State(container): State<Arc<AppContainer>>,
+        headers: HeaderMap,
+        Form(form): Form<TokenActionRequest>,
+    ) -> Result<StatusCode, OAuthError> {
+        let client_id =
+            authenticate_client(&container, &headers, form.client_id, form.client_secret).await?;
+        container
+            .jwt_ident_usecase
+            .revoke(&mut container.db.handle(), &form.token, &client_id)
+            .await
+            .map_err(server_error)?;
+        Ok(StatusCode::OK)
+    }
+
+    /// `/oauth2/authorize` 파라미터. 동의 화면의 hidden input 으로 그대로 다시 전달된다.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct AuthorizeParams {
+        pub response_type: String,
+        pub client_id: String,
+        pub redirect_uri: String,
+        pub scope: Option<String>,
+        pub state: Option<String>,
+        pub code_challenge: Option<String>,
+        pub code_challenge_method: Option<String>,
+        pub nonce: Option<String>,
+    }
+
+    impl From<AuthorizeParams> for AuthorizationRequest {
+        fn from(params: AuthorizeParams) -> Self {
+            Self {
+                response_type: params.response_type,
+                client_id: params.client_id,
+                redirect_uri: params.redirect_uri,
+                scope: params.scope,
+                code_challenge: params.code_challenge,
+                code_challenge_method: params.code_challenge_method,
+                nonce: params.nonce,
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ConsentForm {
+        #[serde(flatten)]
+        pub params: AuthorizeParams,
+        pub decision: String, // "approve" | "deny"
+    }
+
+    /// client 의 redirect_uri 로 결과를 돌려준다. (RFC 6749 4.1.2)
+    fn redirect_to_client(
+        redirect_uri: &str,
+        params: &[(&str, &str)],
+        state: Option<&str>,
+    ) -> Response {
+        let mut pairs = params.to_vec();
+        if let Some(state) = state {
+            pairs.push(("state", state));
+        }
+        let separator = if redirect_uri.contains('?') { '&' } else { '?' };
+        let query = serde_urlencoded::to_string(pairs).unwrap_or_default();
+        Redirect::to(&format!("{}{}{}", redirect_uri, separator, query)).into_response()
+    }
+
+    /// 요청을 검증한다. client 나 redirect_uri 가 잘못되면 redirect 하지 않고 오류를 보여주고,
+    /// 그 외 오류는 client 로 redirect 한다. (RFC 6749 4.1.2.1)
+    async fn validate(
+        container: &AppContainer,
+        params: &AuthorizeParams,
+    ) -> Result<ValidatedAuthorization, Response> {
+        let result = container
+            .authorization_server_usecase
+            .validate_authorization_request(&mut container.db.handle(), params.clone().into())
+            .await;
+        let (error, description) = match result {
+            Ok(authorization) => return Ok(authorization),
+            Err(shinespark::Error::NotFound) => {
+                return Err(ApiError::from(shinespark::Error::IllegalState(
+                    "invalid client_id or redirect_uri".into(),
+                ))
+                .into_response());
+            }
+            Err(shinespark::Error::NotImplemented) => ("unsupported_response_type", None),
+            Err(shinespark::Error::UnAuthorized) => ("invalid_scope", None),
+            Err(shinespark::Error::IllegalState(msg)) => ("invalid_request", Some(msg.to_string())),
+            Err(e) => return Err(ApiError::from(e).into_response()),
+        };
+        let mut pairs = vec![("error", error)];
+        if let Some(description) = description.as_deref() {
+            pairs.push(("error_description", description));
+        }
+        Err(redirect_to_client(
+            &params.redirect_uri,
+            &pairs,
+            params.state.as_deref(),
+        ))
+    }
+
+    /// 동의 화면
+    ///
+    /// 로그인하지 않았다면 로그인 후 이 화면으로 돌아옵니다.
+    async fn authorize_page(
+        State(container): State<Arc<AppContainer>>,
+        user: CookieJwtUser,
+        Query(params): Query<AuthorizeParams>,
+    ) -> Response {
+        // 대리 로그인 중에는 사용자 대신 제3자 앱에 권한을 위임할 수 없다.
+        if user.0.is_impersonated() {
+            return ApiError::from(shinespark::Error::IllegalState(
+                "not allowed while impersonating".into(),
+            ))
+            .into_response();
+        }
+        let authorization = match validate(&container, &params).await {
+            Ok(authorization) => authorization,
+            Err(response) => return response,
+        };
+        let Ok(uid) = uuid::Uuid::parse_str(&user.0.sub) else {
+            return ApiError::from(shinespark::Error::UnAuthorized).into_response();
+        };
+        let user = match container
+            .user_usecase
+            .find_user(&mut container.db.handle(), FindUserQuery::new().uid(uid))
+            .await
+        {
+            Ok(Some(user)) => user,
+            Ok(None) => return ApiError::from(shinespark::Error::UnAuthorized).into_response(),
+            Err(e) => return ApiError::from(e).into_response(),
+        };
+
+        match container.template_env.render(
+            "auth/consent.html",
+            context! {
+                client_name => authorization.client.name,
+                user_email => user.user.email,
+                scopes => authorization.scopes,
+                redirect_uri => authorization.redirect_uri,
+                params => params,
+            },
+        ) {
+            Ok(html) => TemplateResponse(html).into_response(),
+            Err(e) => {
+                ApiError::from(shinespark::Error::Internal(anyhow::anyhow!(e))).into_response()
+            }
+        }
+    }
+
+    /// 동의 결과 처리
+    ///
+    /// 승인하면 authorization code 를, 거부하면 `access_denied` 를 redirect_uri 로 돌려줍니다.
+    async fn authorize(
+        State(container): State<Arc<AppContainer>>,
+        user: CookieJwtUser,
+        Form(form): Form<ConsentForm>,
+    ) -> Response {
+        if user.0.is_impersonated() {
+            return ApiError::from(shinespark::Error::IllegalState(
+                "not allowed while impersonating".into(),
+            ))
+            .into_response();
+        }
+        let params = form.params;
+        // hidden input 으로 돌아온 값이므로 다시 검증한다.
+        let authorization = match validate(&container, &params).await {
+            Ok(authorization) => authorization,
+            Err(response) => return response,
+        };
+        if form.decision != "approve" {
+            return redirect_to_client(
+                &authorization.redirect_uri,
+                &[("error", "access_denied")],
+                params.state.as_deref(),
+            );
+        }
+
+        let mut handle = container.db.handle();
+        let Ok(uid) = uuid::Uuid::parse_str(&user.0.sub) else {
+            return ApiError::from(shinespark::Error::UnAuthorized).into_response();
+        };
+        let user = match container
+            .user_usecase
+            .find_user(&mut handle, FindUserQuery::new().uid(uid))
+            .await
+        {
+            Ok(Some(user)) => user,
+            Ok(None) => return ApiError::from(shinespark::Error::UnAuthorized).into_response(),
+            Err(e) => return ApiError::from(e).into_response(),
+        };
+        match container
+            .authorization_server_usecase
+            .approve(&mut handle, &user, &authorization)
+            .await
+        {
+            Ok(code) => redirect_to_client(
+                &authorization.redirect_uri,
+                &[("code", &code)],
+                params.state.as_deref(),
+            ),
+            Err(e) => ApiError::from(e).into_response(),
+        }
+    }
+
+    /// OIDC UserInfo
+    ///
+    /// `openid` scope 로 발급된 access token 이 필요합니다. `profile`, `email` scope 에 따라 claim 이 포함됩니다.
+    async fn userinfo(
+        State(container): State<Arc<AppContainer>>,
+        user: ScopedJwtUser,
+    ) -> Result<Json<UserInfo>, ApiError> {
+        let userinfo = container
+            .authorization_server_usecase
+            .userinfo(&mut container.db.handle(), &user.0)
+            .await?;
+        Ok(Json(userinfo))
+    }
+
+    /// OIDC Discovery
+    async fn openid_configuration(
+        State(container): State<Arc<AppContainer>>,
+    ) -> Json<OpenIdConfiguration> {
+        Json(container.authorization_server_usecase.discovery())
+    }
+
+    /// ID token 서명 검증용 공개키 (JWKS)
+    async fn jwks(State(container): State<Arc<AppContainer>>) -> Response {
+        Json(container.authorization_server_usecase.jwks()).into_response()
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct RegisterClientRequest {
+        pub name: String,
+        #[serde(default)]
+        pub scopes: Vec<String>,
+        #[serde(default)]
+        pub redirect_uris: Vec<String>,
+    }
+
+    /// client 등록
+    ///
+    /// `oauth_client.create.all` 권한이 필요합니다. client_secret 은 이 응답에서만 확인할 수 있습니다.
+    async fn register_client(
+        State(container): State<Arc<AppContainer>>,
+        user: ScopedJwtUser,
+        Json(body): Json<RegisterClientRequest>,
+    ) -> ApiResult<RegisteredClient> {
+        if !user.0.has_perm(container.rbac_usecase.as_ref(), "oauth_client.create.all") {
+            return Err(shinespark::Error::UnAuthorized.into());
+        }
+        let registered = container
+            .oauth_client_usecase
+            .register_client(
+                &mut container.db.handle(),
+                RegisterClientCommand {
+                    name: body.name,
+                    scopes: body.scopes,
+                    redirect_uris: body.redirect_uris,
+                },
+            )
+            .await?;
+        Ok(ApiResponse::new(registered))
+    }
+
+    pub fn routes(container: Arc<AppContainer>) -> Router<Arc<AppContainer>> {
+        let authorize_routes = Router::new()
+            .route(
+                "/oauth2/authorize",
+                axum::routing::get(authorize_page).post(authorize),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                container,
+                auth_middleware,
+            ));
+
+        Router::new()
+            .route("/oauth2/token", axum::routing::post(token))
+            .route("/oauth2/introspect", axum::routing::post(introspect))
+            .route("/oauth2/revoke", axum::routing::post(revoke))
+            .route("/oauth2/clients", axum::routing::post(register_client))
+            .route("/oauth2/userinfo", axum::routing::get(userinfo))
+            .route("/oauth2/jwks", axum::routing::get(jwks))
+            .route(
+                "/.well-known/openid-configuration",
+                axum::routing::get(openid_configuration),
+            )
+            .merge(authorize_routes)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn basic(id: &str, secret: &str) -> HeaderMap {
+            let mut headers = HeaderMap::new();
+            let encoded = STANDARD.encode(format!("{}:{}", id, secret));
+            headers.insert(
+                header::AUTHORIZATION,
+                format!("Basic {}", encoded).parse().unwrap(),
+            );
+            headers
+        }
+
+        #[test]
+        fn test_client_credentials_basic_is_form_urldecoded() {
+            // 원래 secret 은 "a+b:c%d e"
+            let headers = basic("my%20client", "a%2Bb%3Ac%25d+e");
+            let (id, secret) = client_credentials(&headers, None, None).unwrap();
+            assert_eq!(id, "my client");
+            assert_eq!(secret, "a+b:c%d e");
+
+            let headers = basic("client", "bad%FF");
+            assert!(client_credentials(&headers, None, None).is_err());
+        }
+
+        #[test]
+        fn test_server_error_hides_internal_message() {
+            let error = server_error(shinespark::Error::DatabaseError(anyhow::anyhow!(
+                "relation shs_iam_oauth_client does not exist"
+            )));
+            assert_eq!(error.status_code, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(
+                serde_json::to_value(&error).unwrap(),
+                serde_json::json!({"error": "server_error"})
+            );
+        }
+    }
+}
+
+pub mod web {
+    use std::sync::Arc;
+
+    use axum::{Router, extract::State};
+    use minijinja::context;
+
+    use crate::{
+        AppContainer,
+        http::{
+            ApiError,
+            cookie_jwt::{CookieJwtUser, auth_middleware},
+            template::TemplateResponse,
+        },
+    };
+
+    mod auth {
+        use std::sync::Arc;
+
+        use axum::{
+            Form, Router,
+            extract::{Path, Query, State},
+            response::{IntoResponse, Redirect},
+        };
+        use axum_extra::extract::cookie::CookieJar;
+        use minijinja::context;
+        use serde::Deserialize;
+        use shinespark_identity::usecases::{
+            ConfirmSocialLinkCommand, JwtLoginOutcome, LoginCommand, MfaFactor, MfaLoginCommand,
+            SocialCallbackCommand, SocialLoginOutcome,
+        };
+
+        use crate::{
+            AppContainer,
+            http::{
+                ApiError,
+                cookie_jwt::CookieJarJwt,
+                oauth2_state::{OAuth2PendingLink, OAuth2PendingState, sanitize_return_to},
+                session::Session,
+                template::TemplateResponse,
+            },
+        };
+
+        #[derive(Deserialize)]
+        pub struct LoginForm {
+            pub email: String,
+            pub password: String,
+            pub return_to: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        pub struct LoginPageQuery {
+            pub return_to: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        pub struct LinkForm {
+            pub password: String,
+        }
+
+        #[derive(Deserialize)]
+        pub struct MfaForm {
+            pub challenge_token: String,
+            pub code: String,
+            pub return_to: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        pub struct OAuthCallbackQuery {
+            pub code: String,
+            pub state: String,
+        }
+
+        async fn login_page(
+            State(container): State<Arc<AppContainer>>,
+            Query(query): Query<LoginPageQuery>,
+        ) -> Result<TemplateResponse, ApiError> {
+            let html = container
+                .template_env
+                .render(
+                    "auth/login.html",
+                    context! { error => "", return_to => sanitize_return_to(query.return_to) },
+                )
+                .map_err(|e| shinespark::Error::Internal(anyhow::anyhow!(e)))?;
+            Ok(TemplateResponse(html))
+        }
+
+        async fn login(
+            State(container): State<Arc<AppContainer>>,
+            Form(form): Form<LoginForm>,
+        ) -> impl IntoResponse {
+            // hidden input 으로 돌아온 값이므로 다시 검사한다.
+            let return_to = sanitize_return_to(form.return_to.filter(|r| !r.is_empty()));
+            let command = LoginCommand::Local {
+                email: form.email,
+                password: form.password,
+            };
+            match container
+                .db
+                .transaction(|h| Box::pin(container.jwt_ident_usecase.login(h, command.clone())))
                 .await
             {
-                Ok(u) => u,
+                Ok(outcome) => finish_login(&container, outcome, return_to),
+                Err(_) => match container.template_env.render(
+                    "auth/login.html",
+                    context! {
+                        error => "이메일 또는 비밀번호가 올바르지 않습니다.",
+                        return_to => return_to,
+                    },
+                ) {
+                    Ok(html) => TemplateResponse(html).into_response(),
+                    Err(e) => ApiError::from(shinespark::Error::Internal(anyhow::anyhow!(e)))
+                        .into_response(),
+                },
+            }
+        }
+
+        async fn oauth2_callback(
+            State(container): State<Arc<AppContainer>>,
+            Path(provider): Path<String>,
+            Query(params): Query<OAuthCallbackQuery>,
+            session: Session,
+        ) -> impl IntoResponse {
+            let Some(usecase) = container.social_login_registry.find(&provider) else {
+                return ApiError::from(shinespark::Error::NotImplemented).into_response();
+            };
+            let auth_provider = usecase.provider();
+
+            let pending = match OAuth2PendingState::take(&session, &provider).await {
+                Ok(p) => p,
                 Err(e) => return ApiError::from(e).into_response(),
             };
 
+            let command = SocialCallbackCommand {
+                code: params.code,
+                state: params.state,
+                expected_state: pending.state,
+                code_verifier: pending.code_verifier,
+            };
+            // provider 와의 HTTP 통신은 트랜잭션 밖에서 끝낸다.
+            let profile = match usecase.authenticate(command).await {
+                Ok(profile) => profile,
+                Err(e) => return ApiError::from(e).into_response(),
+            };
+            let outcome = match container
+                .db
+                .transaction(|h| Box::pin(usecase.resolve(h, profile.clone())))
+                .await
+            {
+                Ok(o) => o,
+                Err(e) => return ApiError::from(e).into_response(),
+            };
+
+            let user = match outcome {
+                SocialLoginOutcome::LoggedIn(user) => user,
+                SocialLoginOutcome::LinkRequired(link) => {
+                    // 기존 계정의 비밀번호 확인 화면으로
+                    return match OAuth2PendingLink::new(link, pending.return_to)
+                        .save(&session)
+                        .await
+                    {
+                        Ok(()) => Redirect::to("/auth/oauth2/link").into_response(),
+                        Err(e) => ApiError::from(e).into_response(),
+                    };
+                }
+            };
+
             let provider_uid = match user
                 .identities
                 .iter()
-                .find(|i| i.provider == AuthProvider::Google)
+                .find(|i| i.provider == auth_provider)
                 .map(|i| i.provider_uid.clone())
             {
                 Some(uid) => uid,
@@ -410,26 +1919,167 @@ pub mod web {
                 }
             };
 
-            let pair = match container
-                .jwt_ident_usecase
-                .login(
+            let command = LoginCommand::Social {
+                provider: auth_provider,
+                provider_uid,
+            };
+            match container
+                .db
+                .transaction(|h| Box::pin(container.jwt_ident_usecase.login(h, command.clone())))
+                .await
+            {
+                Ok(outcome) => finish_login(&container, outcome, pending.return_to),
+                Err(e) => ApiError::from(e).into_response(),
+            }
+        }
+
+        /// 토큰이 발급되면 쿠키를 굽고 이동하고, 2 단계 인증이 필요하면 코드 입력 화면을 보여준다.
+        fn finish_login(
+            container: &AppContainer,
+            outcome: JwtLoginOutcome,
+            return_to: Option<String>,
+        ) -> axum::response::Response {
+            match outcome {
+                JwtLoginOutcome::Issued(pair) => {
+                    let return_to = return_to.unwrap_or_else(|| "/".to_string());
+                    (
+                        CookieJarJwt::build(&pair, &container.config.jwt),
+                        Redirect::to(&return_to),
+                    )
+                        .into_response()
+                }
+                JwtLoginOutcome::MfaRequired(challenge) => {
+                    render_mfa_page(container, &challenge.challenge_token, return_to, "")
+                }
+            }
+        }
+
+        fn render_mfa_page(
+            container: &AppContainer,
+            challenge_token: &str,
+            return_to: Option<String>,
+            error: &str,
+        ) -> axum::response::Response {
+            match container.template_env.render(
+                "auth/mfa.html",
+                context! {
+                    challenge_token => challenge_token,
+                    return_to => return_to,
+                    error => error,
+                },
+            ) {
+                Ok(html) => TemplateResponse(html).into_response(),
+                Err(e) => {
+                    ApiError::from(shinespark::Error::Internal(anyhow::anyhow!(e))).into_response()
+                }
+            }
+        }
+
+        async fn mfa(
+            State(container): State<Arc<AppContainer>>,
+            Form(form): Form<MfaForm>,
+        ) -> impl IntoResponse {
+            // hidden input 으로 돌아온 값이므로 다시 검사한다.
+            let return_to = sanitize_return_to(form.return_to.filter(|r| !r.is_empty()));
+            let command = MfaLoginCommand {
+                challenge_token: form.challenge_token.clone(),
+                factor: MfaFactor::Code(form.code),
+            };
+            match container
+                .db
+                .transaction(|h| {
+                    Box::pin(container.jwt_ident_usecase.complete_mfa(h, command.clone()))
+                })
+                .await
+            {
+                Ok(pair) => finish_login(&container, JwtLoginOutcome::Issued(pair), return_to),
+                // challenge 가 만료되었거나 변조된 경우 처음부터 다시 로그인
+                Err(shinespark::Error::UnAuthorized) => Redirect::to("/auth/login").into_response(),
+                Err(_) => render_mfa_page(
+                    &container,
+                    &form.challenge_token,
+                    return_to,
+                    "인증 코드가 올바르지 않습니다.",
+                ),
+            }
+        }
+
+        fn render_link_page(
+            container: &AppContainer,
+            pending: &OAuth2PendingLink,
+            error: &str,
+        ) -> axum::response::Response {
+            match container.template_env.render(
+                "auth/link.html",
+                context! {
+                    email => pending.link.email,
+                    provider => pending.link.provider.as_str(),
+                    error => error,
+                },
+            ) {
+                Ok(html) => TemplateResponse(html).into_response(),
+                Err(e) => {
+                    ApiError::from(shinespark::Error::Internal(anyhow::anyhow!(e))).into_response()
+                }
+            }
+        }
+
+        async fn link_page(
+            State(container): State<Arc<AppContainer>>,
+            session: Session,
+        ) -> impl IntoResponse {
+            match OAuth2PendingLink::load(&session).await {
+                Ok(pending) => render_link_page(&container, &pending, ""),
+                Err(_) => Redirect::to("/auth/login").into_response(),
+            }
+        }
+
+        async fn link(
+            State(container): State<Arc<AppContainer>>,
+            session: Session,
+            Form(form): Form<LinkForm>,
+        ) -> impl IntoResponse {
+            let pending = match OAuth2PendingLink::load(&session).await {
+                Ok(p) => p,
+                Err(_) => return Redirect::to("/auth/login").into_response(),
+            };
+
+            if container
+                .social_link_usecase
+                .confirm_link(
                     &mut container.db.handle(),
-                    LoginCommand::Social {
-                        provider: AuthProvider::Google,
-                        provider_uid,
+                    ConfirmSocialLinkCommand {
+                        link: pending.link.clone(),
+                        password: form.password,
                     },
                 )
                 .await
+                .is_err()
             {
-                Ok(p) => p,
-                Err(e) => return ApiError::from(e).into_response(),
-            };
+                return match pending.clone().record_failure(&session).await {
+                    Ok(true) => {
+                        render_link_page(&container, &pending, "비밀번호가 올바르지 않습니다.")
+                    }
+                    Ok(false) => Redirect::to("/auth/login").into_response(),
+                    Err(e) => ApiError::from(e).into_response(),
+                };
+            }
+            if let Err(e) = OAuth2PendingLink::clear(&session).await {
+                return ApiError::from(e).into_response();
+            }
 
-            (
-                CookieJarJwt::build(&pair, &container.config.jwt),
-                Redirect::to("/"),
-            )
-                .into_response()
+            let command = LoginCommand::Social {
+                provider: pending.link.provider,
+                provider_uid: pending.link.provider_uid,
+            };
+            match container
+                .db
+                .transaction(|h| Box::pin(container.jwt_ident_usecase.login(h, command.clone())))
+                .await
+            {
+                Ok(outcome) => finish_login(&container, outcome, pending.return_to),
+                Err(e) => ApiError::from(e).into_response(),
+            }
         }
 
         async fn logout(_jar: CookieJar) -> impl IntoResponse {
@@ -439,29 +2089,250 @@ pub mod web {
         pub fn routes() -> Router<Arc<AppContainer>> {
             Router::new()
                 .route("/auth/login", axum::routing::get(login_page).post(login))
+                .route("/auth/mfa", axum::routing::post(mfa))
                 .route(
                     "/auth/oauth2/{provider}/callback",
                     axum::routing::get(oauth2_callback),
                 )
+                .route(
+                    "/auth/oauth2/link",
+                    axum::routing::get(link_page).post(link),
+                )
                 .route("/auth/logout", axum::routing::post(logout))
         }
     }
 
+    mod impersonation {
+        use std::sync::Arc;
+
+        use axum::{
+            Form, Router,
+            extract::State,
+            http::HeaderMap,
+            response::{IntoResponse, Redirect, Response},
+        };
+        use axum_extra::extract::cookie::CookieJar;
+        use minijinja::Value;
+        use serde::Deserialize;
+        use shinespark_identity::{
+            infra::JwtClaims,
+            usecases::{FindUserQuery, ImpersonateCommand},
+        };
+
+        use crate::{
+            AppContainer,
+            http::{
+                ApiError,
+                cookie_jwt::{CookieJarJwt, CookieJwtUser},
+            },
+        };
+
+        #[derive(Deserialize)]
+        pub struct ImpersonateForm {
+            pub target_uid: uuid::Uuid,
+            pub reason: String,
+        }
+
+        fn user_agent(headers: &HeaderMap) -> Option<String> {
+            headers
+                .get(axum::http::header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        }
+
+        /// 대리 로그인 중이면 base.html 배너에 보여줄 대상/관리자 email
+        pub async fn banner(container: &AppContainer, claims: &JwtClaims) -> Option<Value> {
+            let actor = claims.act.as_ref()?;
+            let mut handle = container.db.handle();
+            let mut email = async |sub: &str| {
+                let uid = uuid::Uuid::parse_str(sub).ok()?;
+                let user = container
+                    .user_usecase
+                    .find_user(&mut handle, FindUserQuery::new().uid(uid))
+                    .await
+                    .ok()??;
+                Some(user.user.email)
+            };
+            let target_email = email(&claims.sub).await;
+            let actor_email = email(&actor.sub).await;
+            Some(minijinja::context! { target_email, actor_email })
+        }
+
+        async fn start(
+            State(container): State<Arc<AppContainer>>,
+            user: CookieJwtUser,
+            headers: HeaderMap,
+            Form(form): Form<ImpersonateForm>,
+        ) -> Response {
+            if user.0.is_impersonated() {
+                return ApiError::from(shinespark::Error::IllegalState(
+                    "not allowed while impersonating".into(),
+                ))
+                .into_response();
+            }
+            match container
+                .impersonation_usecase
+                .impersonate(
+                    &mut container.db.handle(),
+                    &user.0,
+                    ImpersonateCommand {
+                        target_uid: form.target_uid,
+                        reason: form.reason,
+                        user_agent: user_agent(&headers),
+                    },
+                )
+                .await
+            {
+                Ok(token) => (
+                    CookieJarJwt::impersonation(
+                        &token.access_token,
+                        token.expires_in,
+                        &container.config.jwt,
+                    ),
+                    Redirect::to("/"),
+                )
+                    .into_response(),
+                Err(e) => ApiError::from(e).into_response(),
+            }
+        }
+
+        async fn stop(
+            State(container): State<Arc<AppContainer>>,
+            _user: CookieJwtUser,
+            jar: CookieJar,
+            headers: HeaderMap,
+        ) -> Response {
+            let token = jar.get("access_token").map(|c| c.value()).unwrap_or_default();
+            match container
+                .impersonation_usecase
+                .stop(&mut container.db.handle(), token, user_agent(&headers))
+                .await
+            {
+                Ok(()) => (CookieJarJwt::clear_access_token(), Redirect::to("/")).into_response(),
+                Err(e) => ApiError::from(e).into_response(),
+            }
+        }
+
+        pub fn routes() -> Router<Arc<AppContainer>> {
+            Router::new()
+                .route("/auth/impersonate", axum::routing::post(start))
+                .route("/auth/impersonation/stop", axum::routing::post(stop))
+        }
+    }
+
     async fn index(
         State(container): State<Arc<AppContainer>>,
+        user: CookieJwtUser,
     ) -> Result<TemplateResponse, ApiError> {
+        let impersonation = impersonation::banner(&container, &user.0).await;
         let html = container
             .template_env
-            .render("index.html", context! { title => "Shinespark" })
+            .render(
+                "index.html",
+                context! { title => "Shinespark", impersonation },
+            )
             .map_err(|e| shinespark::Error::Internal(anyhow::anyhow!(e)))?;
         Ok(TemplateResponse(html))
     }
 
     pub fn routes(container: Arc<AppContainer>) -> Router<Arc<AppContainer>> {
-        let protected = Router::new().route("/", axum::routing::get(index)).route_layer(
-            axum::middleware::from_fn_with_state(container, auth_middleware),
-        );
+        let protected = Router::new()
+            .route("/", axum::routing::get(index))
+            .merge(impersonation::routes())
+            .route_layer(axum::middleware::from_fn_with_state(
+                container,
+                auth_middleware,
+            ));
 
         Router::new().merge(auth::routes()).merge(protected)
     }
 }
+
+/// load balancer 와 모니터링이 호출하는 상태 확인
+pub mod health {
+    use std::sync::Arc;
+
+    use axum::{
+        Json, Router,
+        extract::State,
+        http::{StatusCode, header},
+        response::IntoResponse,
+    };
+    use shinespark::db::PoolStats;
+
+    use crate::AppContainer;
+
+    /// primary DB 에 연결할 수 있으면 200, 없으면 503 과 함께 pool, replica 상태를 반환합니다.
+    /// replica 가 내려가 있어도 primary 로 읽으므로 503 이 되지 않습니다.
+    async fn health(State(container): State<Arc<AppContainer>>) -> impl IntoResponse {
+        let database_ok = match container.db.ping().await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(error = %e, "health check failed");
+                false
+            }
+        };
+        let status = match database_ok {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        let body = serde_json::json!({
+            "status": if database_ok { "ok" } else { "unavailable" },
+            "database": {
+                "ok": database_ok,
+                "pool": container.db.pool_stats(),
+                "replicas": container.db.replica_stats(),
+            },
+        });
+        (status, Json(body))
+    }
+
+    /// Prometheus text format
+    async fn metrics(State(container): State<Arc<AppContainer>>) -> impl IntoResponse {
+        (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            render_metrics(&container.db.pool_stats()),
+        )
+    }
+
+    fn render_metrics(stats: &PoolStats) -> String {
+        format!(
+            "# HELP shinespark_db_pool_connections Open database connections.\n\
+             # TYPE shinespark_db_pool_connections gauge\n\
+             shinespark_db_pool_connections{{state=\"idle\"}} {}\n\
+             shinespark_db_pool_connections{{state=\"in_use\"}} {}\n\
+             # HELP shinespark_db_pool_max_connections Maximum database connections.\n\
+             # TYPE shinespark_db_pool_max_connections gauge\n\
+             shinespark_db_pool_max_connections {}\n\
+             # HELP shinespark_db_pool_min_connections Minimum database connections.\n\
+             # TYPE shinespark_db_pool_min_connections gauge\n\
+             shinespark_db_pool_min_connections {}\n",
+            stats.idle, stats.in_use, stats.max_connections, stats.min_connections,
+        )
+    }
+
+    pub fn routes() -> Router<Arc<AppContainer>> {
+        Router::new()
+            .route("/health", axum::routing::get(health))
+            .route("/metrics", axum::routing::get(metrics))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_render_metrics() {
+            let metrics = render_metrics(&PoolStats {
+                size: 3,
+                idle: 2,
+                in_use: 1,
+                min_connections: 0,
+                max_connections: 16,
+            });
+            assert!(metrics.contains("shinespark_db_pool_connections{state=\"idle\"} 2\n"));
+            assert!(metrics.contains("shinespark_db_pool_connections{state=\"in_use\"} 1\n"));
+            assert!(metrics.contains("shinespark_db_pool_max_connections 16\n"));
+        }
+    }
+}
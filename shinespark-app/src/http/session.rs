@@ -1,14 +1,22 @@
+use std::collections::HashMap;
 use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
 
+use crate::AppContainer;
 use crate::http::ApiError;
+use crate::http::session_store::SqlxSessionStore;
 use axum::extract::{FromRequestParts, OptionalFromRequestParts};
 use axum::http::request::Parts;
-use shinespark_identity::entities::UserAggregate;
+use shinespark::config::SessionConfig;
+use shinespark_identity::entities::{UserAggregate, UserStatus};
+use shinespark_identity::usecases::FindUserQuery;
+use tower_sessions::SessionManagerLayer;
 use tower_sessions::cookie::time::Duration;
-use tower_sessions::{MemoryStore, SessionManagerLayer};
 
 pub use tower_sessions::Session;
 
+/// 세션에는 로그인한 사용자의 uid 만 저장한다. 사용자 정보는 요청마다 `SessionUserCache` 를 거쳐 다시 읽는다.
 pub const USER_SESSION_KEY: &str = "user_session";
 
 #[derive(Debug)]
@@ -22,46 +30,141 @@ impl Deref for CurrentUser {
     }
 }
 
-impl<S> OptionalFromRequestParts<S> for CurrentUser
-where
-    S: Send + Sync,
-{
+impl OptionalFromRequestParts<Arc<AppContainer>> for CurrentUser {
     type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
-        _state: &S,
+        container: &Arc<AppContainer>,
     ) -> Result<Option<Self>, Self::Rejection> {
         let session = parts.extensions.get::<Session>().cloned().ok_or(ApiError::from(
             shinespark::Error::IllegalState(std::borrow::Cow::Borrowed("can't extract session")),
         ))?;
-        let user = session.get::<UserAggregate>(USER_SESSION_KEY).await.map_err(|e| {
+        let uid = session.get::<uuid::Uuid>(USER_SESSION_KEY).await.map_err(|e| {
             ApiError::from(shinespark::Error::Internal(
                 anyhow::anyhow!(e).context("get user from session failed"),
             ))
         })?;
+        let Some(uid) = uid else {
+            return Ok(None);
+        };
+        let user = container.session_user_cache.get_or_load(container, uid).await?;
         Ok(user.map(CurrentUser))
     }
 }
 
-impl<S> FromRequestParts<S> for CurrentUser
-where
-    S: Send + Sync,
-{
+impl FromRequestParts<Arc<AppContainer>> for CurrentUser {
     type Rejection = ApiError;
 
-    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        <CurrentUser as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
+    async fn from_request_parts(
+        parts: &mut Parts,
+        container: &Arc<AppContainer>,
+    ) -> Result<Self, Self::Rejection> {
+        <CurrentUser as OptionalFromRequestParts<_>>::from_request_parts(parts, container)
             .await?
             .ok_or(ApiError::from(shinespark::Error::UnAuthorized))
     }
 }
 
-pub fn simple_layer() -> SessionManagerLayer<MemoryStore> {
-    let session_store = MemoryStore::default();
-    let session_layer = SessionManagerLayer::new(session_store)
+/// 세션 uid 로 읽은 사용자를 짧게 보관한다.
+///
+/// 요청마다 DB 를 읽지 않으면서도 역할 변경과 계정 정지가 `ttl` 안에 기존 세션에 반영된다.
+pub struct SessionUserCache {
+    ttl: StdDuration,
+    entries: Mutex<HashMap<uuid::Uuid, (Instant, UserAggregate)>>,
+}
+
+impl SessionUserCache {
+    pub fn new(config: &SessionConfig) -> Self {
+        Self {
+            ttl: StdDuration::from_secs(config.user_cache_ttl_secs),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, uid: &uuid::Uuid) -> Option<UserAggregate> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(uid)
+            .filter(|(loaded_at, _)| loaded_at.elapsed() < self.ttl)
+            .map(|(_, user)| user.clone())
+    }
+
+    fn put(&self, user: UserAggregate) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (loaded_at, _)| loaded_at.elapsed() < self.ttl);
+        entries.insert(user.user.uid, (Instant::now(), user));
+    }
+
+    pub fn invalidate(&self, uid: &uuid::Uuid) {
+        self.entries.lock().unwrap().remove(uid);
+    }
+
+    /// 활성 사용자가 아니면 `None` 이므로 세션이 남아 있어도 로그인되지 않은 것으로 본다.
+    pub async fn get_or_load(
+        &self,
+        container: &AppContainer,
+        uid: uuid::Uuid,
+    ) -> shinespark::Result<Option<UserAggregate>> {
+        if let Some(user) = self.get(&uid) {
+            return Ok(Some(user));
+        }
+        let user = container
+            .user_usecase
+            .find_user(&mut container.db.handle(), FindUserQuery::new().uid(uid))
+            .await?
+            .filter(|user| user.user.status == UserStatus::Active);
+        if let Some(user) = &user {
+            self.put(user.clone());
+        }
+        Ok(user)
+    }
+}
+
+pub fn layer(
+    store: SqlxSessionStore,
+    config: &SessionConfig,
+) -> SessionManagerLayer<SqlxSessionStore> {
+    SessionManagerLayer::new(store)
         .with_name("SID")
-        .with_secure(false)
-        .with_expiry(tower_sessions::Expiry::OnInactivity(Duration::days(1)));
-    session_layer
+        .with_secure(config.secure_cookie)
+        // OAuth2 callback 은 provider 에서 넘어오는 cross-site 이동이므로 Strict 면 세션이 유실된다.
+        .with_same_site(tower_sessions::cookie::SameSite::Lax)
+        .with_expiry(tower_sessions::Expiry::OnInactivity(Duration::seconds(
+            config.inactivity_ttl_secs,
+        )))
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark_identity::entities::User;
+
+    use super::*;
+
+    fn user(uid: uuid::Uuid) -> UserAggregate {
+        let mut user = User::new("a".into(), "a@example.com".into(), UserStatus::Active);
+        user.uid = uid;
+        UserAggregate {
+            user,
+            role_ids: vec![],
+            identities: vec![],
+        }
+    }
+
+    #[test]
+    fn test_session_user_cache_expiry() {
+        let uid = uuid::Uuid::new_v4();
+        let cache = SessionUserCache::new(&SessionConfig::default());
+        cache.put(user(uid));
+        assert!(cache.get(&uid).is_some());
+        cache.invalidate(&uid);
+        assert!(cache.get(&uid).is_none());
+
+        let cache = SessionUserCache::new(&SessionConfig {
+            user_cache_ttl_secs: 0,
+            ..Default::default()
+        });
+        cache.put(user(uid));
+        assert!(cache.get(&uid).is_none());
+    }
 }
@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use shinespark::db::{Database, SqlStatement};
+use sqlx::FromRow;
+use tower_sessions::cookie::time::OffsetDateTime;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store::{self, ExpiredDeletion, SessionStore};
+
+#[derive(Debug)]
+enum SessionQuery {
+    Create,
+    Save,
+    Load,
+    Delete,
+    DeleteExpired,
+}
+
+impl SqlStatement for SessionQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SessionQuery::Create => shinespark::include_sql!("session_store/create.sql"),
+            SessionQuery::Save => shinespark::include_sql!("session_store/save.sql"),
+            SessionQuery::Load => shinespark::include_sql!("session_store/load.sql"),
+            SessionQuery::Delete => shinespark::include_sql!("session_store/delete.sql"),
+            SessionQuery::DeleteExpired => {
+                shinespark::include_sql!("session_store/delete_expired.sql")
+            }
+        }
+    }
+}
+
+fn backend_err(e: sqlx::Error) -> session_store::Error {
+    session_store::Error::Backend(e.to_string())
+}
+
+fn encode(record: &Record) -> session_store::Result<serde_json::Value> {
+    serde_json::to_value(&record.data).map_err(|e| session_store::Error::Encode(e.to_string()))
+}
+
+/// `shs_http_session` 테이블을 쓰는 세션 저장소
+///
+/// 만료된 세션은 `load` 에서 무시되고, `spawn_cleanup` 이 주기적으로 지운다.
+#[derive(Debug, Clone)]
+pub struct SqlxSessionStore {
+    db: Database,
+}
+
+#[derive(FromRow)]
+struct SessionRow {
+    data: serde_json::Value,
+    expiry_date: i64,
+}
+
+impl SqlxSessionStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// 만료된 세션을 `period` 마다 지우는 백그라운드 작업을 시작한다.
+    pub fn spawn_cleanup(&self, period: Duration) -> tokio::task::JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.delete_expired().await {
+                    tracing::warn!("session cleanup failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqlxSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let data = encode(record)?;
+        // id 가 겹치면 새로 만들어 다시 시도한다.
+        loop {
+            let result = SessionQuery::Create
+                .as_query()
+                .bind(record.id.to_string())
+                .bind(&data)
+                .bind(record.expiry_date.unix_timestamp())
+                .execute(&self.db.inner)
+                .await
+                .map_err(backend_err)?;
+            if result.rows_affected() == 1 {
+                return Ok(());
+            }
+            record.id = Id::default();
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        SessionQuery::Save
+            .as_query()
+            .bind(record.id.to_string())
+            .bind(encode(record)?)
+            .bind(record.expiry_date.unix_timestamp())
+            .execute(&self.db.inner)
+            .await
+            .map_err(backend_err)?;
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let row = SessionQuery::Load
+            .as_query_as::<SessionRow>()
+            .bind(session_id.to_string())
+            .fetch_optional(&self.db.inner)
+            .await
+            .map_err(backend_err)?;
+
+        row.map(|row| {
+            Ok(Record {
+                id: *session_id,
+                data: serde_json::from_value(row.data)
+                    .map_err(|e| session_store::Error::Decode(e.to_string()))?,
+                expiry_date: OffsetDateTime::from_unix_timestamp(row.expiry_date)
+                    .map_err(|e| session_store::Error::Decode(e.to_string()))?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        SessionQuery::Delete
+            .as_query()
+            .bind(session_id.to_string())
+            .execute(&self.db.inner)
+            .await
+            .map_err(backend_err)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExpiredDeletion for SqlxSessionStore {
+    async fn delete_expired(&self) -> session_store::Result<()> {
+        let result = SessionQuery::DeleteExpired
+            .as_query()
+            .execute(&self.db.inner)
+            .await
+            .map_err(backend_err)?;
+        if result.rows_affected() > 0 {
+            tracing::debug!("deleted {} expired sessions", result.rows_affected());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+    use tower_sessions::cookie::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_session_store_roundtrip() {
+        let store = SqlxSessionStore::new(Database::new_dotenv().await.unwrap());
+
+        let mut record = Record {
+            id: Id::default(),
+            data: [("user_session".to_string(), serde_json::json!("uid"))].into(),
+            expiry_date: OffsetDateTime::now_utc() + Duration::minutes(5),
+        };
+        store.create(&mut record).await.unwrap();
+        let loaded = store.load(&record.id).await.unwrap().expect("session should exist");
+        assert_eq!(loaded.data, record.data);
+
+        // 만료된 세션은 읽히지 않고 정리 대상이 된다.
+        record.expiry_date = OffsetDateTime::now_utc() - Duration::minutes(1);
+        store.save(&record).await.unwrap();
+        assert!(store.load(&record.id).await.unwrap().is_none());
+        store.delete_expired().await.unwrap();
+
+        store.delete(&record.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                SessionQuery::Create,
+                SessionQuery::Save,
+                SessionQuery::Load,
+                SessionQuery::Delete,
+                SessionQuery::DeleteExpired,
+            ],
+            |statement, check| match statement {
+                SessionQuery::Create | SessionQuery::Save => check.params(3),
+                SessionQuery::Load => check.params(1).sample("data", "{}").returns::<SessionRow>(),
+                SessionQuery::Delete => check.params(1),
+                SessionQuery::DeleteExpired => check.params(0),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
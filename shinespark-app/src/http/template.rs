@@ -17,6 +17,7 @@ impl TemplateEnv {
         #[cfg(debug_assertions)]
         let mut env = Environment::new();
         env.set_loader(path_loader(dir));
+        super::csrf::register_template_functions(&mut env);
         return Self(Inner::Dev(env));
 
         #[cfg(not(debug_assertions))]
@@ -29,6 +30,7 @@ impl TemplateEnv {
             Inner::Prod(dir) => {
                 let mut env = Environment::new();
                 env.set_loader(path_loader(dir.clone()));
+                super::csrf::register_template_functions(&mut env);
                 env.get_template(name)?.render(ctx)
             }
         }
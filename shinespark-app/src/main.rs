@@ -11,34 +11,104 @@ pub struct AppContainer {
     pub config: AppConfig,
     pub user_usecase: Arc<dyn shinespark_identity::usecases::UserUsecase>,
     pub login_usecase: Arc<dyn shinespark_identity::usecases::LoginUsecase>,
+    pub mfa_usecase: Arc<dyn shinespark_identity::usecases::MfaUsecase>,
+    pub webauthn_usecase: Arc<dyn shinespark_identity::usecases::WebAuthnUsecase>,
     pub rbac_usecase: Arc<dyn shinespark_identity::usecases::RbacUsecase>,
+    pub api_key_usecase: Arc<dyn shinespark_identity::usecases::ApiKeyUsecase>,
+    pub oauth_client_usecase: Arc<dyn shinespark_identity::usecases::OAuthClientUsecase>,
+    pub authorization_server_usecase:
+        Arc<dyn shinespark_identity::usecases::AuthorizationServerUsecase>,
+    pub impersonation_usecase: Arc<dyn shinespark_identity::usecases::ImpersonationUsecase>,
     pub jwt_ident_usecase: Arc<dyn shinespark_identity::usecases::JwtIdentUsecase>,
     pub jwt_service: Arc<dyn shinespark_identity::infra::JwtService>,
-    pub google_login_usecase: Arc<dyn shinespark_identity::usecases::SocialLoginUsecase>,
+    pub social_login_registry: Arc<shinespark_identity::infra::SocialLoginRegistry>,
+    pub social_link_usecase: Arc<dyn shinespark_identity::usecases::SocialLinkUsecase>,
+    pub session_user_cache: Arc<http::session::SessionUserCache>,
+    pub rate_limiter: Arc<http::middleware::RateLimiter>,
     pub template_env: Arc<http::template::TemplateEnv>,
 }
 
 impl AppContainer {
-    pub fn new(db: shinespark::db::Database, config: AppConfig) -> Self {
+    /// 설정으로 만들 수 없는 구성(예: 잘못된 social provider)이 있으면 `IllegalState`.
+    pub fn new(db: shinespark::db::Database, config: AppConfig) -> shinespark::Result<Self> {
         let password_service = Arc::new(shinespark::crypto::password::B64PasswordService::new());
         let user_repository = Arc::new(shinespark_identity::infra::SqlxUserRepository::new());
         let user_usecase = Arc::new(shinespark_identity::infra::DefaultUserUsecase::new(
             user_repository.clone(),
             password_service.clone(),
         ));
-        let login_usecase = Arc::new(shinespark_identity::infra::DefaultLoginUsecase::new(
+        let jwt_service = Arc::new(shinespark_identity::infra::HS256JwtService::new(
+            &config.jwt,
+        ));
+        let webauthn_usecase = Arc::new(shinespark_identity::infra::DefaultWebAuthnUsecase::new(
+            Arc::new(shinespark_identity::infra::SqlxWebAuthnRepository::new()),
             user_repository.clone(),
-            password_service.clone(),
+            &config.webauthn,
         ));
+        let mfa_usecase = Arc::new(
+            shinespark_identity::infra::DefaultMfaUsecase::new(
+                Arc::new(shinespark_identity::infra::SqlxMfaRepository::new()),
+                user_usecase.clone(),
+                password_service.clone(),
+                jwt_service.clone(),
+                &config.mfa,
+            )
+            .with_webauthn(webauthn_usecase.clone()),
+        );
+        let login_usecase = Arc::new(
+            shinespark_identity::infra::DefaultLoginUsecase::new(
+                user_repository.clone(),
+                password_service.clone(),
+            )
+            .with_mfa(mfa_usecase.clone())
+            .with_webauthn(webauthn_usecase.clone()),
+        );
         let rbac_repository = Arc::new(shinespark_identity::infra::SqlxRbacRepository::new());
         let rbac_usecase = Arc::new(shinespark_identity::infra::DefaultRbacUsecase::new(
             rbac_repository,
         ));
 
-        let jwt_service = Arc::new(shinespark_identity::infra::HS256JwtService::new(
-            &config.jwt,
+        let api_key_usecase = Arc::new(shinespark_identity::infra::DefaultApiKeyUsecase::new(
+            Arc::new(shinespark_identity::infra::SqlxApiKeyRepository::new()),
+            user_usecase.clone(),
+            rbac_usecase.clone(),
         ));
+
+        let oauth_client_repository =
+            Arc::new(shinespark_identity::infra::SqlxOAuthClientRepository::new());
+        let oauth_client_usecase =
+            Arc::new(shinespark_identity::infra::DefaultOAuthClientUsecase::new(
+                oauth_client_repository.clone(),
+                rbac_usecase.clone(),
+                jwt_service.clone(),
+                &config.jwt,
+            ));
+        let authorization_server_usecase = Arc::new(
+            shinespark_identity::infra::DefaultAuthorizationServerUsecase::new(
+                oauth_client_usecase.clone(),
+                oauth_client_repository,
+                user_usecase.clone(),
+                jwt_service.clone(),
+                Arc::new(shinespark_identity::infra::ES256IdTokenSigner::new(
+                    &config.oauth_server,
+                )?),
+                &config.oauth_server,
+                &config.jwt,
+            ),
+        );
+
         let jwt_repository = Arc::new(shinespark_identity::infra::SqlxJwtIdentRepository::new());
+        let impersonation_usecase = Arc::new(
+            shinespark_identity::infra::DefaultImpersonationUsecase::new(
+                user_usecase.clone(),
+                rbac_usecase.clone(),
+                jwt_service.clone(),
+                Arc::new(shinespark_identity::infra::SqlxAuditLogRepository::new()),
+                jwt_repository.clone(),
+                &config.impersonation,
+            ),
+        );
+
         let jwt_ident_usecase = Arc::new(shinespark_identity::infra::DefaultJwtIdentUsecase::new(
             login_usecase.clone(),
             user_usecase.clone(),
@@ -46,40 +116,140 @@ impl AppContainer {
             jwt_repository,
         ));
 
-        let google_login_usecase =
-            Arc::new(shinespark_identity::infra::DefaultGoogleLoginUsecase::new(
-                config.google_login.clone(),
+        let social_login_registry = Arc::new(
+            shinespark_identity::infra::SocialLoginRegistry::from_config(
+                &config.social_providers,
                 user_usecase.clone(),
                 login_usecase.clone(),
                 rbac_usecase.clone(),
-            ));
+            )
+            .map_err(|e| {
+                shinespark::Error::IllegalState(
+                    format!("invalid social provider config: {}", e).into(),
+                )
+            })?,
+        );
+
+        let social_link_usecase = Arc::new(shinespark_identity::infra::SocialAccountResolver::new(
+            user_usecase.clone(),
+            login_usecase.clone(),
+            rbac_usecase.clone(),
+        ));
+
+        let session_user_cache = Arc::new(http::session::SessionUserCache::new(&config.session));
+
+        let rate_limiter = Arc::new(http::middleware::RateLimiter::from_config(
+            &config.rate_limit,
+            db.clone(),
+            jwt_service.clone(),
+        ));
 
         let template_env = Arc::new(http::template::TemplateEnv::new(&config.template.dir));
 
-        Self {
+        Ok(Self {
             db,
             config,
             user_usecase,
             login_usecase,
+            mfa_usecase,
+            webauthn_usecase,
             rbac_usecase,
+            api_key_usecase,
+            oauth_client_usecase,
+            authorization_server_usecase,
+            impersonation_usecase,
             jwt_ident_usecase,
             jwt_service,
-            google_login_usecase,
+            social_login_registry,
+            social_link_usecase,
+            session_user_cache,
+            rate_limiter,
             template_env,
+        })
+    }
+}
+
+#[cfg(test)]
+impl AppContainer {
+    /// `.env` 의 DB 와 기본 설정으로 만든 container. 라우터 테스트에서 쓴다.
+    pub async fn for_test() -> Arc<Self> {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        Arc::new(Self::new(db, AppConfig::default()).unwrap())
+    }
+}
+
+/// 실행 인자. 인자가 없으면 서버를 띄운다.
+enum Command {
+    Serve,
+    /// `--migrate-only`: 마이그레이션만 적용하고 종료
+    MigrateOnly,
+    /// `--migrate-status`: 적용 상태를 출력하고, 스키마가 바이너리와 다르면 1 로 종료
+    MigrateStatus,
+}
+
+impl Command {
+    fn from_args() -> Self {
+        let arg = std::env::args().nth(1);
+        match arg.as_deref() {
+            None => Command::Serve,
+            Some("--migrate-only") => Command::MigrateOnly,
+            Some("--migrate-status") => Command::MigrateStatus,
+            Some(other) => panic!(
+                "unknown argument: {} (--migrate-only | --migrate-status)",
+                other
+            ),
         }
     }
 }
 
 #[tokio::main]
 async fn main() {
+    let command = Command::from_args();
     AppConfig::load_dotenv();
     let config = AppConfig::new().expect("failed to load config");
     shinespark::trace::init(&config.trace).expect("failed to init trace");
     let db =
         shinespark::db::Database::new(&config.database).await.expect("failed to create database");
-    let container = Arc::new(AppContainer::new(db, config));
 
-    container.rbac_usecase.load(&mut container.db.handle()).await.expect("rbac cache load failed");
+    match command {
+        Command::Serve => {}
+        Command::MigrateOnly => {
+            db.migrate().await.expect("failed to migrate database");
+            tracing::info!(
+                "database migrated: {}",
+                db.migration_status().await.unwrap()
+            );
+            return;
+        }
+        Command::MigrateStatus => {
+            let status = db.migration_status().await.expect("failed to read migration status");
+            println!("{}", status);
+            if !status.is_up_to_date() {
+                std::process::exit(1);
+            }
+            return;
+        }
+    }
+    if config.database.auto_migrate {
+        db.migrate().await.expect("failed to migrate database");
+    }
+    // 스키마가 바이너리보다 뒤처지거나 앞서 있으면 시작하지 않는다.
+    db.ensure_migrated().await.expect("refusing to start");
+
+    let container = match AppContainer::new(db, config) {
+        Ok(container) => Arc::new(container),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to start");
+            std::process::exit(1);
+        }
+    };
+
+    // 시작할 때는 방금 쓴 값이 없으므로 replica 에서 읽어도 된다.
+    container
+        .rbac_usecase
+        .load(&mut container.db.read_handle())
+        .await
+        .expect("rbac cache load failed");
 
     shinespark_identity::infra::seed_admin(
         &mut container.db.handle(),
@@ -88,10 +258,32 @@ async fn main() {
     )
     .await;
 
+    let session_store = http::session_store::SqlxSessionStore::new(container.db.clone());
+    session_store.spawn_cleanup(std::time::Duration::from_secs(
+        container.config.session.cleanup_interval_secs,
+    ));
+    container.rate_limiter.spawn_cleanup(std::time::Duration::from_secs(600));
+    container.db.spawn_replica_health_check(std::time::Duration::from_secs(
+        container.config.database.replica_health_check_secs,
+    ));
+
     let router = axum::Router::new()
         .merge(http::routes::web::routes(container.clone()))
         .merge(http::routes::identity::routes())
-        .layer(http::session::simple_layer())
+        .merge(http::routes::oauth2::routes(container.clone()))
+        .merge(http::routes::health::routes())
+        .layer(axum::middleware::from_fn_with_state(
+            container.clone(),
+            http::csrf::csrf_middleware,
+        ))
+        .layer(http::session::layer(
+            session_store,
+            &container.config.session,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            container.rate_limiter.clone(),
+            http::middleware::rate_limit_middleware,
+        ))
         .layer(TraceLayer::new_for_http())
         .layer(axum::middleware::from_fn(
             http::middleware::trace_id_middleware,
@@ -0,0 +1 @@
+DELETE FROM shs_iam_api_key WHERE prefix = ? AND user_id = ?
\ No newline at end of file
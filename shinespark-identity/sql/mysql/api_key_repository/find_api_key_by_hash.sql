@@ -0,0 +1,6 @@
+SELECT
+    id, user_id, name, prefix, key_hash, permissions, expires_at, last_used_at, created_at
+FROM
+    shs_iam_api_key
+WHERE 1=1
+    AND key_hash = ?
\ No newline at end of file
@@ -0,0 +1,5 @@
+UPDATE shs_iam_api_key SET
+    last_used_at = CURRENT_TIMESTAMP(6)
+WHERE 1=1
+    AND id = ?
+    AND (last_used_at IS NULL OR last_used_at < UTC_TIMESTAMP(6) - INTERVAL 1 MINUTE)
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_user_audit_log (user_id, action, description, ip_address, user_agent, is_success)
+VALUES (?, ?, ?, ?, ?, ?)
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_refresh_token WHERE user_uid = ?
\ No newline at end of file
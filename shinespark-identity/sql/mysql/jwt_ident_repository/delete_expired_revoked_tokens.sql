@@ -0,0 +1 @@
+DELETE FROM shs_iam_revoked_token WHERE expires_at < UTC_TIMESTAMP(6)
\ No newline at end of file
@@ -0,0 +1,6 @@
+SELECT
+    id
+FROM
+    shs_iam_revoked_token
+WHERE 1=1
+    AND token_hash = ?
\ No newline at end of file
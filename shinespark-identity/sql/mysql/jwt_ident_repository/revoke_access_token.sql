@@ -0,0 +1,3 @@
+INSERT IGNORE INTO
+    shs_iam_revoked_token (token_hash, expires_at)
+VALUES (?, ?)
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT IGNORE INTO
+    shs_iam_refresh_token (user_uid, token_hash, expires_at)
+VALUES (?, ?, ?)
\ No newline at end of file
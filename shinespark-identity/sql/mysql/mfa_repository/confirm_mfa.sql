@@ -0,0 +1,6 @@
+UPDATE shs_iam_user_mfa SET
+    confirmed_at = CURRENT_TIMESTAMP(6),
+    last_used_step = ?,
+    updated_at = CURRENT_TIMESTAMP(6)
+WHERE 1=1
+    AND user_id = ?
\ No newline at end of file
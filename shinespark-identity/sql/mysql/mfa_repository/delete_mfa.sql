@@ -0,0 +1 @@
+DELETE FROM shs_iam_user_mfa WHERE user_id = ?
\ No newline at end of file
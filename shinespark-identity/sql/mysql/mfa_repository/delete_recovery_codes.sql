@@ -0,0 +1 @@
+DELETE FROM shs_iam_mfa_recovery_code WHERE user_id = ?
\ No newline at end of file
@@ -0,0 +1,8 @@
+SELECT
+    id, user_id, code_hash, used_at, created_at
+FROM
+    shs_iam_mfa_recovery_code
+WHERE 1=1
+    AND user_id = ?
+    AND used_at IS NULL
+ORDER BY id
\ No newline at end of file
@@ -0,0 +1,8 @@
+INSERT INTO
+    shs_iam_user_mfa (user_id, secret_encrypted)
+VALUES (?, ?) AS new
+ON DUPLICATE KEY UPDATE
+    secret_encrypted = new.secret_encrypted,
+    confirmed_at = NULL,
+    last_used_step = NULL,
+    updated_at = CURRENT_TIMESTAMP(6)
\ No newline at end of file
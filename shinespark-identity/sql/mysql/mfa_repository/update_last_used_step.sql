@@ -0,0 +1,6 @@
+UPDATE shs_iam_user_mfa SET
+    last_used_step = ?,
+    updated_at = CURRENT_TIMESTAMP(6)
+WHERE 1=1
+    AND user_id = ?
+    AND (last_used_step IS NULL OR last_used_step < ?)
\ No newline at end of file
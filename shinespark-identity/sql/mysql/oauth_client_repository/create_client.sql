@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_oauth_client (client_id, client_secret_hash, name, scopes, redirect_uris)
+VALUES (?, ?, ?, ?, ?)
\ No newline at end of file
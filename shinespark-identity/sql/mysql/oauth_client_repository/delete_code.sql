@@ -0,0 +1 @@
+DELETE FROM shs_iam_oauth_authorization_code WHERE id = ?
\ No newline at end of file
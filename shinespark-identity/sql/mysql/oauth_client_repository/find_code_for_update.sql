@@ -0,0 +1,9 @@
+SELECT
+    id, code_hash, client_id, user_id, redirect_uri, scope, code_challenge, nonce,
+    expires_at, created_at
+FROM
+    shs_iam_oauth_authorization_code
+WHERE 1=1
+    AND code_hash = ?
+    AND expires_at > UTC_TIMESTAMP(6)
+FOR UPDATE
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT IGNORE INTO
+    shs_iam_role_permission (role_id, permission_id)
+VALUES (?, ?)
\ No newline at end of file
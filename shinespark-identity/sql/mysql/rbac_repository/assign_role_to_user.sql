@@ -0,0 +1,3 @@
+INSERT IGNORE INTO
+    shs_iam_user_role (user_id, role_id)
+VALUES (?, ?)
\ No newline at end of file
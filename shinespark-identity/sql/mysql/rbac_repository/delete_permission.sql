@@ -0,0 +1 @@
+DELETE FROM shs_iam_permission WHERE id = ?
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_role WHERE id = ?
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_role_permission WHERE role_id = ?
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT * FROM shs_iam_permission ORDER BY code
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT * FROM shs_iam_role ORDER BY name
\ No newline at end of file
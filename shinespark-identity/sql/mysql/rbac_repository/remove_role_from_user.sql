@@ -0,0 +1 @@
+DELETE FROM shs_iam_user_role WHERE user_id = ? AND role_id = ?
\ No newline at end of file
@@ -0,0 +1,8 @@
+INSERT INTO
+    shs_iam_user_identity (user_id, provider, provider_uid, credential_hash, credential_public_key, sign_count)
+VALUES (?, ?, ?, ?, ?, ?) AS new
+ON DUPLICATE KEY UPDATE
+    credential_hash = COALESCE(new.credential_hash, shs_iam_user_identity.credential_hash),
+    credential_public_key = COALESCE(new.credential_public_key, shs_iam_user_identity.credential_public_key),
+    sign_count = COALESCE(new.sign_count, shs_iam_user_identity.sign_count),
+    updated_at = CURRENT_TIMESTAMP(6)
\ No newline at end of file
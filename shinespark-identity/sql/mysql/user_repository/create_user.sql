@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_user (uid, name, email, status)
+VALUES (?, ?, ?, ?)
\ No newline at end of file
@@ -0,0 +1,22 @@
+SELECT
+    u.*
+    ,(  SELECT COALESCE(JSON_ARRAYAGG(role_id), JSON_ARRAY())
+        FROM shs_iam_user_role r
+        WHERE r.user_id = u.id
+    ) as role_ids
+    ,(  SELECT COALESCE(JSON_ARRAYAGG(JSON_OBJECT(
+            'id', i.id,
+            'user_id', i.user_id,
+            'provider', i.provider,
+            'provider_uid', i.provider_uid,
+            'credential_hash', i.credential_hash,
+            'credential_public_key', i.credential_public_key,
+            'sign_count', i.sign_count,
+            'created_at', DATE_FORMAT(i.created_at, '%Y-%m-%dT%H:%i:%s.%fZ'),
+            'updated_at', DATE_FORMAT(i.updated_at, '%Y-%m-%dT%H:%i:%s.%fZ')
+        )), JSON_ARRAY())
+        FROM shs_iam_user_identity i
+        WHERE i.user_id = u.id
+    ) as identities
+FROM shs_iam_user u
+WHERE 1 = 1
\ No newline at end of file
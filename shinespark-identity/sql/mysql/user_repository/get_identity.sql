@@ -0,0 +1,8 @@
+SELECT
+    *
+FROM
+    shs_iam_user_identity
+WHERE 1=1
+    AND user_id = ?
+    AND provider = ?
+    AND provider_uid = ?
\ No newline at end of file
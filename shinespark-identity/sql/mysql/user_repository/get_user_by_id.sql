@@ -0,0 +1 @@
+SELECT * FROM shs_iam_user WHERE id = ?
\ No newline at end of file
@@ -0,0 +1,5 @@
+UPDATE shs_iam_user_identity SET
+    sign_count = ?,
+    updated_at = CURRENT_TIMESTAMP
+WHERE 1=1
+    AND id = ?
\ No newline at end of file
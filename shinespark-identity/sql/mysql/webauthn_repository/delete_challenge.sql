@@ -0,0 +1 @@
+DELETE FROM shs_iam_webauthn_challenge WHERE id = ?
\ No newline at end of file
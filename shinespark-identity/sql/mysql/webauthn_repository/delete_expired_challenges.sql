@@ -0,0 +1 @@
+DELETE FROM shs_iam_webauthn_challenge WHERE expires_at < UTC_TIMESTAMP(6)
\ No newline at end of file
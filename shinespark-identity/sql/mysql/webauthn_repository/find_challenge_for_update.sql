@@ -0,0 +1,9 @@
+SELECT
+    id, challenge, user_id, ceremony, expires_at, created_at
+FROM
+    shs_iam_webauthn_challenge
+WHERE 1=1
+    AND challenge = ?
+    AND ceremony = ?
+    AND expires_at > UTC_TIMESTAMP(6)
+FOR UPDATE
\ No newline at end of file
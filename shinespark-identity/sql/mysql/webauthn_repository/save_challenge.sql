@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_webauthn_challenge (challenge, user_id, ceremony, expires_at)
+VALUES (?, ?, ?, ?)
\ No newline at end of file
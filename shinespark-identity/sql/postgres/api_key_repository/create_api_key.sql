@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_api_key (user_id, name, prefix, key_hash, permissions, expires_at)
+VALUES ($1, $2, $3, $4, $5, $6)
\ No newline at end of file
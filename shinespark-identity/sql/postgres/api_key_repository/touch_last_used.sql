@@ -0,0 +1,5 @@
+UPDATE shs_iam_api_key SET
+    last_used_at = NOW()
+WHERE 1=1
+    AND id = $1
+    AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_user_audit_log (user_id, action, description, ip_address, user_agent, is_success)
+VALUES ($1, $2, $3, $4, $5, $6)
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_revoked_token WHERE expires_at < NOW()
\ No newline at end of file
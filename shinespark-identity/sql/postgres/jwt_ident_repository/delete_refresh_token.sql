@@ -0,0 +1 @@
+DELETE FROM shs_iam_refresh_token WHERE token_hash = $1
\ No newline at end of file
@@ -0,0 +1,4 @@
+INSERT INTO
+    shs_iam_revoked_token (token_hash, expires_at)
+VALUES ($1, $2)
+ON CONFLICT (token_hash) DO NOTHING
\ No newline at end of file
@@ -0,0 +1,8 @@
+INSERT INTO
+    shs_iam_user_mfa (user_id, secret_encrypted)
+VALUES ($1, $2)
+ON CONFLICT (user_id) DO UPDATE SET
+    secret_encrypted = EXCLUDED.secret_encrypted,
+    confirmed_at = NULL,
+    last_used_step = NULL,
+    updated_at = CURRENT_TIMESTAMP
\ No newline at end of file
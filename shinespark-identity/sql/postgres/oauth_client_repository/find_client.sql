@@ -0,0 +1,6 @@
+SELECT
+    id, client_id, client_secret_hash, name, scopes, redirect_uris, created_at
+FROM
+    shs_iam_oauth_client
+WHERE 1=1
+    AND client_id = $1
\ No newline at end of file
@@ -0,0 +1 @@
+INSERT INTO shs_iam_role (name, description) VALUES ($1, $2)
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_role_permission WHERE permission_id = $1
\ No newline at end of file
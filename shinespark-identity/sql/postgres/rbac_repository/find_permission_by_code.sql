@@ -0,0 +1 @@
+SELECT * FROM shs_iam_permission WHERE code = $1
\ No newline at end of file
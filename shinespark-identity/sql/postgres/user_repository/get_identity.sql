@@ -0,0 +1,8 @@
+SELECT
+    *
+FROM
+    shs_iam_user_identity
+WHERE 1=1
+    AND user_id = $1
+    AND provider = $2
+    AND provider_uid = $3
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_api_key WHERE prefix = $1 AND user_id = $2
\ No newline at end of file
@@ -0,0 +1,7 @@
+SELECT
+    id, user_id, name, prefix, key_hash, permissions, expires_at, last_used_at, created_at
+FROM
+    shs_iam_api_key
+WHERE 1=1
+    AND user_id = $1
+ORDER BY id
\ No newline at end of file
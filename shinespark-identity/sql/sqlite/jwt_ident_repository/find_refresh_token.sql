@@ -0,0 +1,7 @@
+SELECT
+    id, user_uid, token_hash, expires_at, created_at
+FROM
+    shs_iam_refresh_token
+WHERE 1=1
+    AND token_hash = $1
+    AND julianday(expires_at) > julianday('now')
\ No newline at end of file
@@ -0,0 +1,6 @@
+SELECT
+    id, user_id, secret_encrypted, confirmed_at, last_used_step, created_at, updated_at
+FROM
+    shs_iam_user_mfa
+WHERE 1=1
+    AND user_id = $1
\ No newline at end of file
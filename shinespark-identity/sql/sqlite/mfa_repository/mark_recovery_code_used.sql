@@ -0,0 +1,5 @@
+UPDATE shs_iam_mfa_recovery_code SET
+    used_at = CURRENT_TIMESTAMP
+WHERE 1=1
+    AND id = $1
+    AND used_at IS NULL
\ No newline at end of file
@@ -0,0 +1,6 @@
+UPDATE shs_iam_user_mfa SET
+    last_used_step = $1,
+    updated_at = CURRENT_TIMESTAMP
+WHERE 1=1
+    AND user_id = $2
+    AND (last_used_step IS NULL OR last_used_step < $3)
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_oauth_client (client_id, client_secret_hash, name, scopes, redirect_uris)
+VALUES ($1, $2, $3, $4, $5)
\ No newline at end of file
@@ -0,0 +1,4 @@
+INSERT INTO
+    shs_iam_oauth_authorization_code
+    (code_hash, client_id, user_id, redirect_uri, scope, code_challenge, nonce, expires_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
\ No newline at end of file
@@ -0,0 +1,7 @@
+DELETE FROM shs_iam_oauth_authorization_code
+WHERE 1=1
+    AND code_hash = $1
+    AND julianday(expires_at) > julianday('now')
+RETURNING
+    id, code_hash, client_id, user_id, redirect_uri, scope, code_challenge, nonce,
+    expires_at, created_at
\ No newline at end of file
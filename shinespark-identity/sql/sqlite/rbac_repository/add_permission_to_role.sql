@@ -0,0 +1,4 @@
+INSERT INTO
+    shs_iam_role_permission (role_id, permission_id)
+VALUES ($1, $2)
+ON CONFLICT (role_id, permission_id) DO NOTHING
\ No newline at end of file
@@ -0,0 +1,4 @@
+INSERT INTO
+    shs_iam_user_role (user_id, role_id)
+VALUES ($1, $2)
+ON CONFLICT (user_id, role_id) DO NOTHING
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_permission WHERE id = $1
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT * FROM shs_iam_role WHERE name = $1
\ No newline at end of file
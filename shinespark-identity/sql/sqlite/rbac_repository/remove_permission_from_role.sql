@@ -0,0 +1 @@
+DELETE FROM shs_iam_role_permission WHERE role_id = $1 AND permission_id = $2
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_user_role WHERE user_id = $1 AND role_id = $2
\ No newline at end of file
@@ -0,0 +1,8 @@
+INSERT INTO
+    shs_iam_user_identity (user_id, provider, provider_uid, credential_hash, credential_public_key, sign_count)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (user_id, provider, provider_uid) DO UPDATE SET
+    credential_hash = COALESCE(EXCLUDED.credential_hash, shs_iam_user_identity.credential_hash),
+    credential_public_key = COALESCE(EXCLUDED.credential_public_key, shs_iam_user_identity.credential_public_key),
+    sign_count = COALESCE(EXCLUDED.sign_count, shs_iam_user_identity.sign_count),
+    updated_at = CURRENT_TIMESTAMP
\ No newline at end of file
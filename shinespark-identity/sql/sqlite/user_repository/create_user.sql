@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_user (uid, name, email, status)
+VALUES ($1, $2, $3, $4)
\ No newline at end of file
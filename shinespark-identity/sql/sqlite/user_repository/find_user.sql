@@ -0,0 +1,22 @@
+SELECT
+    u.*
+    ,(  SELECT json_group_array(role_id)
+        FROM shs_iam_user_role r
+        WHERE r.user_id = u.id
+    ) as role_ids
+    ,(  SELECT json_group_array(json_object(
+            'id', i.id,
+            'user_id', i.user_id,
+            'provider', i.provider,
+            'provider_uid', i.provider_uid,
+            'credential_hash', i.credential_hash,
+            'credential_public_key', i.credential_public_key,
+            'sign_count', i.sign_count,
+            'created_at', strftime('%Y-%m-%dT%H:%M:%fZ', i.created_at),
+            'updated_at', strftime('%Y-%m-%dT%H:%M:%fZ', i.updated_at)
+        ))
+        FROM shs_iam_user_identity i
+        WHERE i.user_id = u.id
+    ) as identities
+FROM shs_iam_user u
+WHERE 1 = 1
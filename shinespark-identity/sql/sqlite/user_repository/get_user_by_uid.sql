@@ -0,0 +1 @@
+SELECT * FROM shs_iam_user WHERE uid = $1
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM shs_iam_webauthn_challenge WHERE julianday(expires_at) < julianday('now')
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO
+    shs_iam_webauthn_challenge (challenge, user_id, ceremony, expires_at)
+VALUES ($1, $2, $3, $4)
\ No newline at end of file
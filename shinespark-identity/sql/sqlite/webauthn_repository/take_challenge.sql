@@ -0,0 +1,8 @@
+DELETE FROM
+    shs_iam_webauthn_challenge
+WHERE 1=1
+    AND challenge = $1
+    AND ceremony = $2
+    AND julianday(expires_at) > julianday('now')
+RETURNING
+    id, challenge, user_id, ceremony, expires_at, created_at
\ No newline at end of file
@@ -2,8 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
-#[sqlx(type_name = "text", rename_all = "lowercase")] // DB 타입이 문자열임을 명시
+// DB 에는 `as_str()` 문자열로 저장된다. mysql 은 derive 된 sqlx::Type 이 ENUM 컬럼만 받으므로
+// AuthProvider 처럼 String 으로 위임한다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum UserStatus {
     Active,
@@ -13,13 +14,15 @@ pub enum UserStatus {
     Deleted,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
-#[sqlx(type_name = "text", rename_all = "lowercase")] // DB 타입이 문자열임을 명시
-#[serde(rename_all = "lowercase")]
+// 설정으로 추가되는 provider 는 `Other` 로 표현된다. DB / JSON 에는 `as_str()` 문자열로 저장된다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
 pub enum AuthProvider {
     Local,
     Google,
     Apple,
+    Passkey, // WebAuthn credential. provider_uid 는 credential id (base64url)
+    Other(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
@@ -31,6 +34,8 @@ pub enum UserAction {
     StatusChanged,
     CredentialUpdated,
     ProfileUpdated,
+    ImpersonationStarted, // 관리자가 이 사용자로 대리 로그인 시작
+    ImpersonationStopped,
 }
 
 // 시스템의 핵심 식별 주체인 사용자 정보입니다.
@@ -63,13 +68,18 @@ impl User {
 // 사용자의 인증 수단 및 자격 증명(Credential) 정보입니다. (다중 플랫폼 로그인 지원)
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct UserIdentity {
-    pub id: i64,                         // 데이터베이스 내부 식별용 PK
-    pub user_id: i64,                    // 연관된 `User`의 PK (FK)
-    pub provider: AuthProvider,          // 해당 인증의 제공자 (Local, Google, Apple 등)
+    pub id: i64,                // 데이터베이스 내부 식별용 PK
+    pub user_id: i64,           // 연관된 `User`의 PK (FK)
+    pub provider: AuthProvider, // 해당 인증의 제공자 (Local, Google, Apple 등)
     pub provider_uid: String, // 인증 제공자 측의 고유 식별자 (소셜 로그인의 경우 해당 플랫폼의 사용자 ID)
+    #[serde(skip_serializing)]
     pub credential_hash: Option<String>, // (Local 인증 전용) 암호화된 비밀번호 해시값. 소셜 로그인 등 비밀번호가 없는 경우 None.
-    pub created_at: DateTime<Utc>,       // 연동 정보 등록 일시
-    pub updated_at: DateTime<Utc>,       // 연동 정보 상태 변경 일시
+    #[serde(default)]
+    pub credential_public_key: Option<String>, // (Passkey 전용) COSE 형식 공개키 (base64url)
+    #[serde(default)]
+    pub sign_count: Option<i64>, // (Passkey 전용) authenticator 의 서명 카운터 (복제 탐지)
+    pub created_at: DateTime<Utc>, // 연동 정보 등록 일시
+    pub updated_at: DateTime<Utc>, // 연동 정보 상태 변경 일시
 }
 
 impl UserIdentity {
@@ -85,10 +95,25 @@ impl UserIdentity {
             provider,
             provider_uid,
             credential_hash,
+            credential_public_key: None,
+            sign_count: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
     }
+
+    pub fn passkey(
+        user_id: i64,
+        credential_id: String,
+        public_key: String,
+        sign_count: i64,
+    ) -> Self {
+        Self {
+            credential_public_key: Some(public_key),
+            sign_count: Some(sign_count),
+            ..Self::new(user_id, AuthProvider::Passkey, credential_id, None)
+        }
+    }
 }
 
 // 사용자와 관련된 핵심 액션(로그인, 상태 변경 등)의 이력을 남기는 감사 로그입니다.
@@ -104,6 +129,117 @@ pub struct UserAuditLog {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebAuthnCeremony {
+    Registration,
+    Authentication,
+}
+
+// WebAuthn 등록/인증 ceremony 에 발급한 1회용 challenge 입니다.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebAuthnChallenge {
+    pub id: i64,
+    pub challenge: String, // base64url challenge (clientDataJSON 의 challenge 와 비교)
+    pub user_id: Option<i64>, // 대상 사용자. passwordless 로그인은 사용자를 모르므로 None
+    pub ceremony: WebAuthnCeremony,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 사용자의 TOTP 2단계 인증 정보입니다.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserMfa {
+    pub id: i64,
+    pub user_id: i64,
+    pub secret_encrypted: String, // 암호화된 TOTP secret (평문은 저장하지 않음)
+    pub confirmed_at: Option<DateTime<Utc>>, // 등록 확인 일시. None 이면 등록 진행 중
+    pub last_used_step: Option<i64>, // 마지막으로 사용된 TOTP step (재사용 방지)
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserMfa {
+    pub fn is_enabled(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+// TOTP 기기를 잃어버린 경우 사용하는 1회용 복구 코드입니다.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MfaRecoveryCode {
+    pub id: i64,
+    pub user_id: i64,
+    pub code_hash: String, // PasswordService 로 해시한 복구 코드
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 스크립트, CI 등 machine client 용 API key 입니다. 평문 키는 발급 시 한 번만 보여준다.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub prefix: String, // 키를 구분하기 위한 공개 prefix
+    #[serde(skip)]
+    pub key_hash: String, // 전체 키의 sha256 (hex)
+    pub permissions: String, // 공백으로 구분된 권한 코드
+    pub expires_at: Option<DateTime<Utc>>, // None 이면 만료 없음
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn permission_list(&self) -> Vec<String> {
+        self.permissions.split_whitespace().map(str::to_string).collect()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+// shinespark 에서 토큰을 발급받는 등록된 OAuth2 client 입니다.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct OAuthClient {
+    pub id: i64,
+    pub client_id: String,
+    #[serde(skip)]
+    pub client_secret_hash: String, // client secret 의 sha256 (hex)
+    pub name: String,
+    pub scopes: String, // 공백으로 구분된 허용 scope (권한 코드, OIDC scope)
+    pub redirect_uris: String, // 공백으로 구분된 허용 redirect_uri
+    pub created_at: DateTime<Utc>,
+}
+
+impl OAuthClient {
+    pub fn scope_list(&self) -> Vec<String> {
+        self.scopes.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// 등록된 값과 정확히 일치해야 한다. (prefix, wildcard 허용 안 함)
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.split_whitespace().any(|uri| uri == redirect_uri)
+    }
+}
+
+// authorization_code grant 의 1회용 code 입니다.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OAuthAuthorizationCode {
+    pub id: i64,
+    pub code_hash: String, // code 의 sha256 (hex)
+    pub client_id: String,
+    pub user_id: i64,
+    pub redirect_uri: String,
+    pub scope: String,          // 공백으로 구분된 승인된 scope
+    pub code_challenge: String, // PKCE S256
+    pub nonce: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Permission {
     pub id: i64,
@@ -183,14 +319,56 @@ impl TryFrom<String> for UserStatus {
     }
 }
 
+impl<DB: sqlx::Database> sqlx::Type<DB> for UserStatus
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'r, DB: sqlx::Database> sqlx::Decode<'r, DB> for UserStatus
+where
+    String: sqlx::Decode<'r, DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let value = <String as sqlx::Decode<'r, DB>>::decode(value)?;
+        Ok(Self::try_from(value)?)
+    }
+}
+
+impl<'q, DB: sqlx::Database> sqlx::Encode<'q, DB> for UserStatus
+where
+    String: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut DB::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <String as sqlx::Encode<'q, DB>>::encode_by_ref(&self.as_str().to_string(), buf)
+    }
+}
+
 impl AuthProvider {
     pub fn as_str(&self) -> &str {
         match self {
             Self::Local => "local",
             Self::Google => "google",
             Self::Apple => "apple",
+            Self::Passkey => "passkey",
+            Self::Other(name) => name.as_str(),
         }
     }
+
+    /// 외부 provider 로 로그인하는 identity 인지. local, passkey 는 자격 증명을 직접 보관한다.
+    pub fn is_social(&self) -> bool {
+        !matches!(self, Self::Local | Self::Passkey)
+    }
 }
 
 impl TryFrom<String> for AuthProvider {
@@ -200,6 +378,14 @@ impl TryFrom<String> for AuthProvider {
             "local" => Ok(Self::Local),
             "google" => Ok(Self::Google),
             "apple" => Ok(Self::Apple),
+            "passkey" => Ok(Self::Passkey),
+            name if !name.is_empty()
+                && name.chars().all(|c| {
+                    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'
+                }) =>
+            {
+                Ok(Self::Other(value))
+            }
             _ => Err(shinespark::Error::IllegalState(
                 format!("Invalid auth provider: {}", value).into(),
             )),
@@ -207,6 +393,50 @@ impl TryFrom<String> for AuthProvider {
     }
 }
 
+impl From<AuthProvider> for String {
+    fn from(value: AuthProvider) -> Self {
+        match value {
+            AuthProvider::Other(name) => name,
+            provider => provider.as_str().to_string(),
+        }
+    }
+}
+
+impl<DB: sqlx::Database> sqlx::Type<DB> for AuthProvider
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'r, DB: sqlx::Database> sqlx::Decode<'r, DB> for AuthProvider
+where
+    String: sqlx::Decode<'r, DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let value = <String as sqlx::Decode<'r, DB>>::decode(value)?;
+        Ok(Self::try_from(value)?)
+    }
+}
+
+impl<'q, DB: sqlx::Database> sqlx::Encode<'q, DB> for AuthProvider
+where
+    String: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut DB::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <String as sqlx::Encode<'q, DB>>::encode_by_ref(&self.as_str().to_string(), buf)
+    }
+}
+
 impl UserAction {
     pub fn as_str(&self) -> &str {
         match self {
@@ -215,6 +445,8 @@ impl UserAction {
             Self::StatusChanged => "status_changed",
             Self::CredentialUpdated => "credential_updated",
             Self::ProfileUpdated => "profile_updated",
+            Self::ImpersonationStarted => "impersonation_started",
+            Self::ImpersonationStopped => "impersonation_stopped",
         }
     }
 }
@@ -228,6 +460,8 @@ impl TryFrom<String> for UserAction {
             "status_changed" => Ok(Self::StatusChanged),
             "credential_updated" => Ok(Self::CredentialUpdated),
             "profile_updated" => Ok(Self::ProfileUpdated),
+            "impersonation_started" => Ok(Self::ImpersonationStarted),
+            "impersonation_stopped" => Ok(Self::ImpersonationStopped),
             _ => Err(shinespark::Error::IllegalState(
                 format!("Invalid user action: {}", value).into(),
             )),
@@ -1,23 +1,58 @@
+mod default_api_key_usecase;
+mod default_authorization_server_usecase;
 mod default_google_login_usecase;
+mod default_impersonation_usecase;
 mod default_jwt_ident_usecase;
 mod default_login_usecase;
+mod default_mfa_usecase;
+mod default_oauth_client_usecase;
+mod default_oidc_login_usecase;
 mod default_rbac_usecase;
 mod default_user_usecase;
+mod default_webauthn_usecase;
+mod id_token_signer;
 pub mod jwt_service;
 mod mock_user_repository;
 mod seed_user;
+mod social_account_resolver;
+mod social_login_registry;
+mod sqlx_api_key_repository;
+mod sqlx_audit_log_repository;
 mod sqlx_jwt_ident_repository;
+mod sqlx_mfa_repository;
+mod sqlx_oauth_client_repository;
 mod sqlx_rbac_repository;
 mod sqlx_user_repository;
+mod sqlx_webauthn_repository;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod webauthn;
 
+pub use default_api_key_usecase::*;
+pub use default_authorization_server_usecase::*;
 pub use default_google_login_usecase::*;
+pub use default_impersonation_usecase::*;
 pub use default_jwt_ident_usecase::*;
 pub use default_login_usecase::*;
+pub use default_mfa_usecase::*;
+pub use default_oauth_client_usecase::*;
+pub use default_oidc_login_usecase::*;
 pub use default_rbac_usecase::*;
 pub use default_user_usecase::*;
-pub use jwt_service::{HS256JwtService, JwtClaims, JwtService, JwtTokenPair};
+pub use default_webauthn_usecase::*;
+pub use id_token_signer::*;
+pub use jwt_service::{
+    ActorClaim, HS256JwtService, IdTokenClaims, JwtClaims, JwtService, JwtTokenPair, SubjectType,
+};
 pub use mock_user_repository::*;
 pub use seed_user::*;
+pub use social_account_resolver::*;
+pub use social_login_registry::*;
+pub use sqlx_api_key_repository::*;
+pub use sqlx_audit_log_repository::*;
 pub use sqlx_jwt_ident_repository::*;
+pub use sqlx_mfa_repository::*;
+pub use sqlx_oauth_client_repository::*;
 pub use sqlx_rbac_repository::*;
 pub use sqlx_user_repository::*;
+pub use sqlx_webauthn_repository::*;
@@ -0,0 +1,395 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use shinespark::crypto::{pkce, totp};
+
+use super::default_jwt_ident_usecase::sha256_hex;
+use crate::entities::{ApiKey, UserStatus};
+use crate::infra::jwt_service::{JwtClaims, SubjectType};
+use crate::repositories::{ApiKeyRepository, NewApiKey};
+use crate::usecases::{
+    ApiKeyUsecase, CreateApiKeyCommand, FindUserQuery, IssuedApiKey, RbacUsecase, UserUsecase,
+};
+
+const API_KEY_PREFIX: &str = "shs_";
+const API_KEY_TOKEN_TYPE: &str = "api_key";
+
+pub struct DefaultApiKeyUsecase {
+    api_key_repository: Arc<dyn ApiKeyRepository>,
+    user_usecase: Arc<dyn UserUsecase>,
+    rbac_usecase: Arc<dyn RbacUsecase>,
+}
+
+impl DefaultApiKeyUsecase {
+    pub fn new(
+        api_key_repository: Arc<dyn ApiKeyRepository>,
+        user_usecase: Arc<dyn UserUsecase>,
+        rbac_usecase: Arc<dyn RbacUsecase>,
+    ) -> Self {
+        Self {
+            api_key_repository,
+            user_usecase,
+            rbac_usecase,
+        }
+    }
+}
+
+/// `shs_<prefix>.<secret>` 형태의 키와 prefix. prefix 는 목록에서 키를 구분하는 용도로만 쓴다.
+fn generate_api_key() -> (String, String) {
+    let prefix = format!(
+        "{}{}",
+        API_KEY_PREFIX,
+        &totp::encode_secret(&totp::generate_secret()).to_lowercase()[..12]
+    );
+    let key = format!("{}.{}", prefix, pkce::generate_code_verifier());
+    (prefix, key)
+}
+
+#[async_trait::async_trait]
+impl ApiKeyUsecase for DefaultApiKeyUsecase {
+    async fn create_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+        command: CreateApiKeyCommand,
+    ) -> shinespark::Result<IssuedApiKey> {
+        if command.name.trim().is_empty() {
+            return Err(shinespark::Error::IllegalState(
+                "api key name is empty".into(),
+            ));
+        }
+        if command.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(shinespark::Error::IllegalState(
+                "expires_at is in the past".into(),
+            ));
+        }
+        // 발급 요청에 쓰인 토큰이 가진 권한만 위임할 수 있다.
+        if let Some(p) = command.permissions.iter().find(|p| {
+            p.contains(char::is_whitespace) || !claims.has_perm(self.rbac_usecase.as_ref(), p)
+        }) {
+            tracing::warn!("api key permission not granted: {}", p);
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        let uid =
+            uuid::Uuid::parse_str(&claims.sub).map_err(|_| shinespark::Error::UnAuthorized)?;
+        let user = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().uid(uid))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+
+        let (prefix, key) = generate_api_key();
+        let api_key = self
+            .api_key_repository
+            .create_api_key(
+                handle,
+                NewApiKey {
+                    user_id: user.user.id,
+                    name: command.name,
+                    prefix,
+                    key_hash: sha256_hex(&key),
+                    permissions: command.permissions.join(" "),
+                    expires_at: command.expires_at,
+                },
+            )
+            .await?;
+        Ok(IssuedApiKey { api_key, key })
+    }
+
+    async fn list_api_keys(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<ApiKey>> {
+        self.api_key_repository.list_api_keys(handle, user_id).await
+    }
+
+    async fn revoke_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        prefix: &str,
+    ) -> shinespark::Result<()> {
+        if !self.api_key_repository.delete_api_key(handle, user_id, prefix).await? {
+            return Err(shinespark::Error::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn authenticate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        key: &str,
+    ) -> shinespark::Result<JwtClaims> {
+        if !key.starts_with(API_KEY_PREFIX) {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        let api_key = self
+            .api_key_repository
+            .find_api_key_by_hash(handle, &sha256_hex(key))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        if api_key.is_expired(Utc::now()) {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        // roles 는 발급 시점이 아니라 현재 사용자 기준이다.
+        let user = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().id(api_key.user_id))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        if user.user.status != UserStatus::Active {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        self.api_key_repository.touch_last_used(handle, api_key.id).await?;
+
+        Ok(JwtClaims {
+            sub: user.user.uid.to_string(),
+            sub_type: SubjectType::User,
+            // 만료가 없는 키는 usize::MAX
+            exp: api_key.expires_at.map_or(usize::MAX, |e| e.timestamp() as usize),
+            roles: Some(user.role_ids),
+            token_type: API_KEY_TOKEN_TYPE.to_string(),
+            permissions: Some(api_key.permission_list()),
+            client_id: None,
+            act: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::Duration;
+    use shinespark::crypto::password::B64PasswordService;
+
+    use super::*;
+    use crate::entities::UserAggregate;
+    use crate::infra::testing::{MockRbacUsecase, access_claims, mock_handle};
+    use crate::infra::{DefaultUserUsecase, MockUserRepository};
+    use crate::usecases::{CreateUserCommand, InitialCredentials, UpdateUserCommand};
+
+    #[derive(Default)]
+    struct MockApiKeyRepository {
+        keys: Mutex<Vec<ApiKey>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ApiKeyRepository for MockApiKeyRepository {
+        async fn create_api_key(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            new_key: NewApiKey,
+        ) -> shinespark::Result<ApiKey> {
+            let mut keys = self.keys.lock().unwrap();
+            let row = ApiKey {
+                id: keys.len() as i64 + 1,
+                user_id: new_key.user_id,
+                name: new_key.name,
+                prefix: new_key.prefix,
+                key_hash: new_key.key_hash,
+                permissions: new_key.permissions,
+                expires_at: new_key.expires_at,
+                last_used_at: None,
+                created_at: Utc::now(),
+            };
+            keys.push(row.clone());
+            Ok(row)
+        }
+
+        async fn find_api_key_by_hash(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            key_hash: &str,
+        ) -> shinespark::Result<Option<ApiKey>> {
+            Ok(self.keys.lock().unwrap().iter().find(|k| k.key_hash == key_hash).cloned())
+        }
+
+        async fn list_api_keys(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+        ) -> shinespark::Result<Vec<ApiKey>> {
+            Ok(
+                self.keys
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|k| k.user_id == user_id)
+                    .cloned()
+                    .collect(),
+            )
+        }
+
+        async fn delete_api_key(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+            prefix: &str,
+        ) -> shinespark::Result<bool> {
+            let mut keys = self.keys.lock().unwrap();
+            let before = keys.len();
+            keys.retain(|k| !(k.prefix == prefix && k.user_id == user_id));
+            Ok(keys.len() < before)
+        }
+
+        async fn touch_last_used(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            id: i64,
+        ) -> shinespark::Result<()> {
+            if let Some(k) = self.keys.lock().unwrap().iter_mut().find(|k| k.id == id) {
+                k.last_used_at = Some(Utc::now());
+            }
+            Ok(())
+        }
+    }
+
+    async fn setup(
+        handle: &mut shinespark::db::Handle<'_>,
+    ) -> (DefaultApiKeyUsecase, Arc<dyn UserUsecase>, UserAggregate) {
+        let user_usecase: Arc<dyn UserUsecase> = Arc::new(DefaultUserUsecase::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(B64PasswordService::new()),
+        ));
+        let usecase = DefaultApiKeyUsecase::new(
+            Arc::new(MockApiKeyRepository::default()),
+            user_usecase.clone(),
+            Arc::new(user_rbac()),
+        );
+        let created = user_usecase
+            .create_user(
+                handle,
+                CreateUserCommand {
+                    name: "ci".to_string(),
+                    email: "ci@example.com".to_string(),
+                    credentials: InitialCredentials::Local {
+                        password: "pw".to_string(),
+                    },
+                    status: UserStatus::Active,
+                },
+            )
+            .await
+            .unwrap();
+        let user = UserAggregate {
+            user: created.user,
+            role_ids: vec![1],
+            identities: created.identities,
+        };
+        (usecase, user_usecase, user)
+    }
+
+    /// role 1 은 `user.read.own`, `user.update.own` 권한을 갖는다.
+    fn user_rbac() -> MockRbacUsecase {
+        MockRbacUsecase::new(&[(1, &["user.read.own", "user.update.own"])])
+    }
+
+    fn command(permissions: &[&str]) -> CreateApiKeyCommand {
+        CreateApiKeyCommand {
+            name: "ci".to_string(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate() {
+        let mut handle = mock_handle();
+        let (usecase, _, user) = setup(&mut handle).await;
+
+        let issued = usecase
+            .create_api_key(
+                &mut handle,
+                &access_claims(&user),
+                command(&["user.read.own"]),
+            )
+            .await
+            .unwrap();
+        assert!(issued.key.starts_with(&format!("{}.", issued.api_key.prefix)));
+        assert_ne!(issued.api_key.key_hash, issued.key);
+
+        let claims = usecase.authenticate(&mut handle, &issued.key).await.unwrap();
+        assert_eq!(claims.sub, user.user.uid.to_string());
+        assert_eq!(claims.token_type, "api_key");
+        assert_eq!(claims.permissions, Some(vec!["user.read.own".to_string()]));
+
+        // 사용자가 가진 권한이라도 키에 없는 권한은 허용되지 않는다.
+        // (mock repository 는 role 을 저장하지 않으므로 직접 채운다)
+        let claims = JwtClaims {
+            roles: Some(user.role_ids.clone()),
+            ..claims
+        };
+        assert!(claims.has_perm(&user_rbac(), "user.read.own"));
+        assert!(!claims.has_perm(&user_rbac(), "user.update.own"));
+
+        let keys = usecase.list_api_keys(&mut handle, user.user.id).await.unwrap();
+        assert!(keys[0].last_used_at.is_some());
+
+        assert!(usecase.authenticate(&mut handle, "shs_unknown.secret").await.is_err());
+        assert!(usecase.authenticate(&mut handle, "not-a-key").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_permissions_must_be_subset() {
+        let mut handle = mock_handle();
+        let (usecase, _, user) = setup(&mut handle).await;
+
+        let result = usecase
+            .create_api_key(
+                &mut handle,
+                &access_claims(&user),
+                command(&["user.create.all"]),
+            )
+            .await;
+        assert!(matches!(result, Err(shinespark::Error::UnAuthorized)));
+
+        // OAuth client 에 위임된 토큰은 사용자의 role 이 아니라 승인된 scope 까지만 위임할 수 있다.
+        let delegated = JwtClaims {
+            permissions: Some(vec!["openid".to_string()]),
+            client_id: Some("shs_client".to_string()),
+            ..access_claims(&user)
+        };
+        let result =
+            usecase.create_api_key(&mut handle, &delegated, command(&["user.read.own"])).await;
+        assert!(matches!(result, Err(shinespark::Error::UnAuthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_revoked_keys_are_rejected() {
+        let mut handle = mock_handle();
+        let (usecase, user_usecase, user) = setup(&mut handle).await;
+
+        let past = CreateApiKeyCommand {
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+            ..command(&[])
+        };
+        assert!(usecase.create_api_key(&mut handle, &access_claims(&user), past).await.is_err());
+
+        let issued =
+            usecase.create_api_key(&mut handle, &access_claims(&user), command(&[])).await.unwrap();
+        assert!(matches!(
+            usecase.revoke_api_key(&mut handle, user.user.id + 1, &issued.api_key.prefix).await,
+            Err(shinespark::Error::NotFound)
+        ));
+        usecase.revoke_api_key(&mut handle, user.user.id, &issued.api_key.prefix).await.unwrap();
+        assert!(usecase.authenticate(&mut handle, &issued.key).await.is_err());
+
+        // 정지된 사용자의 키는 사용할 수 없다.
+        let issued =
+            usecase.create_api_key(&mut handle, &access_claims(&user), command(&[])).await.unwrap();
+        user_usecase
+            .update_user(
+                &mut handle,
+                UpdateUserCommand {
+                    id: user.user.id,
+                    status: Some(UserStatus::Suspended),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(usecase.authenticate(&mut handle, &issued.key).await.is_err());
+    }
+}
@@ -0,0 +1,492 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use shinespark::config::{JwtConfig, OAuthServerConfig};
+use shinespark::crypto::pkce;
+
+use super::default_jwt_ident_usecase::sha256_hex;
+use crate::entities::{UserAggregate, UserStatus};
+use crate::infra::id_token_signer::IdTokenSigner;
+use crate::infra::jwt_service::{IdTokenClaims, JwtClaims, JwtService, SubjectType};
+use crate::repositories::{AuthorizationCodeRepository, NewAuthorizationCode};
+use crate::usecases::{
+    AuthorizationCodeCommand, AuthorizationCodeToken, AuthorizationRequest,
+    AuthorizationServerUsecase, FindUserQuery, OAuthClientUsecase, OIDC_SCOPES,
+    OpenIdConfiguration, UserInfo, UserUsecase, ValidatedAuthorization,
+};
+
+pub struct DefaultAuthorizationServerUsecase {
+    client_usecase: Arc<dyn OAuthClientUsecase>,
+    code_repository: Arc<dyn AuthorizationCodeRepository>,
+    user_usecase: Arc<dyn UserUsecase>,
+    jwt_service: Arc<dyn JwtService>,
+    id_token_signer: Arc<dyn IdTokenSigner>,
+    issuer: String,
+    authorization_code_ttl_secs: i64,
+    access_token_ttl_secs: i64,
+}
+
+impl DefaultAuthorizationServerUsecase {
+    pub fn new(
+        client_usecase: Arc<dyn OAuthClientUsecase>,
+        code_repository: Arc<dyn AuthorizationCodeRepository>,
+        user_usecase: Arc<dyn UserUsecase>,
+        jwt_service: Arc<dyn JwtService>,
+        id_token_signer: Arc<dyn IdTokenSigner>,
+        config: &OAuthServerConfig,
+        jwt_config: &JwtConfig,
+    ) -> Self {
+        Self {
+            client_usecase,
+            code_repository,
+            user_usecase,
+            jwt_service,
+            id_token_signer,
+            issuer: config.issuer.trim_end_matches('/').to_string(),
+            authorization_code_ttl_secs: config.authorization_code_ttl_secs,
+            access_token_ttl_secs: jwt_config.access_token_ttl_secs,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthorizationServerUsecase for DefaultAuthorizationServerUsecase {
+    async fn validate_authorization_request(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        request: AuthorizationRequest,
+    ) -> shinespark::Result<ValidatedAuthorization> {
+        let client = self
+            .client_usecase
+            .find_client(handle, &request.client_id)
+            .await?
+            .ok_or(shinespark::Error::NotFound)?;
+        if !client.allows_redirect_uri(&request.redirect_uri) {
+            return Err(shinespark::Error::NotFound);
+        }
+
+        if request.response_type != "code" {
+            return Err(shinespark::Error::NotImplemented);
+        }
+
+        let allowed = client.scope_list();
+        let scopes = match request.scope.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(requested) => {
+                let requested: Vec<String> =
+                    requested.split_whitespace().map(str::to_string).collect();
+                if requested.iter().any(|s| !allowed.contains(s)) {
+                    return Err(shinespark::Error::UnAuthorized);
+                }
+                requested
+            }
+            None => allowed,
+        };
+
+        // public client 가 아니더라도 PKCE(S256)를 필수로 요구한다.
+        let code_challenge = request.code_challenge.filter(|c| !c.is_empty()).ok_or(
+            shinespark::Error::IllegalState("code_challenge required".into()),
+        )?;
+        if request.code_challenge_method.as_deref() != Some("S256") {
+            return Err(shinespark::Error::IllegalState(
+                "code_challenge_method must be S256".into(),
+            ));
+        }
+
+        Ok(ValidatedAuthorization {
+            client,
+            redirect_uri: request.redirect_uri,
+            scopes,
+            code_challenge,
+            nonce: request.nonce.filter(|n| !n.is_empty()),
+        })
+    }
+
+    async fn approve(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+        authorization: &ValidatedAuthorization,
+    ) -> shinespark::Result<String> {
+        if user.user.status != UserStatus::Active {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        let code = pkce::generate_code_verifier();
+        self.code_repository
+            .save_code(
+                handle,
+                NewAuthorizationCode {
+                    code_hash: sha256_hex(&code),
+                    client_id: authorization.client.client_id.clone(),
+                    user_id: user.user.id,
+                    redirect_uri: authorization.redirect_uri.clone(),
+                    scope: authorization.scopes.join(" "),
+                    code_challenge: authorization.code_challenge.clone(),
+                    nonce: authorization.nonce.clone(),
+                    expires_at: Utc::now() + Duration::seconds(self.authorization_code_ttl_secs),
+                },
+            )
+            .await?;
+        Ok(code)
+    }
+
+    async fn exchange_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: AuthorizationCodeCommand,
+    ) -> shinespark::Result<AuthorizationCodeToken> {
+        let client = self
+            .client_usecase
+            .authenticate_client(handle, &command.client_id, &command.client_secret)
+            .await?;
+
+        // 검증에 실패해도 code 는 이미 소비되어 재사용할 수 없다.
+        let code = self
+            .code_repository
+            .take_code(handle, &sha256_hex(&command.code))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        if code.client_id != client.client_id
+            || code.redirect_uri != command.redirect_uri
+            || code.code_challenge != pkce::code_challenge_s256(&command.code_verifier)
+        {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        let user = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().id(code.user_id))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        if user.user.status != UserStatus::Active {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        let scopes: Vec<String> = code.scope.split_whitespace().map(str::to_string).collect();
+        let access_token = self.jwt_service.create_delegated_token(
+            &user,
+            &client.client_id,
+            &scopes,
+            self.access_token_ttl_secs,
+        )?;
+
+        let id_token = if scopes.iter().any(|s| s == "openid") {
+            let now = Utc::now();
+            Some(self.id_token_signer.sign(&IdTokenClaims {
+                iss: self.issuer.clone(),
+                sub: user.user.uid.to_string(),
+                aud: client.client_id.clone(),
+                exp: (now + Duration::seconds(self.access_token_ttl_secs)).timestamp() as usize,
+                iat: now.timestamp() as usize,
+                nonce: code.nonce,
+                email: scopes.iter().any(|s| s == "email").then(|| user.user.email.clone()),
+                name: scopes.iter().any(|s| s == "profile").then(|| user.user.name.clone()),
+            })?)
+        } else {
+            None
+        };
+
+        Ok(AuthorizationCodeToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.access_token_ttl_secs,
+            scope: code.scope,
+            id_token,
+        })
+    }
+
+    async fn userinfo(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+    ) -> shinespark::Result<UserInfo> {
+        let scopes = claims.permissions.as_deref().unwrap_or_default();
+        let has_scope = |scope: &str| scopes.iter().any(|s| s == scope);
+        if claims.sub_type != SubjectType::User || !has_scope("openid") {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        let uid =
+            uuid::Uuid::parse_str(&claims.sub).map_err(|_| shinespark::Error::UnAuthorized)?;
+        let user = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().uid(uid))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+
+        Ok(UserInfo {
+            sub: user.user.uid.to_string(),
+            name: has_scope("profile").then_some(user.user.name),
+            email: has_scope("email").then_some(user.user.email),
+        })
+    }
+
+    fn discovery(&self) -> OpenIdConfiguration {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        OpenIdConfiguration {
+            issuer: self.issuer.clone(),
+            authorization_endpoint: format!("{}/oauth2/authorize", self.issuer),
+            token_endpoint: format!("{}/oauth2/token", self.issuer),
+            userinfo_endpoint: format!("{}/oauth2/userinfo", self.issuer),
+            jwks_uri: format!("{}/oauth2/jwks", self.issuer),
+            scopes_supported: strings(&OIDC_SCOPES),
+            response_types_supported: strings(&["code"]),
+            grant_types_supported: strings(&["authorization_code", "client_credentials"]),
+            subject_types_supported: strings(&["public"]),
+            id_token_signing_alg_values_supported: strings(&[self.id_token_signer.algorithm()]),
+            token_endpoint_auth_methods_supported: strings(&[
+                "client_secret_basic",
+                "client_secret_post",
+            ]),
+            code_challenge_methods_supported: strings(&["S256"]),
+        }
+    }
+
+    fn jwks(&self) -> JwkSet {
+        self.id_token_signer.jwks()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::crypto::password::B64PasswordService;
+
+    use super::*;
+    use crate::infra::testing::{MockOAuthClientRepository, MockRbacUsecase, mock_handle};
+    use crate::infra::{
+        DefaultOAuthClientUsecase, DefaultUserUsecase, ES256IdTokenSigner, HS256JwtService,
+    };
+    use crate::usecases::{
+        CreateUserCommand, InitialCredentials, RegisterClientCommand, RegisteredClient,
+    };
+
+    const REDIRECT_URI: &str = "https://app.example.com/callback";
+
+    struct Fixture {
+        usecase: DefaultAuthorizationServerUsecase,
+        jwt_service: Arc<dyn JwtService>,
+        client: RegisteredClient,
+        user: UserAggregate,
+    }
+
+    async fn setup(handle: &mut shinespark::db::Handle<'_>) -> Fixture {
+        let jwt_config = JwtConfig {
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        };
+        let jwt_service: Arc<dyn JwtService> = Arc::new(HS256JwtService::new(&jwt_config));
+        let repository = Arc::new(MockOAuthClientRepository::default());
+        let client_usecase: Arc<dyn OAuthClientUsecase> = Arc::new(DefaultOAuthClientUsecase::new(
+            repository.clone(),
+            Arc::new(MockRbacUsecase::new(&[(1, &["user.read.own"])])),
+            jwt_service.clone(),
+            &jwt_config,
+        ));
+        let user_usecase: Arc<dyn UserUsecase> = Arc::new(DefaultUserUsecase::new(
+            Arc::new(crate::infra::MockUserRepository::new()),
+            Arc::new(B64PasswordService::new()),
+        ));
+
+        let client = client_usecase
+            .register_client(
+                handle,
+                RegisterClientCommand {
+                    name: "app".to_string(),
+                    scopes: vec![
+                        "openid".to_string(),
+                        "email".to_string(),
+                        "user.read.own".to_string(),
+                    ],
+                    redirect_uris: vec![REDIRECT_URI.to_string()],
+                },
+            )
+            .await
+            .unwrap();
+        let created = user_usecase
+            .create_user(
+                handle,
+                CreateUserCommand {
+                    name: "alice".to_string(),
+                    email: "alice@example.com".to_string(),
+                    credentials: InitialCredentials::Local {
+                        password: "pw".to_string(),
+                    },
+                    status: UserStatus::Active,
+                },
+            )
+            .await
+            .unwrap();
+        let user = UserAggregate {
+            user: created.user,
+            role_ids: vec![1],
+            identities: created.identities,
+        };
+
+        let usecase = DefaultAuthorizationServerUsecase::new(
+            client_usecase,
+            repository,
+            user_usecase,
+            jwt_service.clone(),
+            Arc::new(ES256IdTokenSigner::new(&OAuthServerConfig::default()).unwrap()),
+            &OAuthServerConfig::default(),
+            &jwt_config,
+        );
+        Fixture {
+            usecase,
+            jwt_service,
+            client,
+            user,
+        }
+    }
+
+    fn request(client_id: &str, scope: &str, code_challenge: &str) -> AuthorizationRequest {
+        AuthorizationRequest {
+            response_type: "code".to_string(),
+            client_id: client_id.to_string(),
+            redirect_uri: REDIRECT_URI.to_string(),
+            scope: Some(scope.to_string()),
+            code_challenge: Some(code_challenge.to_string()),
+            code_challenge_method: Some("S256".to_string()),
+            nonce: Some("n-1".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow() {
+        let mut handle = mock_handle();
+        let f = setup(&mut handle).await;
+        let client_id = f.client.client.client_id.clone();
+        let verifier = pkce::generate_code_verifier();
+
+        let authorization = f
+            .usecase
+            .validate_authorization_request(
+                &mut handle,
+                request(
+                    &client_id,
+                    "openid email",
+                    &pkce::code_challenge_s256(&verifier),
+                ),
+            )
+            .await
+            .unwrap();
+        let code = f.usecase.approve(&mut handle, &f.user, &authorization).await.unwrap();
+
+        let command = |code: &str| AuthorizationCodeCommand {
+            client_id: client_id.clone(),
+            client_secret: f.client.client_secret.clone(),
+            code: code.to_string(),
+            redirect_uri: REDIRECT_URI.to_string(),
+            code_verifier: verifier.clone(),
+        };
+        let token = f.usecase.exchange_code(&mut handle, command(&code)).await.unwrap();
+        assert_eq!(token.scope, "openid email");
+
+        // ID token 은 공개된 JWKS 로 검증하고, HS256 secret 으로는 검증되지 않는다.
+        let id_token = token.id_token.as_deref().unwrap();
+        let header = jsonwebtoken::decode_header(id_token).unwrap();
+        let jwks = f.usecase.jwks();
+        let jwk = jwks.find(header.kid.as_deref().unwrap()).unwrap();
+        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::ES256);
+        validation.set_audience(&[client_id.as_str()]);
+        let id_claims = jsonwebtoken::decode::<IdTokenClaims>(
+            id_token,
+            &jsonwebtoken::DecodingKey::from_jwk(jwk).unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims;
+        assert_eq!(id_claims.sub, f.user.user.uid.to_string());
+        assert_eq!(id_claims.nonce.as_deref(), Some("n-1"));
+        assert_eq!(id_claims.email.as_deref(), Some("alice@example.com"));
+        assert!(
+            jsonwebtoken::decode::<IdTokenClaims>(
+                id_token,
+                &jsonwebtoken::DecodingKey::from_secret(b"test-secret"),
+                &jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256),
+            )
+            .is_err()
+        );
+        let discovery = f.usecase.discovery();
+        assert_eq!(discovery.id_token_signing_alg_values_supported, ["ES256"]);
+        assert!(discovery.jwks_uri.ends_with("/oauth2/jwks"));
+
+        let claims = f.jwt_service.verify(&token.access_token).unwrap();
+        assert_eq!(claims.sub, f.user.user.uid.to_string());
+        assert_eq!(claims.client_id.as_deref(), Some(client_id.as_str()));
+
+        let userinfo = f.usecase.userinfo(&mut handle, &claims).await.unwrap();
+        assert_eq!(userinfo.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(userinfo.name, None);
+
+        // code 는 1회용이다.
+        assert!(matches!(
+            f.usecase.exchange_code(&mut handle, command(&code)).await,
+            Err(shinespark::Error::UnAuthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_authorization_request_validation() {
+        let mut handle = mock_handle();
+        let f = setup(&mut handle).await;
+        let client_id = f.client.client.client_id.clone();
+
+        let unknown_redirect = AuthorizationRequest {
+            redirect_uri: "https://evil.example.com/callback".to_string(),
+            ..request(&client_id, "openid", "challenge")
+        };
+        assert!(matches!(
+            f.usecase.validate_authorization_request(&mut handle, unknown_redirect).await,
+            Err(shinespark::Error::NotFound)
+        ));
+        assert!(matches!(
+            f.usecase
+                .validate_authorization_request(&mut handle, request(&client_id, "profile", "c"))
+                .await,
+            Err(shinespark::Error::UnAuthorized)
+        ));
+        let no_pkce = AuthorizationRequest {
+            code_challenge: None,
+            ..request(&client_id, "openid", "challenge")
+        };
+        assert!(matches!(
+            f.usecase.validate_authorization_request(&mut handle, no_pkce).await,
+            Err(shinespark::Error::IllegalState(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_rejects_wrong_verifier() {
+        let mut handle = mock_handle();
+        let f = setup(&mut handle).await;
+        let client_id = f.client.client.client_id.clone();
+        let verifier = pkce::generate_code_verifier();
+
+        let authorization = f
+            .usecase
+            .validate_authorization_request(
+                &mut handle,
+                request(&client_id, "openid", &pkce::code_challenge_s256(&verifier)),
+            )
+            .await
+            .unwrap();
+        let code = f.usecase.approve(&mut handle, &f.user, &authorization).await.unwrap();
+
+        let result = f
+            .usecase
+            .exchange_code(
+                &mut handle,
+                AuthorizationCodeCommand {
+                    client_id,
+                    client_secret: f.client.client_secret.clone(),
+                    code,
+                    redirect_uri: REDIRECT_URI.to_string(),
+                    code_verifier: pkce::generate_code_verifier(),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(shinespark::Error::UnAuthorized)));
+    }
+}
@@ -3,10 +3,11 @@ use std::sync::Arc;
 use serde::Deserialize;
 use shinespark::config::GoogleLoginConfig;
 
-use crate::entities::{AuthProvider, UserAggregate, UserStatus};
+use crate::entities::AuthProvider;
+use crate::infra::SocialAccountResolver;
 use crate::usecases::{
-    CreateUserCommand, FindUserQuery, InitialCredentials, LoginCommand, LoginUsecase, RbacUsecase,
-    SocialCallbackCommand, SocialLoginCommand, SocialLoginUsecase, UserUsecase,
+    LoginUsecase, RbacUsecase, SocialCallbackCommand, SocialLoginCommand, SocialLoginOutcome,
+    SocialLoginUsecase, SocialProfile, UserUsecase,
 };
 
 #[derive(Deserialize, Debug)]
@@ -18,15 +19,14 @@ struct GoogleTokenResponse {
 struct GoogleIdTokenClaims {
     sub: String,
     email: Option<String>,
+    email_verified: bool,
     name: Option<String>,
 }
 
 pub struct DefaultGoogleLoginUsecase {
     config: GoogleLoginConfig,
     http: reqwest::Client,
-    user_usecase: Arc<dyn UserUsecase>,
-    login_usecase: Arc<dyn LoginUsecase>,
-    rbac_usecase: Arc<dyn RbacUsecase>,
+    resolver: SocialAccountResolver,
 }
 
 impl DefaultGoogleLoginUsecase {
@@ -39,32 +39,34 @@ impl DefaultGoogleLoginUsecase {
         Self {
             config,
             http: reqwest::Client::new(),
-            user_usecase,
-            login_usecase,
-            rbac_usecase,
+            resolver: SocialAccountResolver::new(user_usecase, login_usecase, rbac_usecase),
         }
     }
 }
 
 #[async_trait::async_trait]
 impl SocialLoginUsecase for DefaultGoogleLoginUsecase {
+    fn provider(&self) -> AuthProvider {
+        AuthProvider::Google
+    }
+
     async fn login(&self, cmd: SocialLoginCommand) -> shinespark::Result<String> {
         let url = format!(
             "https://accounts.google.com/o/oauth2/v2/auth?response_type=code\
-            &client_id={}&redirect_uri={}&scope={}&state={}&access_type=offline&prompt=consent",
+            &client_id={}&redirect_uri={}&scope={}&state={}&access_type=offline&prompt=consent\
+            &code_challenge={}&code_challenge_method=S256",
             self.config.client_id.as_str(),
             self.config.redirect_uri.as_str(),
             self.config.scope.as_str(),
             cmd.state.as_str(),
+            cmd.code_challenge.as_str(),
         );
         Ok(url)
     }
 
-    async fn callback(
-        &self,
-        handle: &mut shinespark::db::Handle<'_>,
-        cmd: SocialCallbackCommand,
-    ) -> shinespark::Result<UserAggregate> {
+    async fn authenticate(&self, cmd: SocialCallbackCommand) -> shinespark::Result<SocialProfile> {
+        cmd.verify_state()?;
+
         let token_resp = self
             .http
             .post("https://oauth2.googleapis.com/token")
@@ -74,6 +76,7 @@ impl SocialLoginUsecase for DefaultGoogleLoginUsecase {
                 ("client_secret", self.config.client_secret.as_str()),
                 ("redirect_uri", self.config.redirect_uri.as_str()),
                 ("grant_type", "authorization_code"),
+                ("code_verifier", cmd.code_verifier.as_str()),
             ])
             .send()
             .await
@@ -84,45 +87,21 @@ impl SocialLoginUsecase for DefaultGoogleLoginUsecase {
 
         let claims = decode_id_token(self.config.client_id.as_str(), &token_resp.id_token).await?;
 
-        let login_result = self
-            .login_usecase
-            .login(
-                handle,
-                LoginCommand::Social {
-                    provider: AuthProvider::Google,
-                    provider_uid: claims.sub.clone(),
-                },
-            )
-            .await;
+        Ok(SocialProfile {
+            provider: AuthProvider::Google,
+            provider_uid: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: Some(claims.name.unwrap_or_else(|| "Google User".to_string())),
+        })
+    }
 
-        match login_result {
-            Ok(user) => Ok(user),
-            Err(shinespark::Error::NotFound) => {
-                let created = self
-                    .user_usecase
-                    .create_user(
-                        handle,
-                        CreateUserCommand {
-                            name: claims.name.unwrap_or_else(|| "Google User".to_string()),
-                            email: claims.email.unwrap_or_default(),
-                            credentials: InitialCredentials::Social {
-                                provider: AuthProvider::Google,
-                                provider_uid: claims.sub,
-                            },
-                            status: UserStatus::Active,
-                        },
-                    )
-                    .await?;
-                self.rbac_usecase.assign_role_to_user(handle, created.user.id, "user").await?;
-                self.user_usecase
-                    .find_user(handle, FindUserQuery::new().id(created.user.id))
-                    .await?
-                    .ok_or_else(|| {
-                        shinespark::Error::IllegalState("user not found after creation".into())
-                    })
-            }
-            Err(e) => Err(e),
-        }
+    async fn resolve(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        profile: SocialProfile,
+    ) -> shinespark::Result<SocialLoginOutcome> {
+        self.resolver.resolve(handle, profile).await
     }
 }
 
@@ -137,6 +116,7 @@ async fn decode_id_token(
     Ok(GoogleIdTokenClaims {
         sub: id_token.sub,
         email: id_token.email,
+        email_verified: id_token.email_verified.unwrap_or(false),
         name: id_token.name,
     })
 }
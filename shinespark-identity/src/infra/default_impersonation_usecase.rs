@@ -0,0 +1,347 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use shinespark::config::ImpersonationConfig;
+
+use super::default_jwt_ident_usecase::sha256_hex;
+use crate::entities::{UserAction, UserStatus};
+use crate::infra::jwt_service::{JwtClaims, JwtService};
+use crate::repositories::{AuditLogRepository, JwtIdentRepository, NewUserAuditLog};
+use crate::usecases::{
+    FindUserQuery, IMPERSONATE_PERMISSION, ImpersonateCommand, ImpersonationToken,
+    ImpersonationUsecase, RbacUsecase, UserUsecase,
+};
+
+/// 감사 로그 description 컬럼 크기 (VARCHAR 255). 사유는 JSON 으로 escape 된 뒤의 길이로 확인한다.
+const MAX_DESCRIPTION_CHARS: usize = 255;
+
+pub struct DefaultImpersonationUsecase {
+    user_usecase: Arc<dyn UserUsecase>,
+    rbac_usecase: Arc<dyn RbacUsecase>,
+    jwt_service: Arc<dyn JwtService>,
+    audit_log_repository: Arc<dyn AuditLogRepository>,
+    jwt_repository: Arc<dyn JwtIdentRepository>,
+    token_ttl_secs: i64,
+}
+
+impl DefaultImpersonationUsecase {
+    pub fn new(
+        user_usecase: Arc<dyn UserUsecase>,
+        rbac_usecase: Arc<dyn RbacUsecase>,
+        jwt_service: Arc<dyn JwtService>,
+        audit_log_repository: Arc<dyn AuditLogRepository>,
+        jwt_repository: Arc<dyn JwtIdentRepository>,
+        config: &ImpersonationConfig,
+    ) -> Self {
+        Self {
+            user_usecase,
+            rbac_usecase,
+            jwt_service,
+            audit_log_repository,
+            jwt_repository,
+            token_ttl_secs: config.token_ttl_secs,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ImpersonationUsecase for DefaultImpersonationUsecase {
+    async fn impersonate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+        command: ImpersonateCommand,
+    ) -> shinespark::Result<ImpersonationToken> {
+        if !claims.has_perm(self.rbac_usecase.as_ref(), IMPERSONATE_PERMISSION) {
+            tracing::warn!("impersonation denied: {}", claims.sub);
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        let reason = command.reason.trim();
+        if reason.is_empty() {
+            return Err(shinespark::Error::IllegalState("reason is required".into()));
+        }
+        let description = serde_json::json!({ "actor": claims.sub, "reason": reason }).to_string();
+        if description.chars().count() > MAX_DESCRIPTION_CHARS {
+            return Err(shinespark::Error::IllegalState("reason is too long".into()));
+        }
+
+        let admin_uid =
+            uuid::Uuid::parse_str(&claims.sub).map_err(|_| shinespark::Error::UnAuthorized)?;
+        let admin = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().uid(admin_uid))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)?;
+        let target = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().uid(command.target_uid))
+            .await?
+            .ok_or(shinespark::Error::NotFound)?;
+        if target.user.id == admin.user.id {
+            return Err(shinespark::Error::IllegalState(
+                "cannot impersonate yourself".into(),
+            ));
+        }
+        if target.user.status != UserStatus::Active {
+            return Err(shinespark::Error::IllegalState(
+                "target user is not active".into(),
+            ));
+        }
+        // 관리자 권한을 빌려 쓰는 경로가 되지 않도록 관리자끼리는 허용하지 않는다.
+        if self.rbac_usecase.check_perm(&target.role_ids, IMPERSONATE_PERMISSION) {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+
+        let access_token = self.jwt_service.create_impersonation_token(
+            &target,
+            &admin.user.uid.to_string(),
+            self.token_ttl_secs,
+        )?;
+
+        self.audit_log_repository
+            .record(
+                handle,
+                NewUserAuditLog {
+                    user_id: target.user.id,
+                    action: UserAction::ImpersonationStarted,
+                    description: Some(description),
+                    ip_address: None,
+                    user_agent: command.user_agent,
+                    is_success: true,
+                },
+            )
+            .await?;
+
+        Ok(ImpersonationToken {
+            access_token,
+            expires_in: self.token_ttl_secs,
+            target: target.user,
+        })
+    }
+
+    async fn stop(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        access_token: &str,
+        user_agent: Option<String>,
+    ) -> shinespark::Result<()> {
+        let claims =
+            self.jwt_service.verify(access_token).map_err(|_| shinespark::Error::UnAuthorized)?;
+        let actor = claims
+            .act
+            .as_ref()
+            .ok_or(shinespark::Error::IllegalState("not impersonating".into()))?;
+        let uid =
+            uuid::Uuid::parse_str(&claims.sub).map_err(|_| shinespark::Error::UnAuthorized)?;
+        let target = self
+            .user_usecase
+            .find_user(handle, FindUserQuery::new().uid(uid))
+            .await?
+            .ok_or(shinespark::Error::NotFound)?;
+
+        self.audit_log_repository
+            .record(
+                handle,
+                NewUserAuditLog {
+                    user_id: target.user.id,
+                    action: UserAction::ImpersonationStopped,
+                    description: Some(serde_json::json!({ "actor": actor.sub }).to_string()),
+                    ip_address: None,
+                    user_agent,
+                    is_success: true,
+                },
+            )
+            .await?;
+
+        // 버려지지 않은 사본이 있어도 만료 전까지 쓰지 못하도록 폐기한다.
+        let expires_at =
+            DateTime::<Utc>::from_timestamp(claims.exp as i64, 0).unwrap_or_else(Utc::now);
+        self.jwt_repository.revoke_access_token(handle, &sha256_hex(access_token), expires_at).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use shinespark::config::JwtConfig;
+    use shinespark::crypto::password::B64PasswordService;
+
+    use super::*;
+    use crate::entities::UserAggregate;
+    use crate::infra::testing::{
+        MockJwtIdentRepository, MockRbacUsecase, access_claims, mock_handle,
+    };
+    use crate::infra::{DefaultUserUsecase, HS256JwtService, MockUserRepository};
+    use crate::usecases::{CreateUserCommand, InitialCredentials};
+
+    #[derive(Default)]
+    struct MockAuditLogRepository {
+        logs: Mutex<Vec<NewUserAuditLog>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AuditLogRepository for MockAuditLogRepository {
+        async fn record(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            log: NewUserAuditLog,
+        ) -> shinespark::Result<()> {
+            self.logs.lock().unwrap().push(log);
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        usecase: DefaultImpersonationUsecase,
+        jwt_service: Arc<dyn JwtService>,
+        audit_log: Arc<MockAuditLogRepository>,
+        jwt_repository: Arc<MockJwtIdentRepository>,
+        admin: UserAggregate,
+        target: UserAggregate,
+    }
+
+    async fn setup(handle: &mut shinespark::db::Handle<'_>) -> Fixture {
+        let user_usecase: Arc<dyn UserUsecase> = Arc::new(DefaultUserUsecase::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(B64PasswordService::new()),
+        ));
+        let jwt_service: Arc<dyn JwtService> = Arc::new(HS256JwtService::new(&JwtConfig {
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        }));
+        let audit_log = Arc::new(MockAuditLogRepository::default());
+        let jwt_repository = Arc::new(MockJwtIdentRepository::new());
+        let usecase = DefaultImpersonationUsecase::new(
+            user_usecase.clone(),
+            Arc::new(MockRbacUsecase::new(&[(1, &[IMPERSONATE_PERMISSION])])),
+            jwt_service.clone(),
+            audit_log.clone(),
+            jwt_repository.clone(),
+            &ImpersonationConfig::default(),
+        );
+
+        let mut create = async |name: &str, role_ids: Vec<i64>| {
+            let created = user_usecase
+                .create_user(
+                    handle,
+                    CreateUserCommand {
+                        name: name.to_string(),
+                        email: format!("{}@example.com", name),
+                        credentials: InitialCredentials::Local {
+                            password: "pw".to_string(),
+                        },
+                        status: UserStatus::Active,
+                    },
+                )
+                .await
+                .unwrap();
+            UserAggregate {
+                user: created.user,
+                role_ids,
+                identities: created.identities,
+            }
+        };
+        let admin = create("support", vec![1]).await;
+        let target = create("customer", vec![]).await;
+        Fixture {
+            usecase,
+            jwt_service,
+            audit_log,
+            jwt_repository,
+            admin,
+            target,
+        }
+    }
+
+    fn command(target_uid: uuid::Uuid) -> ImpersonateCommand {
+        ImpersonateCommand {
+            target_uid,
+            reason: "ticket-1234".to_string(),
+            user_agent: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_impersonate_and_stop() {
+        let mut handle = mock_handle();
+        let f = setup(&mut handle).await;
+
+        let token = f
+            .usecase
+            .impersonate(
+                &mut handle,
+                &access_claims(&f.admin),
+                command(f.target.user.uid),
+            )
+            .await
+            .unwrap();
+        let claims = f.jwt_service.verify(&token.access_token).unwrap();
+        assert_eq!(claims.sub, f.target.user.uid.to_string());
+        assert_eq!(
+            claims.act.as_ref().unwrap().sub,
+            f.admin.user.uid.to_string()
+        );
+
+        f.usecase.stop(&mut handle, &token.access_token, None).await.unwrap();
+        assert!(f.jwt_repository.is_revoked(&sha256_hex(&token.access_token)));
+
+        let logs = f.audit_log.logs.lock().unwrap();
+        let actions: Vec<_> = logs.iter().map(|l| l.action.clone()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                UserAction::ImpersonationStarted,
+                UserAction::ImpersonationStopped
+            ]
+        );
+        assert!(logs.iter().all(|l| l.user_id == f.target.user.id));
+        assert!(logs[0].description.as_deref().unwrap().contains("ticket-1234"));
+    }
+
+    #[tokio::test]
+    async fn test_impersonate_requires_permission() {
+        let mut handle = mock_handle();
+        let f = setup(&mut handle).await;
+
+        // 권한이 없는 사용자
+        assert!(matches!(
+            f.usecase
+                .impersonate(
+                    &mut handle,
+                    &access_claims(&f.target),
+                    command(f.admin.user.uid)
+                )
+                .await,
+            Err(shinespark::Error::UnAuthorized)
+        ));
+        // 자기 자신
+        assert!(
+            f.usecase
+                .impersonate(
+                    &mut handle,
+                    &access_claims(&f.admin),
+                    command(f.admin.user.uid)
+                )
+                .await
+                .is_err()
+        );
+        // 사유 없음
+        let no_reason = ImpersonateCommand {
+            reason: " ".to_string(),
+            ..command(f.target.user.uid)
+        };
+        assert!(
+            f.usecase.impersonate(&mut handle, &access_claims(&f.admin), no_reason).await.is_err()
+        );
+        // escape 하면 description 컬럼을 넘는 사유
+        let too_long = ImpersonateCommand {
+            reason: "\"".repeat(120),
+            ..command(f.target.user.uid)
+        };
+        assert!(
+            f.usecase.impersonate(&mut handle, &access_claims(&f.admin), too_long).await.is_err()
+        );
+        assert!(f.audit_log.logs.lock().unwrap().is_empty());
+    }
+}
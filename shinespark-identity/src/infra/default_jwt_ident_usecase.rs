@@ -1,13 +1,17 @@
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
 
-use crate::infra::jwt_service::{JwtService, JwtTokenPair};
+use crate::entities::UserAggregate;
+use crate::infra::jwt_service::{JwtClaims, JwtService, JwtTokenPair};
 use crate::repositories::JwtIdentRepository;
-use crate::usecases::{FindUserQuery, JwtIdentUsecase, LoginCommand, LoginUsecase, UserUsecase};
+use crate::usecases::{
+    FindUserQuery, JwtIdentUsecase, JwtLoginOutcome, LoginCommand, LoginOutcome, LoginUsecase,
+    MfaLoginCommand, TokenIntrospection, UserUsecase,
+};
 
-fn sha256_hex(input: &str) -> String {
+pub(crate) fn sha256_hex(input: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(input.as_bytes());
     format!("{:x}", hasher.finalize())
@@ -46,6 +50,24 @@ where
             jwt_repository,
         }
     }
+
+    async fn issue(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        aggregate: &UserAggregate,
+    ) -> shinespark::Result<JwtTokenPair> {
+        let pair = self.jwt_service.create(aggregate)?;
+        let token_hash = sha256_hex(&pair.refresh_token);
+        self.jwt_repository
+            .save_refresh_token(
+                handle,
+                &aggregate.user.uid.to_string(),
+                &token_hash,
+                pair.refresh_token_expires_at,
+            )
+            .await?;
+        Ok(pair)
+    }
 }
 
 #[async_trait::async_trait]
@@ -60,19 +82,22 @@ where
         &self,
         handle: &mut shinespark::db::Handle<'_>,
         command: LoginCommand,
+    ) -> shinespark::Result<JwtLoginOutcome> {
+        match self.login_usecase.authenticate(handle, command).await? {
+            LoginOutcome::Authenticated(aggregate) => {
+                self.issue(handle, &aggregate).await.map(JwtLoginOutcome::Issued)
+            }
+            LoginOutcome::MfaRequired(challenge) => Ok(JwtLoginOutcome::MfaRequired(challenge)),
+        }
+    }
+
+    async fn complete_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: MfaLoginCommand,
     ) -> shinespark::Result<JwtTokenPair> {
-        let aggregate = self.login_usecase.login(handle, command).await?;
-        let pair = self.jwt_service.create(&aggregate)?;
-        let token_hash = sha256_hex(&pair.refresh_token);
-        self.jwt_repository
-            .save_refresh_token(
-                handle,
-                &aggregate.user.uid.to_string(),
-                &token_hash,
-                pair.refresh_token_expires_at,
-            )
-            .await?;
-        Ok(pair)
+        let aggregate = self.login_usecase.complete_mfa(handle, command).await?;
+        self.issue(handle, &aggregate).await
     }
 
     async fn logout(
@@ -132,20 +157,84 @@ where
 
         Ok(pair)
     }
+
+    async fn authenticate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token: &str,
+    ) -> shinespark::Result<JwtClaims> {
+        let claims = self.jwt_service.verify(token).map_err(|_| shinespark::Error::UnAuthorized)?;
+        if claims.token_type != "access" {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        if self.jwt_repository.is_access_token_revoked(handle, &sha256_hex(token)).await? {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        Ok(claims)
+    }
+
+    async fn introspect(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token: &str,
+    ) -> shinespark::Result<TokenIntrospection> {
+        let Ok(claims) = self.jwt_service.verify(token) else {
+            return Ok(TokenIntrospection::inactive());
+        };
+        let token_hash = sha256_hex(token);
+        let active = match claims.token_type.as_str() {
+            "access" => !self.jwt_repository.is_access_token_revoked(handle, &token_hash).await?,
+            "refresh" => {
+                self.jwt_repository.find_refresh_token(handle, &token_hash).await?.is_some()
+            }
+            // mfa_challenge 등은 resource server 에서 쓰는 토큰이 아니다.
+            _ => false,
+        };
+        if !active {
+            return Ok(TokenIntrospection::inactive());
+        }
+        Ok(TokenIntrospection::active(claims))
+    }
+
+    async fn revoke(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token: &str,
+        client_id: &str,
+    ) -> shinespark::Result<()> {
+        let Ok(claims) = self.jwt_service.verify(token) else {
+            return Ok(());
+        };
+        // client 에 발급된 토큰은 그 client 만 폐기할 수 있다. 1st party 토큰은 토큰을 가진 쪽이면 폐기할 수 있다.
+        if claims.client_id.as_deref().is_some_and(|id| id != client_id) {
+            tracing::warn!("revoke requested by another client: {}", client_id);
+            return Ok(());
+        }
+
+        let token_hash = sha256_hex(token);
+        match claims.token_type.as_str() {
+            "refresh" => self.jwt_repository.delete_refresh_token(handle, &token_hash).await,
+            "access" => {
+                let expires_at =
+                    DateTime::<Utc>::from_timestamp(claims.exp as i64, 0).unwrap_or_else(Utc::now);
+                self.jwt_repository.revoke_access_token(handle, &token_hash, expires_at).await
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Mutex;
 
-    use chrono::{DateTime, Utc};
+    use chrono::Utc;
     use shinespark::crypto::password::B64PasswordService;
 
     use crate::entities::{UserAggregate, UserStatus};
-    use crate::infra::jwt_service::{JwtClaims, JwtTokenPair};
+    use crate::infra::jwt_service::{JwtClaims, JwtTokenPair, SubjectType};
+    use crate::infra::testing::MockJwtIdentRepository;
     use crate::infra::{DefaultLoginUsecase, DefaultUserUsecase, MockUserRepository};
-    use crate::repositories::{JwtIdentRepository, RefreshTokenRow};
     use crate::usecases::{CreateUserCommand, InitialCredentials, UserUsecase};
 
     // --- Mock JwtService ---
@@ -164,6 +253,42 @@ mod tests {
             })
         }
 
+        fn create_mfa_challenge(
+            &self,
+            aggregate: &UserAggregate,
+            _ttl_secs: i64,
+        ) -> shinespark::Result<String> {
+            Ok(format!("mfa.{}", aggregate.user.uid))
+        }
+
+        fn create_client_token(
+            &self,
+            client_id: &str,
+            _scopes: &[String],
+            _ttl_secs: i64,
+        ) -> shinespark::Result<String> {
+            Ok(format!("client.{}", client_id))
+        }
+
+        fn create_delegated_token(
+            &self,
+            aggregate: &UserAggregate,
+            client_id: &str,
+            _scopes: &[String],
+            _ttl_secs: i64,
+        ) -> shinespark::Result<String> {
+            Ok(format!("delegated.{}.{}", client_id, aggregate.user.uid))
+        }
+
+        fn create_impersonation_token(
+            &self,
+            target: &UserAggregate,
+            actor_uid: &str,
+            _ttl_secs: i64,
+        ) -> shinespark::Result<String> {
+            Ok(format!("impersonation.{}.{}", actor_uid, target.user.uid))
+        }
+
         fn verify(&self, token: &str) -> shinespark::Result<JwtClaims> {
             if self.fail_verify {
                 return Err(shinespark::Error::UnAuthorized);
@@ -177,67 +302,18 @@ mod tests {
                 token.strip_prefix("refresh.").unwrap_or("00000000-0000-0000-0000-000000000001");
             Ok(JwtClaims {
                 sub: uid.to_string(),
+                sub_type: SubjectType::User,
                 exp: usize::MAX,
                 roles: None,
                 token_type,
+                permissions: None,
+                client_id: None,
+                act: None,
             })
         }
-    }
-
-    // --- Mock JwtIdentRepository ---
-
-    struct MockJwtIdentRepository {
-        tokens: Mutex<Vec<(String, String)>>, // (user_uid, token_hash)
-    }
-
-    impl MockJwtIdentRepository {
-        fn new() -> Self {
-            Self {
-                tokens: Mutex::new(vec![]),
-            }
-        }
-    }
-
-    #[async_trait::async_trait]
-    impl JwtIdentRepository for MockJwtIdentRepository {
-        async fn save_refresh_token(
-            &self,
-            _handle: &mut shinespark::db::Handle<'_>,
-            user_uid: &str,
-            token_hash: &str,
-            _expires_at: DateTime<Utc>,
-        ) -> shinespark::Result<()> {
-            self.tokens.lock().unwrap().push((user_uid.to_string(), token_hash.to_string()));
-            Ok(())
-        }
-
-        async fn find_refresh_token(
-            &self,
-            _handle: &mut shinespark::db::Handle<'_>,
-            token_hash: &str,
-        ) -> shinespark::Result<Option<RefreshTokenRow>> {
-            let tokens = self.tokens.lock().unwrap();
-            let found = tokens.iter().any(|(_, h)| h == token_hash);
-            if found {
-                Ok(Some(RefreshTokenRow {
-                    id: 1,
-                    user_uid: uuid::Uuid::new_v4(),
-                    token_hash: token_hash.to_string(),
-                    expires_at: Utc::now() + chrono::Duration::hours(1),
-                    created_at: Utc::now(),
-                }))
-            } else {
-                Ok(None)
-            }
-        }
 
-        async fn delete_by_user_uid(
-            &self,
-            _handle: &mut shinespark::db::Handle<'_>,
-            user_uid: &str,
-        ) -> shinespark::Result<()> {
-            self.tokens.lock().unwrap().retain(|(uid, _)| uid != user_uid);
-            Ok(())
+        fn is_expired(&self, _token: &str) -> bool {
+            false
         }
     }
 
@@ -310,8 +386,9 @@ mod tests {
                 },
             )
             .await;
-        assert!(result.is_ok());
-        let pair = result.unwrap();
+        let Ok(JwtLoginOutcome::Issued(pair)) = result else {
+            panic!("expected token pair");
+        };
         assert!(!pair.access_token.is_empty());
         assert!(!pair.refresh_token.is_empty());
     }
@@ -401,4 +478,59 @@ mod tests {
         let result = usecase.refresh(&mut handle, "some.token").await;
         assert!(matches!(result, Err(shinespark::Error::UnAuthorized)));
     }
+
+    #[tokio::test]
+    async fn test_introspect_and_revoke() {
+        let (login_usecase, user_usecase) = make_mock_usecase();
+        let jwt_service = Arc::new(crate::infra::HS256JwtService::new(
+            &shinespark::config::JwtConfig {
+                secret: "test-secret".to_string(),
+                ..Default::default()
+            },
+        ));
+        let jwt_repository = Arc::new(MockJwtIdentRepository::new());
+        let usecase = DefaultJwtIdentUsecase::new(
+            login_usecase,
+            user_usecase,
+            jwt_service.clone(),
+            jwt_repository,
+        );
+        let mut handle = crate::infra::testing::mock_handle();
+
+        let aggregate = UserAggregate {
+            user: crate::entities::User::new(
+                "test".to_string(),
+                "introspect@example.com".to_string(),
+                UserStatus::Active,
+            ),
+            role_ids: vec![1],
+            identities: vec![],
+        };
+        let pair = usecase.issue(&mut handle, &aggregate).await.unwrap();
+
+        let access = usecase.introspect(&mut handle, &pair.access_token).await.unwrap();
+        assert!(access.active);
+        assert_eq!(access.sub, Some(aggregate.user.uid.to_string()));
+        assert_eq!(access.roles, Some(vec![1]));
+        assert!(usecase.introspect(&mut handle, &pair.refresh_token).await.unwrap().active);
+        assert!(!usecase.introspect(&mut handle, "invalid.token").await.unwrap().active);
+        assert!(usecase.authenticate(&mut handle, &pair.access_token).await.is_ok());
+        assert!(usecase.authenticate(&mut handle, &pair.refresh_token).await.is_err());
+
+        // 다른 client 에 발급된 토큰은 폐기하지 않는다.
+        let delegated = jwt_service
+            .create_delegated_token(&aggregate, "shs_client_a", &["openid".to_string()], 300)
+            .unwrap();
+        usecase.revoke(&mut handle, &delegated, "shs_client_b").await.unwrap();
+        assert!(usecase.introspect(&mut handle, &delegated).await.unwrap().active);
+
+        usecase.revoke(&mut handle, &pair.access_token, "shs_client_b").await.unwrap();
+        usecase.revoke(&mut handle, &pair.refresh_token, "shs_client_b").await.unwrap();
+        assert!(!usecase.introspect(&mut handle, &pair.access_token).await.unwrap().active);
+        assert!(!usecase.introspect(&mut handle, &pair.refresh_token).await.unwrap().active);
+        assert!(matches!(
+            usecase.authenticate(&mut handle, &pair.access_token).await,
+            Err(shinespark::Error::UnAuthorized)
+        ));
+    }
 }
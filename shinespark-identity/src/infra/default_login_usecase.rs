@@ -4,11 +4,17 @@ use shinespark::crypto::password::PasswordService;
 
 use crate::entities::{AuthProvider, UserAggregate};
 use crate::repositories::UserRepository;
-use crate::usecases::{LoginCommand, LoginUsecase};
+use crate::usecases::{
+    LoginCommand, LoginOutcome, LoginUsecase, MfaLoginCommand, MfaUsecase, WebAuthnUsecase,
+};
 
 pub struct DefaultLoginUsecase<T: UserRepository + ?Sized, P: PasswordService> {
     pub user_repository: Arc<T>,
     pub password_service: Arc<P>,
+    /// 설정하지 않으면 2 단계 인증 없이 로그인한다.
+    pub mfa_usecase: Option<Arc<dyn MfaUsecase>>,
+    /// 설정하지 않으면 passkey 로그인을 지원하지 않는다.
+    pub webauthn_usecase: Option<Arc<dyn WebAuthnUsecase>>,
 }
 
 impl<T: UserRepository + ?Sized, P: PasswordService> DefaultLoginUsecase<T, P> {
@@ -16,8 +22,20 @@ impl<T: UserRepository + ?Sized, P: PasswordService> DefaultLoginUsecase<T, P> {
         Self {
             user_repository,
             password_service,
+            mfa_usecase: None,
+            webauthn_usecase: None,
         }
     }
+
+    pub fn with_mfa(mut self, mfa_usecase: Arc<dyn MfaUsecase>) -> Self {
+        self.mfa_usecase = Some(mfa_usecase);
+        self
+    }
+
+    pub fn with_webauthn(mut self, webauthn_usecase: Arc<dyn WebAuthnUsecase>) -> Self {
+        self.webauthn_usecase = Some(webauthn_usecase);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -71,8 +89,38 @@ impl<T: UserRepository + ?Sized, P: PasswordService> LoginUsecase for DefaultLog
                     Err(shinespark::Error::NotFound)
                 }
             }
+            LoginCommand::Passkey { credential } => {
+                let webauthn =
+                    self.webauthn_usecase.as_ref().ok_or(shinespark::Error::NotImplemented)?;
+                webauthn.finish_authentication(handle, None, credential).await
+            }
         }
     }
+
+    async fn authenticate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: LoginCommand,
+    ) -> shinespark::Result<LoginOutcome> {
+        let skip_mfa = matches!(command, LoginCommand::Passkey { .. });
+        let user = self.login(handle, command).await?;
+        match &self.mfa_usecase {
+            Some(mfa) if !skip_mfa => match mfa.issue_challenge(handle, &user).await? {
+                Some(challenge) => Ok(LoginOutcome::MfaRequired(challenge)),
+                None => Ok(LoginOutcome::Authenticated(user)),
+            },
+            _ => Ok(LoginOutcome::Authenticated(user)),
+        }
+    }
+
+    async fn complete_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: MfaLoginCommand,
+    ) -> shinespark::Result<UserAggregate> {
+        let mfa = self.mfa_usecase.as_ref().ok_or(shinespark::Error::NotImplemented)?;
+        mfa.verify_challenge(handle, command).await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,657 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use shinespark::config::MfaConfig;
+use shinespark::crypto::cipher::SecretCipher;
+use shinespark::crypto::password::PasswordService;
+use shinespark::crypto::totp;
+
+use super::default_jwt_ident_usecase::sha256_hex;
+use crate::entities::{AuthProvider, UserAggregate, UserMfa};
+use crate::infra::jwt_service::{JwtClaims, JwtService};
+use crate::repositories::MfaRepository;
+use crate::usecases::{
+    FindUserQuery, MfaChallenge, MfaEnrollment, MfaFactor, MfaLoginCommand, MfaMethod, MfaUsecase,
+    UserUsecase, WebAuthnUsecase,
+};
+
+const MFA_CHALLENGE_TOKEN_TYPE: &str = "mfa_challenge";
+
+pub struct DefaultMfaUsecase {
+    mfa_repository: Arc<dyn MfaRepository>,
+    user_usecase: Arc<dyn UserUsecase>,
+    password_service: Arc<dyn PasswordService>,
+    jwt_service: Arc<dyn JwtService>,
+    /// 설정하지 않으면 passkey 를 2 단계 인증 수단으로 쓰지 않는다.
+    webauthn_usecase: Option<Arc<dyn WebAuthnUsecase>>,
+    cipher: SecretCipher,
+    config: MfaConfig,
+    /// challenge token 해시 → (실패 횟수, 토큰 만료 시각).
+    /// 실패해도 요청의 트랜잭션은 롤백되므로 DB 가 아니라 프로세스 메모리에 센다.
+    challenge_failures: Mutex<HashMap<String, (u32, usize)>>,
+}
+
+impl DefaultMfaUsecase {
+    pub fn new(
+        mfa_repository: Arc<dyn MfaRepository>,
+        user_usecase: Arc<dyn UserUsecase>,
+        password_service: Arc<dyn PasswordService>,
+        jwt_service: Arc<dyn JwtService>,
+        config: &MfaConfig,
+    ) -> Self {
+        Self {
+            mfa_repository,
+            user_usecase,
+            password_service,
+            jwt_service,
+            webauthn_usecase: None,
+            cipher: SecretCipher::new(&config.encryption_key),
+            config: config.clone(),
+            challenge_failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_webauthn(mut self, webauthn_usecase: Arc<dyn WebAuthnUsecase>) -> Self {
+        self.webauthn_usecase = Some(webauthn_usecase);
+        self
+    }
+
+    fn now_secs() -> u64 {
+        Utc::now().timestamp().max(0) as u64
+    }
+
+    /// 코드가 맞으면 일치한 step 을 돌려준다. 이미 사용한 step 이하의 코드는 거부한다.
+    fn match_totp(&self, mfa: &UserMfa, code: &str) -> shinespark::Result<Option<i64>> {
+        let secret = self.cipher.decrypt(&mfa.secret_encrypted)?;
+        let step = totp::verify(
+            &secret,
+            code,
+            Self::now_secs(),
+            self.config.allowed_skew_steps,
+        )
+        .map(|step| step as i64);
+        Ok(step.filter(|step| mfa.last_used_step.is_none_or(|last| *step > last)))
+    }
+
+    fn challenge_claims(&self, challenge_token: &str) -> shinespark::Result<JwtClaims> {
+        let claims = self.jwt_service.verify(challenge_token)?;
+        if claims.token_type != MFA_CHALLENGE_TOKEN_TYPE {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        Ok(claims)
+    }
+
+    async fn find_challenge_user(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+    ) -> shinespark::Result<UserAggregate> {
+        let uid =
+            uuid::Uuid::parse_str(&claims.sub).map_err(|_| shinespark::Error::UnAuthorized)?;
+        self.user_usecase
+            .find_user(handle, FindUserQuery::new().uid(uid))
+            .await?
+            .ok_or(shinespark::Error::UnAuthorized)
+    }
+
+    fn is_challenge_exhausted(&self, challenge_hash: &str) -> bool {
+        let failures = self.challenge_failures.lock().unwrap();
+        failures
+            .get(challenge_hash)
+            .is_some_and(|(count, _)| *count >= self.config.max_challenge_attempts)
+    }
+
+    /// 실패를 기록한다. 만료된 challenge 의 기록은 이때 함께 지운다.
+    fn record_challenge_failure(&self, challenge_hash: String, exp: usize) {
+        let now = Self::now_secs() as usize;
+        let mut failures = self.challenge_failures.lock().unwrap();
+        failures.retain(|_, (_, exp)| *exp >= now);
+        failures.entry(challenge_hash).or_insert((0, exp)).0 += 1;
+    }
+
+    async fn enabled_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<UserMfa> {
+        self.mfa_repository
+            .find_mfa(handle, user_id)
+            .await?
+            .filter(UserMfa::is_enabled)
+            .ok_or(shinespark::Error::NotFound)
+    }
+
+    async fn use_recovery_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<bool> {
+        let normalized = normalize_recovery_code(code);
+        if normalized.is_empty() {
+            return Ok(false);
+        }
+        let codes = self.mfa_repository.list_unused_recovery_codes(handle, user_id).await?;
+        for recovery_code in codes {
+            if self
+                .password_service
+                .verify_password(normalized.as_bytes(), &recovery_code.code_hash)
+                .is_ok()
+            {
+                return self.mfa_repository.mark_recovery_code_used(handle, recovery_code.id).await;
+            }
+        }
+        Ok(false)
+    }
+
+    async fn issue_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<String>> {
+        let codes: Vec<String> =
+            (0..self.config.recovery_code_count).map(|_| generate_recovery_code()).collect();
+        let hashes = codes
+            .iter()
+            .map(|code| {
+                self.password_service.hash_password(normalize_recovery_code(code).as_bytes())
+            })
+            .collect::<shinespark::Result<Vec<_>>>()?;
+        self.mfa_repository.replace_recovery_codes(handle, user_id, &hashes).await?;
+        Ok(codes)
+    }
+}
+
+/// `xxxxx-xxxxx` 형태의 소문자 base32 복구 코드
+fn generate_recovery_code() -> String {
+    let encoded = totp::encode_secret(&totp::generate_secret()).to_lowercase();
+    format!("{}-{}", &encoded[..5], &encoded[5..10])
+}
+
+/// 사용자가 대문자나 구분자를 다르게 입력해도 같은 코드로 취급한다.
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+#[async_trait::async_trait]
+impl MfaUsecase for DefaultMfaUsecase {
+    async fn begin_enrollment(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+    ) -> shinespark::Result<MfaEnrollment> {
+        if let Some(mfa) = self.mfa_repository.find_mfa(handle, user.user.id).await?
+            && mfa.is_enabled()
+        {
+            return Err(shinespark::Error::AlreadyExists);
+        }
+
+        let secret = totp::generate_secret();
+        let encrypted = self.cipher.encrypt(&secret)?;
+        self.mfa_repository.save_pending_mfa(handle, user.user.id, &encrypted).await?;
+
+        Ok(MfaEnrollment {
+            secret: totp::encode_secret(&secret),
+            otpauth_uri: totp::otpauth_uri(&self.config.issuer, &user.user.email, &secret),
+        })
+    }
+
+    async fn confirm_enrollment(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<Vec<String>> {
+        let mfa = self
+            .mfa_repository
+            .find_mfa(handle, user_id)
+            .await?
+            .ok_or(shinespark::Error::NotFound)?;
+        if mfa.is_enabled() {
+            return Err(shinespark::Error::AlreadyExists);
+        }
+        let step = self.match_totp(&mfa, code)?.ok_or(shinespark::Error::InvalidCredentials)?;
+        self.mfa_repository.confirm_mfa(handle, user_id, step).await?;
+        self.issue_recovery_codes(handle, user_id).await
+    }
+
+    async fn is_enabled(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<bool> {
+        Ok(
+            self.mfa_repository
+                .find_mfa(handle, user_id)
+                .await?
+                .is_some_and(|mfa| mfa.is_enabled()),
+        )
+    }
+
+    async fn verify(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<()> {
+        let mfa = self.enabled_mfa(handle, user_id).await?;
+        if let Some(step) = self.match_totp(&mfa, code)? {
+            // 동시에 같은 코드로 들어온 요청은 한쪽만 갱신에 성공한다.
+            if self.mfa_repository.update_last_used_step(handle, user_id, step).await? {
+                return Ok(());
+            }
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+        if self.use_recovery_code(handle, user_id, code).await? {
+            return Ok(());
+        }
+        Err(shinespark::Error::InvalidCredentials)
+    }
+
+    async fn disable(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<()> {
+        self.verify(handle, user_id, code).await?;
+        self.mfa_repository.delete_mfa(handle, user_id).await
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<Vec<String>> {
+        self.verify(handle, user_id, code).await?;
+        self.issue_recovery_codes(handle, user_id).await
+    }
+
+    async fn issue_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+    ) -> shinespark::Result<Option<MfaChallenge>> {
+        let mut methods = vec![];
+        if self.is_enabled(handle, user.user.id).await? {
+            methods.push(MfaMethod::Totp);
+        }
+        if self.webauthn_usecase.is_some()
+            && user.identities.iter().any(|i| i.provider == AuthProvider::Passkey)
+        {
+            methods.push(MfaMethod::Passkey);
+        }
+        if methods.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(MfaChallenge {
+            challenge_token: self
+                .jwt_service
+                .create_mfa_challenge(user, self.config.challenge_ttl_secs)?,
+            expires_in: self.config.challenge_ttl_secs,
+            methods,
+        }))
+    }
+
+    async fn challenge_user(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge_token: &str,
+    ) -> shinespark::Result<UserAggregate> {
+        let claims = self.challenge_claims(challenge_token)?;
+        self.find_challenge_user(handle, &claims).await
+    }
+
+    async fn verify_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: MfaLoginCommand,
+    ) -> shinespark::Result<UserAggregate> {
+        let claims = self.challenge_claims(&command.challenge_token)?;
+        let user = self.find_challenge_user(handle, &claims).await?;
+        let challenge_hash = sha256_hex(&command.challenge_token);
+        if self.is_challenge_exhausted(&challenge_hash) {
+            tracing::warn!("mfa challenge exhausted: {}", user.user.uid);
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        let verified = match command.factor {
+            MfaFactor::Code(code) => self.verify(handle, user.user.id, &code).await,
+            MfaFactor::Passkey(credential) => {
+                let webauthn =
+                    self.webauthn_usecase.as_ref().ok_or(shinespark::Error::NotImplemented)?;
+                webauthn.finish_authentication(handle, Some(&user), credential).await.map(drop)
+            }
+        };
+        match verified {
+            Ok(()) => {
+                self.challenge_failures.lock().unwrap().remove(&challenge_hash);
+                Ok(user)
+            }
+            Err(e) => {
+                self.record_challenge_failure(challenge_hash, claims.exp);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use shinespark::crypto::password::B64PasswordService;
+
+    use crate::entities::{MfaRecoveryCode, UserStatus};
+    use crate::infra::testing::mock_handle;
+    use crate::infra::{DefaultUserUsecase, HS256JwtService, MockUserRepository};
+    use crate::usecases::{CreateUserCommand, InitialCredentials};
+
+    #[derive(Default)]
+    struct MockMfaRepository {
+        mfa: Mutex<Vec<UserMfa>>,
+        recovery_codes: Mutex<Vec<MfaRecoveryCode>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MfaRepository for MockMfaRepository {
+        async fn find_mfa(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+        ) -> shinespark::Result<Option<UserMfa>> {
+            Ok(self.mfa.lock().unwrap().iter().find(|m| m.user_id == user_id).cloned())
+        }
+
+        async fn save_pending_mfa(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+            secret_encrypted: &str,
+        ) -> shinespark::Result<UserMfa> {
+            let mut mfa = self.mfa.lock().unwrap();
+            mfa.retain(|m| m.user_id != user_id);
+            let row = UserMfa {
+                id: mfa.len() as i64 + 1,
+                user_id,
+                secret_encrypted: secret_encrypted.to_string(),
+                confirmed_at: None,
+                last_used_step: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            mfa.push(row.clone());
+            Ok(row)
+        }
+
+        async fn confirm_mfa(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+            step: i64,
+        ) -> shinespark::Result<()> {
+            if let Some(m) = self.mfa.lock().unwrap().iter_mut().find(|m| m.user_id == user_id) {
+                m.confirmed_at = Some(Utc::now());
+                m.last_used_step = Some(step);
+            }
+            Ok(())
+        }
+
+        async fn update_last_used_step(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+            step: i64,
+        ) -> shinespark::Result<bool> {
+            let mut mfa = self.mfa.lock().unwrap();
+            match mfa.iter_mut().find(|m| m.user_id == user_id) {
+                Some(m) if m.last_used_step.is_none_or(|last| last < step) => {
+                    m.last_used_step = Some(step);
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        async fn delete_mfa(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+        ) -> shinespark::Result<()> {
+            self.mfa.lock().unwrap().retain(|m| m.user_id != user_id);
+            self.recovery_codes.lock().unwrap().retain(|c| c.user_id != user_id);
+            Ok(())
+        }
+
+        async fn replace_recovery_codes(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+            code_hashes: &[String],
+        ) -> shinespark::Result<()> {
+            let mut codes = self.recovery_codes.lock().unwrap();
+            codes.retain(|c| c.user_id != user_id);
+            for code_hash in code_hashes {
+                let id = codes.len() as i64 + 1;
+                codes.push(MfaRecoveryCode {
+                    id,
+                    user_id,
+                    code_hash: code_hash.clone(),
+                    used_at: None,
+                    created_at: Utc::now(),
+                });
+            }
+            Ok(())
+        }
+
+        async fn list_unused_recovery_codes(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            user_id: i64,
+        ) -> shinespark::Result<Vec<MfaRecoveryCode>> {
+            Ok(self
+                .recovery_codes
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|c| c.user_id == user_id && c.used_at.is_none())
+                .cloned()
+                .collect())
+        }
+
+        async fn mark_recovery_code_used(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            id: i64,
+        ) -> shinespark::Result<bool> {
+            let mut codes = self.recovery_codes.lock().unwrap();
+            match codes.iter_mut().find(|c| c.id == id && c.used_at.is_none()) {
+                Some(c) => {
+                    c.used_at = Some(Utc::now());
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+    }
+
+    async fn setup(handle: &mut shinespark::db::Handle<'_>) -> (DefaultMfaUsecase, UserAggregate) {
+        let password_service = Arc::new(B64PasswordService::new());
+        let user_usecase = Arc::new(DefaultUserUsecase::new(
+            Arc::new(MockUserRepository::new()),
+            password_service.clone(),
+        ));
+        let jwt_service = Arc::new(HS256JwtService::new(&shinespark::config::JwtConfig {
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        }));
+        let usecase = DefaultMfaUsecase::new(
+            Arc::new(MockMfaRepository::default()),
+            user_usecase.clone(),
+            password_service,
+            jwt_service,
+            &MfaConfig {
+                recovery_code_count: 3,
+                ..Default::default()
+            },
+        );
+
+        let created = user_usecase
+            .create_user(
+                handle,
+                CreateUserCommand {
+                    name: "mfa".to_string(),
+                    email: "mfa@example.com".to_string(),
+                    credentials: InitialCredentials::Local {
+                        password: "pw".to_string(),
+                    },
+                    status: UserStatus::Active,
+                },
+            )
+            .await
+            .unwrap();
+        let user = UserAggregate {
+            user: created.user,
+            role_ids: vec![],
+            identities: created.identities,
+        };
+        (usecase, user)
+    }
+
+    fn code_at(enrollment: &MfaEnrollment, step_offset: i64) -> String {
+        let secret = totp::decode_secret(&enrollment.secret).unwrap();
+        let step = totp::step(DefaultMfaUsecase::now_secs()) as i64 + step_offset;
+        totp::code_at_step(&secret, step as u64)
+    }
+
+    #[tokio::test]
+    async fn test_enroll_and_verify_totp() {
+        let mut handle = mock_handle();
+        let (usecase, user) = setup(&mut handle).await;
+
+        let enrollment = usecase.begin_enrollment(&mut handle, &user).await.unwrap();
+        assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"));
+        assert!(!usecase.is_enabled(&mut handle, user.user.id).await.unwrap());
+
+        let wrong = usecase.confirm_enrollment(&mut handle, user.user.id, "000000").await;
+        assert!(wrong.is_err());
+
+        let codes = usecase
+            .confirm_enrollment(&mut handle, user.user.id, &code_at(&enrollment, -1))
+            .await
+            .unwrap();
+        assert_eq!(codes.len(), 3);
+        assert!(usecase.is_enabled(&mut handle, user.user.id).await.unwrap());
+
+        // 등록 확인에 쓴 step 이하의 코드는 재사용할 수 없다.
+        let replay = usecase.verify(&mut handle, user.user.id, &code_at(&enrollment, -1)).await;
+        assert!(matches!(replay, Err(shinespark::Error::InvalidCredentials)));
+
+        usecase.verify(&mut handle, user.user.id, &code_at(&enrollment, 0)).await.unwrap();
+        let replay = usecase.verify(&mut handle, user.user.id, &code_at(&enrollment, 0)).await;
+        assert!(matches!(replay, Err(shinespark::Error::InvalidCredentials)));
+
+        let again = usecase.begin_enrollment(&mut handle, &user).await;
+        assert!(matches!(again, Err(shinespark::Error::AlreadyExists)));
+    }
+
+    #[tokio::test]
+    async fn test_recovery_code_is_single_use() {
+        let mut handle = mock_handle();
+        let (usecase, user) = setup(&mut handle).await;
+
+        let enrollment = usecase.begin_enrollment(&mut handle, &user).await.unwrap();
+        let codes = usecase
+            .confirm_enrollment(&mut handle, user.user.id, &code_at(&enrollment, 0))
+            .await
+            .unwrap();
+
+        let recovery = codes[0].to_uppercase();
+        usecase.verify(&mut handle, user.user.id, &recovery).await.unwrap();
+        let reused = usecase.verify(&mut handle, user.user.id, &recovery).await;
+        assert!(matches!(reused, Err(shinespark::Error::InvalidCredentials)));
+
+        usecase.verify(&mut handle, user.user.id, &codes[1]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_challenge_login() {
+        let mut handle = mock_handle();
+        let (usecase, user) = setup(&mut handle).await;
+
+        let enrollment = usecase.begin_enrollment(&mut handle, &user).await.unwrap();
+        usecase
+            .confirm_enrollment(&mut handle, user.user.id, &code_at(&enrollment, -1))
+            .await
+            .unwrap();
+
+        let challenge = usecase.issue_challenge(&mut handle, &user).await.unwrap().unwrap();
+        assert_eq!(challenge.methods, vec![MfaMethod::Totp]);
+        let wrong = usecase
+            .verify_challenge(
+                &mut handle,
+                MfaLoginCommand {
+                    challenge_token: challenge.challenge_token.clone(),
+                    factor: MfaFactor::Code("abc".to_string()),
+                },
+            )
+            .await;
+        assert!(wrong.is_err());
+
+        let logged_in = usecase
+            .verify_challenge(
+                &mut handle,
+                MfaLoginCommand {
+                    challenge_token: challenge.challenge_token,
+                    factor: MfaFactor::Code(code_at(&enrollment, 0)),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(logged_in.user.uid, user.user.uid);
+
+        // 실패가 max_challenge_attempts 에 이르면 맞는 코드를 보내도 다시 로그인해야 한다.
+        let challenge = usecase.issue_challenge(&mut handle, &user).await.unwrap().unwrap();
+        let attempt = |code: String| MfaLoginCommand {
+            challenge_token: challenge.challenge_token.clone(),
+            factor: MfaFactor::Code(code),
+        };
+        for _ in 0..MfaConfig::default().max_challenge_attempts {
+            let wrong = usecase.verify_challenge(&mut handle, attempt("abc".into())).await;
+            assert!(matches!(wrong, Err(shinespark::Error::InvalidCredentials)));
+        }
+        let exhausted =
+            usecase.verify_challenge(&mut handle, attempt(code_at(&enrollment, 1))).await;
+        assert!(matches!(exhausted, Err(shinespark::Error::UnAuthorized)));
+
+        // access token 으로는 2 단계 인증을 건너뛸 수 없다.
+        let pair = usecase.jwt_service.create(&user).unwrap();
+        let bypass = usecase
+            .verify_challenge(
+                &mut handle,
+                MfaLoginCommand {
+                    challenge_token: pair.access_token,
+                    factor: MfaFactor::Code(code_at(&enrollment, 1)),
+                },
+            )
+            .await;
+        assert!(matches!(bypass, Err(shinespark::Error::UnAuthorized)));
+    }
+
+    #[tokio::test]
+    async fn test_disable_removes_mfa() {
+        let mut handle = mock_handle();
+        let (usecase, user) = setup(&mut handle).await;
+
+        let enrollment = usecase.begin_enrollment(&mut handle, &user).await.unwrap();
+        usecase
+            .confirm_enrollment(&mut handle, user.user.id, &code_at(&enrollment, -1))
+            .await
+            .unwrap();
+        usecase.disable(&mut handle, user.user.id, &code_at(&enrollment, 0)).await.unwrap();
+        assert!(!usecase.is_enabled(&mut handle, user.user.id).await.unwrap());
+    }
+}
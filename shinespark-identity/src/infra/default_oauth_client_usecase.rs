@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use shinespark::config::JwtConfig;
+use shinespark::crypto::{pkce, totp};
+
+use super::default_jwt_ident_usecase::sha256_hex;
+use crate::entities::OAuthClient;
+use crate::infra::jwt_service::JwtService;
+use crate::repositories::{NewOAuthClient, OAuthClientRepository};
+use crate::usecases::{
+    ClientAccessToken, ClientCredentialsCommand, OAuthClientUsecase, OIDC_SCOPES, RbacUsecase,
+    RegisterClientCommand, RegisteredClient,
+};
+
+pub struct DefaultOAuthClientUsecase {
+    client_repository: Arc<dyn OAuthClientRepository>,
+    rbac_usecase: Arc<dyn RbacUsecase>,
+    jwt_service: Arc<dyn JwtService>,
+    access_token_ttl_secs: i64,
+}
+
+impl DefaultOAuthClientUsecase {
+    pub fn new(
+        client_repository: Arc<dyn OAuthClientRepository>,
+        rbac_usecase: Arc<dyn RbacUsecase>,
+        jwt_service: Arc<dyn JwtService>,
+        config: &JwtConfig,
+    ) -> Self {
+        Self {
+            client_repository,
+            rbac_usecase,
+            jwt_service,
+            access_token_ttl_secs: config.access_token_ttl_secs,
+        }
+    }
+}
+
+/// UUID 와 겹치지 않도록 prefix 를 붙인다. (`sub` 로 사용자와 구분)
+fn generate_client_id() -> String {
+    format!(
+        "shs_client_{}",
+        &totp::encode_secret(&totp::generate_secret()).to_lowercase()[..16]
+    )
+}
+
+#[async_trait::async_trait]
+impl OAuthClientUsecase for DefaultOAuthClientUsecase {
+    async fn register_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: RegisterClientCommand,
+    ) -> shinespark::Result<RegisteredClient> {
+        if command.name.trim().is_empty() {
+            return Err(shinespark::Error::IllegalState(
+                "client name is empty".into(),
+            ));
+        }
+        for scope in &command.scopes {
+            if OIDC_SCOPES.contains(&scope.as_str()) {
+                continue;
+            }
+            if self.rbac_usecase.find_permission_by_code(handle, scope).await?.is_none() {
+                return Err(shinespark::Error::IllegalState(
+                    format!("unknown scope: {}", scope).into(),
+                ));
+            }
+        }
+        for redirect_uri in &command.redirect_uris {
+            // RFC 6749 3.1.2: 절대 URI 이고 fragment 가 없어야 한다.
+            let absolute =
+                redirect_uri.starts_with("https://") || redirect_uri.starts_with("http://");
+            if !absolute || redirect_uri.contains('#') || redirect_uri.contains(char::is_whitespace)
+            {
+                return Err(shinespark::Error::IllegalState(
+                    format!("invalid redirect_uri: {}", redirect_uri).into(),
+                ));
+            }
+        }
+
+        let client_secret = pkce::generate_code_verifier();
+        let client = self
+            .client_repository
+            .create_client(
+                handle,
+                NewOAuthClient {
+                    client_id: generate_client_id(),
+                    client_secret_hash: sha256_hex(&client_secret),
+                    name: command.name,
+                    scopes: command.scopes.join(" "),
+                    redirect_uris: command.redirect_uris.join(" "),
+                },
+            )
+            .await?;
+        Ok(RegisteredClient {
+            client,
+            client_secret,
+        })
+    }
+
+    async fn find_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+    ) -> shinespark::Result<Option<OAuthClient>> {
+        self.client_repository.find_client(handle, client_id).await
+    }
+
+    async fn authenticate_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+        client_secret: &str,
+    ) -> shinespark::Result<OAuthClient> {
+        let client = self
+            .client_repository
+            .find_client(handle, client_id)
+            .await?
+            .ok_or(shinespark::Error::InvalidCredentials)?;
+        if client.client_secret_hash != sha256_hex(client_secret) {
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+        Ok(client)
+    }
+
+    async fn issue_client_token(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: ClientCredentialsCommand,
+    ) -> shinespark::Result<ClientAccessToken> {
+        let client =
+            self.authenticate_client(handle, &command.client_id, &command.client_secret).await?;
+
+        let allowed = client.scope_list();
+        let scopes = match command.scope.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(requested) => {
+                let requested: Vec<String> =
+                    requested.split_whitespace().map(str::to_string).collect();
+                if requested.iter().any(|s| !allowed.contains(s)) {
+                    return Err(shinespark::Error::UnAuthorized);
+                }
+                requested
+            }
+            None => allowed,
+        };
+
+        let access_token = self.jwt_service.create_client_token(
+            &client.client_id,
+            &scopes,
+            self.access_token_ttl_secs,
+        )?;
+        Ok(ClientAccessToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.access_token_ttl_secs,
+            scope: scopes.join(" "),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::HS256JwtService;
+    use crate::infra::jwt_service::SubjectType;
+    use crate::infra::testing::{MockOAuthClientRepository, MockRbacUsecase, mock_handle};
+
+    fn setup() -> (
+        DefaultOAuthClientUsecase,
+        Arc<dyn JwtService>,
+        MockRbacUsecase,
+    ) {
+        let config = JwtConfig {
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        };
+        let jwt_service: Arc<dyn JwtService> = Arc::new(HS256JwtService::new(&config));
+        let rbac = || MockRbacUsecase::new(&[(1, &["user.read.all", "user.create.all"])]);
+        let usecase = DefaultOAuthClientUsecase::new(
+            Arc::new(MockOAuthClientRepository::default()),
+            Arc::new(rbac()),
+            jwt_service.clone(),
+            &config,
+        );
+        (usecase, jwt_service, rbac())
+    }
+
+    fn register_command(scopes: &[&str]) -> RegisterClientCommand {
+        RegisterClientCommand {
+            name: "batch".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            redirect_uris: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials_token() {
+        let mut handle = mock_handle();
+        let (usecase, jwt_service, rbac) = setup();
+
+        let registered = usecase
+            .register_client(
+                &mut handle,
+                register_command(&["user.read.all", "user.create.all"]),
+            )
+            .await
+            .unwrap();
+        assert_ne!(
+            registered.client.client_secret_hash,
+            registered.client_secret
+        );
+
+        let token = usecase
+            .issue_client_token(
+                &mut handle,
+                ClientCredentialsCommand {
+                    client_id: registered.client.client_id.clone(),
+                    client_secret: registered.client_secret.clone(),
+                    scope: Some("user.read.all".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(token.scope, "user.read.all");
+
+        let claims = jwt_service.verify(&token.access_token).unwrap();
+        assert_eq!(claims.sub, registered.client.client_id);
+        assert_eq!(claims.sub_type, SubjectType::Client);
+        // role 없이 발급받은 scope 로만 권한을 판단한다.
+        assert!(claims.has_perm(&rbac, "user.read.all"));
+        assert!(!claims.has_perm(&rbac, "user.create.all"));
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials_rejects_bad_secret_and_scope() {
+        let mut handle = mock_handle();
+        let (usecase, _, _) = setup();
+
+        assert!(
+            usecase
+                .register_client(&mut handle, register_command(&["unknown.scope"]))
+                .await
+                .is_err()
+        );
+
+        let registered = usecase
+            .register_client(&mut handle, register_command(&["user.read.all"]))
+            .await
+            .unwrap();
+        let command = |secret: &str, scope: Option<&str>| ClientCredentialsCommand {
+            client_id: registered.client.client_id.clone(),
+            client_secret: secret.to_string(),
+            scope: scope.map(str::to_string),
+        };
+
+        assert!(matches!(
+            usecase.issue_client_token(&mut handle, command("wrong", None)).await,
+            Err(shinespark::Error::InvalidCredentials)
+        ));
+        assert!(matches!(
+            usecase
+                .issue_client_token(
+                    &mut handle,
+                    command(&registered.client_secret, Some("user.create.all"))
+                )
+                .await,
+            Err(shinespark::Error::UnAuthorized)
+        ));
+        let token = usecase
+            .issue_client_token(&mut handle, command(&registered.client_secret, None))
+            .await
+            .unwrap();
+        assert_eq!(token.scope, "user.read.all");
+    }
+}
@@ -209,7 +209,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_load_and_check_perm() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -242,7 +242,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_assign_role_to_user_role_not_found() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -253,7 +253,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_create_and_delete_permission() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -292,7 +292,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_create_and_delete_role() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -331,7 +331,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_delete_permission_cascades_role_permission() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -363,7 +363,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_assign_and_revoke_permission_by_name_code() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -390,7 +390,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_list_permissions_and_roles() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -405,7 +405,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_add_and_remove_permission_refreshes_cache() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let usecase = make_usecase();
@@ -5,7 +5,7 @@ use shinespark::crypto::password::PasswordService;
 use crate::entities::{User, UserAggregate, UserIdentity, UserWithIdentities};
 use crate::repositories::UserRepository;
 use crate::usecases::{
-    CreateUserCommand, FindUserQuery, InitialCredentials, UpdateUserCommand,
+    CreateUserCommand, FindUserQuery, InitialCredentials, LinkIdentityCommand, UpdateUserCommand,
     UserUsecase,
 };
 
@@ -81,6 +81,31 @@ impl<T: UserRepository + ?Sized, P: PasswordService> UserUsecase for DefaultUser
         Ok(user)
     }
 
+    async fn link_identity(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: LinkIdentityCommand,
+    ) -> shinespark::Result<UserIdentity> {
+        if !command.provider.is_social() {
+            return Err(shinespark::Error::IllegalState(
+                format!("{} identity cannot be linked", command.provider.as_str()).into(),
+            ));
+        }
+        if self
+            .user_repository
+            .find_user_by_identity(handle, command.provider.clone(), command.provider_uid.clone())
+            .await?
+            .is_some()
+        {
+            return Err(shinespark::Error::AlreadyExists);
+        }
+        self.user_repository
+            .create_identity(
+                handle,
+                UserIdentity::new(command.user_id, command.provider, command.provider_uid, None),
+            )
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,476 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use shinespark::config::WebAuthnConfig;
+
+use crate::entities::{AuthProvider, UserAggregate, UserIdentity, WebAuthnCeremony};
+use crate::infra::webauthn::{
+    self, AuthenticatorData, CLIENT_DATA_CREATE, CLIENT_DATA_GET, CollectedClientData, CoseKey,
+    SUPPORTED_ALGORITHMS,
+};
+use crate::repositories::{UserRepository, WebAuthnRepository};
+use crate::usecases::{
+    AssertionCredential, AuthenticatorSelection, CredentialCreationOptions, CredentialDescriptor,
+    CredentialRequestOptions, PubKeyCredParam, RegistrationCredential, RelyingParty,
+    WebAuthnUsecase, WebAuthnUserEntity,
+};
+
+/// `shs_iam_user_identity.provider_uid` 컬럼 길이
+const MAX_CREDENTIAL_ID_LEN: usize = 255;
+
+pub struct DefaultWebAuthnUsecase {
+    webauthn_repository: Arc<dyn WebAuthnRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    config: WebAuthnConfig,
+}
+
+impl DefaultWebAuthnUsecase {
+    pub fn new(
+        webauthn_repository: Arc<dyn WebAuthnRepository>,
+        user_repository: Arc<dyn UserRepository>,
+        config: &WebAuthnConfig,
+    ) -> Self {
+        Self {
+            webauthn_repository,
+            user_repository,
+            config: config.clone(),
+        }
+    }
+
+    async fn issue_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: Option<i64>,
+        ceremony: WebAuthnCeremony,
+    ) -> shinespark::Result<String> {
+        let challenge = webauthn::generate_challenge()?;
+        let expires_at = Utc::now() + Duration::seconds(self.config.challenge_ttl_secs);
+        self.webauthn_repository
+            .save_challenge(handle, &challenge, user_id, ceremony, expires_at)
+            .await?;
+        Ok(challenge)
+    }
+
+    fn passkeys(user: &UserAggregate) -> Vec<CredentialDescriptor> {
+        user.identities
+            .iter()
+            .filter(|identity| identity.provider == AuthProvider::Passkey)
+            .map(|identity| CredentialDescriptor::public_key(identity.provider_uid.clone()))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl WebAuthnUsecase for DefaultWebAuthnUsecase {
+    async fn start_registration(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+    ) -> shinespark::Result<CredentialCreationOptions> {
+        let challenge = self
+            .issue_challenge(handle, Some(user.user.id), WebAuthnCeremony::Registration)
+            .await?;
+        Ok(CredentialCreationOptions {
+            challenge,
+            rp: RelyingParty {
+                id: self.config.rp_id.clone(),
+                name: self.config.rp_name.clone(),
+            },
+            user: WebAuthnUserEntity {
+                id: webauthn::encode(user.user.uid.as_bytes()),
+                name: user.user.email.clone(),
+                display_name: user.user.name.clone(),
+            },
+            pub_key_cred_params: SUPPORTED_ALGORITHMS
+                .iter()
+                .map(|alg| PubKeyCredParam {
+                    type_: "public-key".to_string(),
+                    alg: *alg,
+                })
+                .collect(),
+            timeout: self.config.timeout_ms,
+            exclude_credentials: Self::passkeys(user),
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "preferred".to_string(),
+                user_verification: "preferred".to_string(),
+            },
+            attestation: "none".to_string(),
+        })
+    }
+
+    async fn finish_registration(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+        credential: RegistrationCredential,
+    ) -> shinespark::Result<UserIdentity> {
+        let client_data_json = webauthn::decode(&credential.response.client_data_json)?;
+        let client_data = CollectedClientData::parse(&client_data_json)?;
+        client_data.verify(CLIENT_DATA_CREATE, &self.config.origin)?;
+
+        let challenge = self
+            .webauthn_repository
+            .take_challenge(
+                handle,
+                &client_data.challenge,
+                WebAuthnCeremony::Registration,
+            )
+            .await?
+            .ok_or(shinespark::Error::InvalidCredentials)?;
+        if challenge.user_id != Some(user.user.id) {
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+
+        let auth_data = webauthn::parse_attestation_object(&webauthn::decode(
+            &credential.response.attestation_object,
+        )?)?;
+        let auth_data = AuthenticatorData::parse(&auth_data)?;
+        auth_data.verify(&self.config.rp_id, false)?;
+        let attested =
+            auth_data.attested_credential.ok_or(shinespark::Error::InvalidCredentials)?;
+        CoseKey::parse(&attested.public_key)?;
+
+        let credential_id = webauthn::encode(&attested.credential_id);
+        if credential_id != credential.id || credential_id.len() > MAX_CREDENTIAL_ID_LEN {
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+        if self
+            .user_repository
+            .find_user_by_identity(handle, AuthProvider::Passkey, credential_id.clone())
+            .await?
+            .is_some()
+        {
+            return Err(shinespark::Error::AlreadyExists);
+        }
+
+        self.user_repository
+            .create_identity(
+                handle,
+                UserIdentity::passkey(
+                    user.user.id,
+                    credential_id,
+                    webauthn::encode(&attested.public_key),
+                    auth_data.sign_count as i64,
+                ),
+            )
+            .await
+    }
+
+    async fn start_authentication(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: Option<&UserAggregate>,
+    ) -> shinespark::Result<CredentialRequestOptions> {
+        let allow_credentials = user.map(Self::passkeys).unwrap_or_default();
+        if user.is_some() && allow_credentials.is_empty() {
+            return Err(shinespark::Error::NotFound);
+        }
+        let challenge = self
+            .issue_challenge(
+                handle,
+                user.map(|u| u.user.id),
+                WebAuthnCeremony::Authentication,
+            )
+            .await?;
+        Ok(CredentialRequestOptions {
+            challenge,
+            rp_id: self.config.rp_id.clone(),
+            timeout: self.config.timeout_ms,
+            allow_credentials,
+            // passwordless 로그인은 passkey 하나로 두 요소를 대신하므로 사용자 확인이 필요하다.
+            user_verification: if user.is_some() {
+                "preferred"
+            } else {
+                "required"
+            }
+            .to_string(),
+        })
+    }
+
+    async fn finish_authentication(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: Option<&UserAggregate>,
+        credential: AssertionCredential,
+    ) -> shinespark::Result<UserAggregate> {
+        let client_data_json = webauthn::decode(&credential.response.client_data_json)?;
+        let client_data = CollectedClientData::parse(&client_data_json)?;
+        client_data.verify(CLIENT_DATA_GET, &self.config.origin)?;
+
+        let challenge = self
+            .webauthn_repository
+            .take_challenge(
+                handle,
+                &client_data.challenge,
+                WebAuthnCeremony::Authentication,
+            )
+            .await?
+            .ok_or(shinespark::Error::InvalidCredentials)?;
+        if challenge.user_id != user.map(|u| u.user.id) {
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+
+        let owner = self
+            .user_repository
+            .find_user_by_identity(handle, AuthProvider::Passkey, credential.id.clone())
+            .await?
+            .ok_or(shinespark::Error::InvalidCredentials)?;
+        if user.is_some_and(|u| u.user.id != owner.user.id) {
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+        let identity = owner
+            .identities
+            .iter()
+            .find(|i| i.provider == AuthProvider::Passkey && i.provider_uid == credential.id)
+            .ok_or(shinespark::Error::InvalidCredentials)?;
+        let public_key = identity
+            .credential_public_key
+            .as_deref()
+            .ok_or(shinespark::Error::InvalidCredentials)?;
+
+        let auth_data_bytes = webauthn::decode(&credential.response.authenticator_data)?;
+        let auth_data = AuthenticatorData::parse(&auth_data_bytes)?;
+        auth_data.verify(&self.config.rp_id, user.is_none())?;
+
+        CoseKey::parse(&webauthn::decode(public_key)?)?.verify(
+            &webauthn::signed_message(&auth_data_bytes, &client_data_json),
+            &webauthn::decode(&credential.response.signature)?,
+        )?;
+
+        // 카운터를 쓰지 않는 authenticator 는 항상 0 을 보낸다. 그 외에는 증가해야 한다.
+        let stored = identity.sign_count.unwrap_or(0);
+        let received = auth_data.sign_count as i64;
+        if (received != 0 || stored != 0) && received <= stored {
+            tracing::warn!(
+                "passkey sign count did not increase (user_id={}, stored={}, received={})",
+                owner.user.id,
+                stored,
+                received
+            );
+            return Err(shinespark::Error::InvalidCredentials);
+        }
+        if received != stored {
+            self.user_repository.update_sign_count(handle, identity.id, received).await?;
+        }
+        Ok(owner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use chrono::DateTime;
+    use shinespark::crypto::password::B64PasswordService;
+
+    use crate::entities::{UserStatus, WebAuthnChallenge};
+    use crate::infra::testing::mock_handle;
+    use crate::infra::webauthn::testing::{TestAuthenticator, USER_PRESENT, USER_PRESENT_VERIFIED};
+    use crate::infra::{DefaultUserUsecase, MockUserRepository};
+    use crate::usecases::{
+        AssertionResponse, AttestationResponse, CreateUserCommand, InitialCredentials, UserUsecase,
+    };
+
+    #[derive(Default)]
+    struct MockWebAuthnRepository {
+        challenges: Mutex<Vec<WebAuthnChallenge>>,
+    }
+
+    #[async_trait::async_trait]
+    impl WebAuthnRepository for MockWebAuthnRepository {
+        async fn save_challenge(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            challenge: &str,
+            user_id: Option<i64>,
+            ceremony: WebAuthnCeremony,
+            expires_at: DateTime<Utc>,
+        ) -> shinespark::Result<()> {
+            let mut challenges = self.challenges.lock().unwrap();
+            let id = challenges.len() as i64 + 1;
+            challenges.push(WebAuthnChallenge {
+                id,
+                challenge: challenge.to_string(),
+                user_id,
+                ceremony,
+                expires_at,
+                created_at: Utc::now(),
+            });
+            Ok(())
+        }
+
+        async fn take_challenge(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            challenge: &str,
+            ceremony: WebAuthnCeremony,
+        ) -> shinespark::Result<Option<WebAuthnChallenge>> {
+            let mut challenges = self.challenges.lock().unwrap();
+            let index = challenges.iter().position(|c| {
+                c.challenge == challenge && c.ceremony == ceremony && c.expires_at > Utc::now()
+            });
+            Ok(index.map(|i| challenges.remove(i)))
+        }
+    }
+
+    const ORIGIN: &str = "http://localhost:8085";
+
+    async fn setup(
+        handle: &mut shinespark::db::Handle<'_>,
+    ) -> (
+        DefaultWebAuthnUsecase,
+        Arc<MockUserRepository>,
+        UserAggregate,
+    ) {
+        let user_repository = Arc::new(MockUserRepository::new());
+        let usecase = DefaultWebAuthnUsecase::new(
+            Arc::new(MockWebAuthnRepository::default()),
+            user_repository.clone(),
+            &WebAuthnConfig::default(),
+        );
+        let created =
+            DefaultUserUsecase::new(user_repository.clone(), Arc::new(B64PasswordService::new()))
+                .create_user(
+                    handle,
+                    CreateUserCommand {
+                        name: "passkey".to_string(),
+                        email: "passkey@example.com".to_string(),
+                        credentials: InitialCredentials::Local {
+                            password: "pw".to_string(),
+                        },
+                        status: UserStatus::Active,
+                    },
+                )
+                .await
+                .unwrap();
+        let user = UserAggregate {
+            user: created.user,
+            role_ids: vec![],
+            identities: created.identities,
+        };
+        (usecase, user_repository, user)
+    }
+
+    async fn register(
+        usecase: &DefaultWebAuthnUsecase,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+        authenticator: &TestAuthenticator,
+    ) -> UserIdentity {
+        let options = usecase.start_registration(handle, user).await.unwrap();
+        usecase
+            .finish_registration(
+                handle,
+                user,
+                RegistrationCredential {
+                    id: webauthn::encode(&authenticator.credential_id),
+                    response: AttestationResponse {
+                        client_data_json: webauthn::encode(&TestAuthenticator::client_data(
+                            CLIENT_DATA_CREATE,
+                            &options.challenge,
+                            ORIGIN,
+                        )),
+                        attestation_object: webauthn::encode(
+                            &authenticator.attestation_object("localhost"),
+                        ),
+                    },
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    fn assertion(
+        authenticator: &TestAuthenticator,
+        challenge: &str,
+        flags: u8,
+    ) -> AssertionCredential {
+        let client_data = TestAuthenticator::client_data(CLIENT_DATA_GET, challenge, ORIGIN);
+        let auth_data = authenticator.auth_data("localhost", flags, false);
+        AssertionCredential {
+            id: webauthn::encode(&authenticator.credential_id),
+            response: AssertionResponse {
+                client_data_json: webauthn::encode(&client_data),
+                authenticator_data: webauthn::encode(&auth_data),
+                signature: webauthn::encode(&authenticator.sign(&auth_data, &client_data)),
+                user_handle: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_passwordless_login() {
+        let mut handle = mock_handle();
+        let (usecase, user_repository, user) = setup(&mut handle).await;
+        let mut authenticator = TestAuthenticator::new();
+
+        let identity = register(&usecase, &mut handle, &user, &authenticator).await;
+        assert_eq!(identity.provider, AuthProvider::Passkey);
+        assert!(identity.credential_public_key.is_some());
+
+        authenticator.sign_count = 1;
+        let options = usecase.start_authentication(&mut handle, None).await.unwrap();
+        assert_eq!(options.user_verification, "required");
+
+        // user verification 없이는 passwordless 로그인 불가
+        let no_uv = assertion(&authenticator, &options.challenge, USER_PRESENT);
+        assert!(usecase.finish_authentication(&mut handle, None, no_uv).await.is_err());
+
+        let options = usecase.start_authentication(&mut handle, None).await.unwrap();
+        let credential = assertion(&authenticator, &options.challenge, USER_PRESENT_VERIFIED);
+        let logged_in =
+            usecase.finish_authentication(&mut handle, None, credential.clone()).await.unwrap();
+        assert_eq!(logged_in.user.id, user.user.id);
+
+        let stored = user_repository.identities.lock().unwrap()[1].sign_count;
+        assert_eq!(stored, Some(1));
+
+        // 같은 challenge 는 다시 쓸 수 없다.
+        assert!(usecase.finish_authentication(&mut handle, None, credential).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_count_must_increase() {
+        let mut handle = mock_handle();
+        let (usecase, _, user) = setup(&mut handle).await;
+        let mut authenticator = TestAuthenticator::new();
+        authenticator.sign_count = 5;
+        register(&usecase, &mut handle, &user, &authenticator).await;
+
+        let options = usecase.start_authentication(&mut handle, None).await.unwrap();
+        let cloned = assertion(&authenticator, &options.challenge, USER_PRESENT_VERIFIED);
+        let result = usecase.finish_authentication(&mut handle, None, cloned).await;
+        assert!(matches!(result, Err(shinespark::Error::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_second_factor_requires_own_credential() {
+        let mut handle = mock_handle();
+        let (usecase, user_repository, user) = setup(&mut handle).await;
+        let authenticator = TestAuthenticator::new();
+        register(&usecase, &mut handle, &user, &authenticator).await;
+        let user = user_repository
+            .find_user_by_identity(
+                &mut handle,
+                AuthProvider::Passkey,
+                webauthn::encode(&authenticator.credential_id),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        let options = usecase.start_authentication(&mut handle, Some(&user)).await.unwrap();
+        assert_eq!(options.allow_credentials.len(), 1);
+
+        // passwordless 용 challenge 로 2 단계 인증을 할 수 없다.
+        let passwordless = usecase.start_authentication(&mut handle, None).await.unwrap();
+        let wrong = assertion(&authenticator, &passwordless.challenge, USER_PRESENT);
+        assert!(usecase.finish_authentication(&mut handle, Some(&user), wrong).await.is_err());
+
+        let credential = assertion(&authenticator, &options.challenge, USER_PRESENT);
+        let verified =
+            usecase.finish_authentication(&mut handle, Some(&user), credential).await.unwrap();
+        assert_eq!(verified.user.id, user.user.id);
+    }
+}
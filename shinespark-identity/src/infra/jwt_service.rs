@@ -4,13 +4,81 @@ use serde::{Deserialize, Serialize};
 use shinespark::config::JwtConfig;
 
 use crate::entities::UserAggregate;
+use crate::usecases::RbacUsecase;
+
+/// `sub` 가 무엇을 가리키는지. client_credentials 로 발급된 토큰은 사용자가 없다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectType {
+    #[default]
+    User, // sub = user UUID
+    Client, // sub = OAuth client_id
+}
+
+/// RFC 8693 4.1 `act` claim. 대리 로그인한 관리자
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorClaim {
+    pub sub: String, // 관리자 user UUID
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtClaims {
-    pub sub: String, // user UUID
+    pub sub: String, // user UUID 또는 client_id (sub_type 참고)
+    #[serde(default)]
+    pub sub_type: SubjectType,
     pub exp: usize,
     pub roles: Option<Vec<i64>>,
-    pub token_type: String, // "access" | "refresh"
+    pub token_type: String, // "access" | "refresh" | "mfa_challenge" | "api_key"
+    /// API key 처럼 권한이 제한된 경우의 허용 권한. None 이면 roles 의 권한을 모두 갖는다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    /// OAuth client 에 발급된 토큰(client_credentials, authorization_code)이면 그 client_id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// 대리 로그인 토큰이면 실제로 요청하는 관리자. `sub` 는 대상 사용자이다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+}
+
+impl JwtClaims {
+    pub fn is_impersonated(&self) -> bool {
+        self.act.is_some()
+    }
+
+    /// roles 가 권한을 갖고, 제한된 권한 목록이 있다면 그 안에도 포함되어야 한다.
+    /// service principal(client)은 role 이 없으므로 발급받은 scope 만으로 판단한다.
+    pub fn has_perm(&self, rbac: &dyn RbacUsecase, permission: &str) -> bool {
+        if self.sub_type == SubjectType::Client {
+            return self
+                .permissions
+                .as_ref()
+                .is_some_and(|allowed| allowed.iter().any(|p| p == permission || p == "*.*.all"));
+        }
+        let role_ids = self.roles.as_deref().unwrap_or_default();
+        if !rbac.check_perm(role_ids, permission) {
+            return false;
+        }
+        match &self.permissions {
+            Some(allowed) => allowed.iter().any(|p| p == permission || p == "*.*.all"),
+            None => true,
+        }
+    }
+}
+
+/// OIDC Core 2. ID Token. `aud` 는 client_id 이다. `IdTokenSigner` 가 서명한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String, // user UUID
+    pub aud: String,
+    pub exp: usize,
+    pub iat: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -22,6 +90,35 @@ pub struct JwtTokenPair {
 
 pub trait JwtService: Send + Sync + 'static {
     fn create(&self, aggregate: &UserAggregate) -> shinespark::Result<JwtTokenPair>;
+    /// 2 단계 인증 대기용 토큰. roles 가 없고 `token_type` 이 "mfa_challenge" 이다.
+    fn create_mfa_challenge(
+        &self,
+        aggregate: &UserAggregate,
+        ttl_secs: i64,
+    ) -> shinespark::Result<String>;
+    /// client_credentials grant 로 발급하는 access token. `sub_type` 이 client 이고 scope 가 `permissions` 에 담긴다.
+    fn create_client_token(
+        &self,
+        client_id: &str,
+        scopes: &[String],
+        ttl_secs: i64,
+    ) -> shinespark::Result<String>;
+    /// authorization_code grant 로 외부 앱에 위임하는 access token.
+    /// 사용자의 roles 를 갖되 승인된 scope 만 `permissions` 로 허용하고, `client_id` 를 남긴다.
+    fn create_delegated_token(
+        &self,
+        aggregate: &UserAggregate,
+        client_id: &str,
+        scopes: &[String],
+        ttl_secs: i64,
+    ) -> shinespark::Result<String>;
+    /// 관리자의 대리 로그인용 access token. `sub` 는 대상 사용자, `act` 는 관리자이고 refresh token 은 없다.
+    fn create_impersonation_token(
+        &self,
+        target: &UserAggregate,
+        actor_uid: &str,
+        ttl_secs: i64,
+    ) -> shinespark::Result<String>;
     fn verify(&self, token: &str) -> shinespark::Result<JwtClaims>;
     /// 서명은 유효하지만 만료된 토큰인지 확인. 서명 자체가 무효하면 false.
     fn is_expired(&self, token: &str) -> bool;
@@ -57,9 +154,13 @@ impl JwtService for HS256JwtService {
             &header,
             &JwtClaims {
                 sub: aggregate.user.uid.to_string(),
+                sub_type: SubjectType::User,
                 exp: access_exp,
                 roles: Some(aggregate.role_ids.clone()),
                 token_type: "access".to_string(),
+                permissions: None,
+                client_id: None,
+                act: None,
             },
             &encoding_key,
         )
@@ -71,9 +172,13 @@ impl JwtService for HS256JwtService {
             &header,
             &JwtClaims {
                 sub: aggregate.user.uid.to_string(),
+                sub_type: SubjectType::User,
                 exp: refresh_exp,
                 roles: None,
                 token_type: "refresh".to_string(),
+                permissions: None,
+                client_id: None,
+                act: None,
             },
             &encoding_key,
         )
@@ -90,6 +195,120 @@ impl JwtService for HS256JwtService {
         })
     }
 
+    fn create_mfa_challenge(
+        &self,
+        aggregate: &UserAggregate,
+        ttl_secs: i64,
+    ) -> shinespark::Result<String> {
+        let exp = (Utc::now() + Duration::seconds(ttl_secs)).timestamp() as usize;
+        encode(
+            &Header::new(Algorithm::HS256),
+            &JwtClaims {
+                sub: aggregate.user.uid.to_string(),
+                sub_type: SubjectType::User,
+                exp,
+                roles: None,
+                token_type: "mfa_challenge".to_string(),
+                permissions: None,
+                client_id: None,
+                act: None,
+            },
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|e| {
+            shinespark::Error::Internal(
+                anyhow::anyhow!(e).context("failed to encode mfa challenge token"),
+            )
+        })
+    }
+
+    fn create_client_token(
+        &self,
+        client_id: &str,
+        scopes: &[String],
+        ttl_secs: i64,
+    ) -> shinespark::Result<String> {
+        let exp = (Utc::now() + Duration::seconds(ttl_secs)).timestamp() as usize;
+        encode(
+            &Header::new(Algorithm::HS256),
+            &JwtClaims {
+                sub: client_id.to_string(),
+                sub_type: SubjectType::Client,
+                exp,
+                roles: None,
+                token_type: "access".to_string(),
+                permissions: Some(scopes.to_vec()),
+                client_id: Some(client_id.to_string()),
+                act: None,
+            },
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|e| {
+            shinespark::Error::Internal(
+                anyhow::anyhow!(e).context("failed to encode client access token"),
+            )
+        })
+    }
+
+    fn create_delegated_token(
+        &self,
+        aggregate: &UserAggregate,
+        client_id: &str,
+        scopes: &[String],
+        ttl_secs: i64,
+    ) -> shinespark::Result<String> {
+        let exp = (Utc::now() + Duration::seconds(ttl_secs)).timestamp() as usize;
+        encode(
+            &Header::new(Algorithm::HS256),
+            &JwtClaims {
+                sub: aggregate.user.uid.to_string(),
+                sub_type: SubjectType::User,
+                exp,
+                roles: Some(aggregate.role_ids.clone()),
+                token_type: "access".to_string(),
+                permissions: Some(scopes.to_vec()),
+                client_id: Some(client_id.to_string()),
+                act: None,
+            },
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|e| {
+            shinespark::Error::Internal(
+                anyhow::anyhow!(e).context("failed to encode delegated access token"),
+            )
+        })
+    }
+
+    fn create_impersonation_token(
+        &self,
+        target: &UserAggregate,
+        actor_uid: &str,
+        ttl_secs: i64,
+    ) -> shinespark::Result<String> {
+        let exp = (Utc::now() + Duration::seconds(ttl_secs)).timestamp() as usize;
+        encode(
+            &Header::new(Algorithm::HS256),
+            &JwtClaims {
+                sub: target.user.uid.to_string(),
+                sub_type: SubjectType::User,
+                exp,
+                roles: Some(target.role_ids.clone()),
+                token_type: "access".to_string(),
+                permissions: None,
+                client_id: None,
+                act: Some(ActorClaim {
+                    sub: actor_uid.to_string(),
+                }),
+            },
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|e| {
+            shinespark::Error::Internal(
+                anyhow::anyhow!(e).context("failed to encode impersonation token"),
+            )
+        })
+    }
+
     fn verify(&self, token: &str) -> shinespark::Result<JwtClaims> {
         let mut validation = Validation::new(Algorithm::HS256);
         validation.leeway = 0;
@@ -168,6 +387,54 @@ mod tests {
         assert_eq!(claims.roles, None);
     }
 
+    #[test]
+    fn test_create_and_verify_mfa_challenge() {
+        let svc = make_service();
+        let agg = make_aggregate();
+        let token = svc.create_mfa_challenge(&agg, 300).unwrap();
+        let claims = svc.verify(&token).unwrap();
+        assert_eq!(claims.sub, agg.user.uid.to_string());
+        assert_eq!(claims.token_type, "mfa_challenge");
+        assert_eq!(claims.roles, None);
+    }
+
+    #[test]
+    fn test_create_and_verify_client_token() {
+        let svc = make_service();
+        let token = svc.create_client_token("svc-a", &["user.read.all".to_string()], 300).unwrap();
+        let claims = svc.verify(&token).unwrap();
+        assert_eq!(claims.sub, "svc-a");
+        assert_eq!(claims.sub_type, SubjectType::Client);
+        assert_eq!(claims.token_type, "access");
+        assert_eq!(claims.permissions, Some(vec!["user.read.all".to_string()]));
+    }
+
+    #[test]
+    fn test_create_and_verify_delegated_token() {
+        let svc = make_service();
+        let agg = make_aggregate();
+        let token =
+            svc.create_delegated_token(&agg, "shs_client_a", &["openid".to_string()], 300).unwrap();
+        let claims = svc.verify(&token).unwrap();
+        assert_eq!(claims.sub, agg.user.uid.to_string());
+        assert_eq!(claims.sub_type, SubjectType::User);
+        assert_eq!(claims.roles, Some(vec![1, 2]));
+        assert_eq!(claims.permissions, Some(vec!["openid".to_string()]));
+        assert_eq!(claims.client_id.as_deref(), Some("shs_client_a"));
+    }
+
+    #[test]
+    fn test_create_and_verify_impersonation_token() {
+        let svc = make_service();
+        let agg = make_aggregate();
+        let token = svc.create_impersonation_token(&agg, "admin-uid", 300).unwrap();
+        let claims = svc.verify(&token).unwrap();
+        assert_eq!(claims.sub, agg.user.uid.to_string());
+        assert_eq!(claims.token_type, "access");
+        assert!(claims.is_impersonated());
+        assert_eq!(claims.act.unwrap().sub, "admin-uid");
+    }
+
     #[test]
     fn test_tampered_token_returns_error() {
         let svc = make_service();
@@ -98,4 +98,17 @@ impl UserRepository for MockUserRepository {
             Ok(None)
         }
     }
+
+    async fn update_sign_count(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        identity_id: i64,
+        sign_count: i64,
+    ) -> shinespark::Result<()> {
+        let mut identities = self.identities.lock().unwrap();
+        let identity =
+            identities.iter_mut().find(|i| i.id == identity_id).ok_or(shinespark::Error::NotFound)?;
+        identity.sign_count = Some(sign_count);
+        Ok(())
+    }
 }
@@ -0,0 +1,311 @@
+use std::sync::Arc;
+
+use crate::entities::{AuthProvider, UserAggregate, UserStatus};
+use crate::usecases::{
+    ConfirmSocialLinkCommand, CreateUserCommand, FindUserQuery, InitialCredentials,
+    LinkIdentityCommand, LoginCommand, LoginUsecase, PendingSocialLink, RbacUsecase,
+    SocialLinkUsecase, SocialLoginOutcome, SocialProfile, UserUsecase,
+};
+
+/// social login 공통 처리 — 연결된 identity 로 로그인하고, 없으면 계정 연결 또는 자동 회원가입한다.
+///
+/// 같은 email 의 기존 계정이 있으면 provider 가 email 을 확인한 경우에만 바로 연결하고,
+/// 그렇지 않으면 `LinkRequired` 로 돌려 기존 계정의 비밀번호 확인을 받는다.
+pub struct SocialAccountResolver {
+    user_usecase: Arc<dyn UserUsecase>,
+    login_usecase: Arc<dyn LoginUsecase>,
+    rbac_usecase: Arc<dyn RbacUsecase>,
+}
+
+impl SocialAccountResolver {
+    pub fn new(
+        user_usecase: Arc<dyn UserUsecase>,
+        login_usecase: Arc<dyn LoginUsecase>,
+        rbac_usecase: Arc<dyn RbacUsecase>,
+    ) -> Self {
+        Self {
+            user_usecase,
+            login_usecase,
+            rbac_usecase,
+        }
+    }
+
+    pub async fn resolve(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        profile: SocialProfile,
+    ) -> shinespark::Result<SocialLoginOutcome> {
+        let login_result = self
+            .login_usecase
+            .login(
+                handle,
+                LoginCommand::Social {
+                    provider: profile.provider.clone(),
+                    provider_uid: profile.provider_uid.clone(),
+                },
+            )
+            .await;
+
+        match login_result {
+            Ok(user) => Ok(SocialLoginOutcome::LoggedIn(user)),
+            Err(shinespark::Error::NotFound) => {
+                let existing = match profile.email.as_deref().filter(|email| !email.is_empty()) {
+                    Some(email) => {
+                        self.user_usecase
+                            .find_user(handle, FindUserQuery::new().email(email.to_string()))
+                            .await?
+                    }
+                    None => None,
+                };
+                match existing {
+                    Some(existing) => self.link_existing(handle, existing, profile).await,
+                    None => self.create(handle, profile).await.map(SocialLoginOutcome::LoggedIn),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn link_existing(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        existing: UserAggregate,
+        profile: SocialProfile,
+    ) -> shinespark::Result<SocialLoginOutcome> {
+        if existing.user.status != UserStatus::Active {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        if !profile.email_verified {
+            return Ok(SocialLoginOutcome::LinkRequired(PendingSocialLink {
+                user_uid: existing.user.uid,
+                email: existing.user.email,
+                provider: profile.provider,
+                provider_uid: profile.provider_uid,
+            }));
+        }
+        self.link(
+            handle,
+            existing.user.id,
+            profile.provider,
+            profile.provider_uid,
+        )
+        .await
+        .map(SocialLoginOutcome::LoggedIn)
+    }
+
+    async fn link(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        provider: AuthProvider,
+        provider_uid: String,
+    ) -> shinespark::Result<UserAggregate> {
+        self.user_usecase
+            .link_identity(
+                handle,
+                LinkIdentityCommand {
+                    user_id,
+                    provider,
+                    provider_uid,
+                },
+            )
+            .await?;
+        self.user_usecase
+            .find_user(handle, FindUserQuery::new().id(user_id))
+            .await?
+            .ok_or(shinespark::Error::NotFound)
+    }
+
+    async fn create(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        profile: SocialProfile,
+    ) -> shinespark::Result<UserAggregate> {
+        let created = self
+            .user_usecase
+            .create_user(
+                handle,
+                CreateUserCommand {
+                    name: profile
+                        .name
+                        .unwrap_or_else(|| format!("{} User", profile.provider.as_str())),
+                    email: profile.email.unwrap_or_default(),
+                    credentials: InitialCredentials::Social {
+                        provider: profile.provider,
+                        provider_uid: profile.provider_uid,
+                    },
+                    status: UserStatus::Active,
+                },
+            )
+            .await?;
+        self.rbac_usecase.assign_role_to_user(handle, created.user.id, "user").await?;
+        self.user_usecase
+            .find_user(handle, FindUserQuery::new().id(created.user.id))
+            .await?
+            .ok_or_else(|| shinespark::Error::IllegalState("user not found after creation".into()))
+    }
+}
+
+#[async_trait::async_trait]
+impl SocialLinkUsecase for SocialAccountResolver {
+    async fn confirm_link(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        cmd: ConfirmSocialLinkCommand,
+    ) -> shinespark::Result<UserAggregate> {
+        let user = self
+            .login_usecase
+            .login(
+                handle,
+                LoginCommand::Local {
+                    email: cmd.link.email,
+                    password: cmd.password,
+                },
+            )
+            .await?;
+        if user.user.uid != cmd.link.user_uid || user.user.status != UserStatus::Active {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        self.link(
+            handle,
+            user.user.id,
+            cmd.link.provider,
+            cmd.link.provider_uid,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shinespark::crypto::password::B64PasswordService;
+
+    use crate::infra::testing::mock_handle;
+    use crate::infra::{
+        DefaultLoginUsecase, DefaultRbacUsecase, DefaultUserUsecase, MockUserRepository,
+        SqlxRbacRepository,
+    };
+    use crate::repositories::UserRepository;
+
+    fn setup() -> (SocialAccountResolver, Arc<MockUserRepository>) {
+        let password_service = Arc::new(B64PasswordService::new());
+        let user_repository = Arc::new(MockUserRepository::new());
+        let resolver = SocialAccountResolver::new(
+            Arc::new(DefaultUserUsecase::new(
+                user_repository.clone(),
+                password_service.clone(),
+            )),
+            Arc::new(DefaultLoginUsecase::new(
+                user_repository.clone(),
+                password_service,
+            )),
+            Arc::new(DefaultRbacUsecase::new(Arc::new(SqlxRbacRepository::new()))),
+        );
+        (resolver, user_repository)
+    }
+
+    async fn create_local_user(
+        resolver: &SocialAccountResolver,
+        handle: &mut shinespark::db::Handle<'_>,
+    ) -> UserAggregate {
+        let created = resolver
+            .user_usecase
+            .create_user(
+                handle,
+                CreateUserCommand {
+                    name: "local".to_string(),
+                    email: "linked@example.com".to_string(),
+                    credentials: InitialCredentials::Local {
+                        password: "local_password".to_string(),
+                    },
+                    status: UserStatus::Active,
+                },
+            )
+            .await
+            .unwrap();
+        UserAggregate {
+            user: created.user,
+            role_ids: vec![],
+            identities: created.identities,
+        }
+    }
+
+    fn google_profile(email_verified: bool) -> SocialProfile {
+        SocialProfile {
+            provider: AuthProvider::Google,
+            provider_uid: "google-sub-1".to_string(),
+            email: Some("linked@example.com".to_string()),
+            email_verified,
+            name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_link_when_email_verified() {
+        let (resolver, user_repository) = setup();
+        let mut handle = mock_handle();
+        let local = create_local_user(&resolver, &mut handle).await;
+
+        let outcome = resolver.resolve(&mut handle, google_profile(true)).await.unwrap();
+        let SocialLoginOutcome::LoggedIn(user) = outcome else {
+            panic!("expected auto link");
+        };
+        assert_eq!(user.user.uid, local.user.uid);
+
+        let linked = user_repository
+            .find_user_by_identity(
+                &mut handle,
+                AuthProvider::Google,
+                "google-sub-1".to_string(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(linked.user.id, local.user.id);
+    }
+
+    #[tokio::test]
+    async fn test_link_requires_password_when_email_not_verified() {
+        let (resolver, user_repository) = setup();
+        let mut handle = mock_handle();
+        let local = create_local_user(&resolver, &mut handle).await;
+
+        let outcome = resolver.resolve(&mut handle, google_profile(false)).await.unwrap();
+        let SocialLoginOutcome::LinkRequired(link) = outcome else {
+            panic!("expected link confirmation");
+        };
+        assert_eq!(link.user_uid, local.user.uid);
+        assert_eq!(user_repository.users.lock().unwrap().len(), 1);
+
+        let wrong = resolver
+            .confirm_link(
+                &mut handle,
+                ConfirmSocialLinkCommand {
+                    link: link.clone(),
+                    password: "wrong".to_string(),
+                },
+            )
+            .await;
+        assert!(matches!(wrong, Err(shinespark::Error::InvalidCredentials)));
+
+        let user = resolver
+            .confirm_link(
+                &mut handle,
+                ConfirmSocialLinkCommand {
+                    link,
+                    password: "local_password".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(user.user.id, local.user.id);
+        assert!(
+            user_repository
+                .find_user_by_identity(&mut handle, AuthProvider::Google, "google-sub-1".into())
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+}
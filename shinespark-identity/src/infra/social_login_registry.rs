@@ -0,0 +1,229 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use shinespark::config::{OidcProviderConfig, SocialProviderConfig};
+
+use crate::entities::AuthProvider;
+use crate::infra::{DefaultGoogleLoginUsecase, DefaultOidcLoginUsecase};
+use crate::usecases::{LoginUsecase, RbacUsecase, SocialLoginUsecase, UserUsecase};
+
+/// provider 별 social login usecase 목록.
+///
+/// 경로의 `{provider}` 이름으로 usecase 를 찾으므로 provider 를 추가할 때 route 를 고칠 필요가 없다.
+#[derive(Default, Clone)]
+pub struct SocialLoginRegistry {
+    usecases: HashMap<AuthProvider, Arc<dyn SocialLoginUsecase>>,
+}
+
+impl SocialLoginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `social_providers` 설정으로 registry 를 만든다. key 가 provider 이름이다.
+    /// `google` 구현은 `google` key 로만 등록할 수 있다.
+    pub fn from_config(
+        providers: &BTreeMap<String, SocialProviderConfig>,
+        user_usecase: Arc<dyn UserUsecase>,
+        login_usecase: Arc<dyn LoginUsecase>,
+        rbac_usecase: Arc<dyn RbacUsecase>,
+    ) -> shinespark::Result<Self> {
+        let mut registry = Self::new();
+        for (name, provider) in providers {
+            let usecase: Arc<dyn SocialLoginUsecase> = match provider {
+                SocialProviderConfig::Google(config) => {
+                    if name != AuthProvider::Google.as_str() {
+                        return Err(shinespark::Error::IllegalState(
+                            format!("google provider must be named google, not {}", name).into(),
+                        ));
+                    }
+                    Arc::new(DefaultGoogleLoginUsecase::new(
+                        config.clone(),
+                        user_usecase.clone(),
+                        login_usecase.clone(),
+                        rbac_usecase.clone(),
+                    ))
+                }
+                SocialProviderConfig::Oidc(config) => Arc::new(DefaultOidcLoginUsecase::new(
+                    OidcProviderConfig {
+                        name: name.clone(),
+                        ..config.clone()
+                    },
+                    user_usecase.clone(),
+                    login_usecase.clone(),
+                    rbac_usecase.clone(),
+                )?),
+            };
+            registry.register(usecase)?;
+        }
+        Ok(registry)
+    }
+
+    /// 같은 provider 가 이미 등록되어 있으면 `AlreadyExists`. `local`, `passkey` 는 등록할 수 없다.
+    pub fn register(&mut self, usecase: Arc<dyn SocialLoginUsecase>) -> shinespark::Result<()> {
+        let provider = usecase.provider();
+        if !provider.is_social() {
+            return Err(shinespark::Error::IllegalState(
+                format!(
+                    "{} provider cannot be used for social login",
+                    provider.as_str()
+                )
+                .into(),
+            ));
+        }
+        if self.usecases.contains_key(&provider) {
+            return Err(shinespark::Error::AlreadyExists);
+        }
+        self.usecases.insert(provider, usecase);
+        Ok(())
+    }
+
+    pub fn get(&self, provider: &AuthProvider) -> Option<Arc<dyn SocialLoginUsecase>> {
+        self.usecases.get(provider).cloned()
+    }
+
+    /// 경로 등에서 받은 provider 이름으로 찾는다.
+    pub fn find(&self, name: &str) -> Option<Arc<dyn SocialLoginUsecase>> {
+        let provider = AuthProvider::try_from(name.to_string()).ok()?;
+        self.get(&provider)
+    }
+
+    pub fn providers(&self) -> Vec<AuthProvider> {
+        self.usecases.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::crypto::password::B64PasswordService;
+
+    use super::*;
+    use crate::infra::testing::MockRbacUsecase;
+    use crate::infra::{DefaultLoginUsecase, DefaultUserUsecase, MockUserRepository};
+    use crate::usecases::{
+        SocialCallbackCommand, SocialLoginCommand, SocialLoginOutcome, SocialProfile,
+    };
+
+    struct StubSocialLoginUsecase(AuthProvider);
+
+    #[async_trait::async_trait]
+    impl SocialLoginUsecase for StubSocialLoginUsecase {
+        fn provider(&self) -> AuthProvider {
+            self.0.clone()
+        }
+
+        async fn login(&self, _cmd: SocialLoginCommand) -> shinespark::Result<String> {
+            Ok(format!("https://idp.example/{}", self.0.as_str()))
+        }
+
+        async fn authenticate(
+            &self,
+            _cmd: SocialCallbackCommand,
+        ) -> shinespark::Result<SocialProfile> {
+            Err(shinespark::Error::NotImplemented)
+        }
+
+        async fn resolve(
+            &self,
+            _handle: &mut shinespark::db::Handle<'_>,
+            _profile: SocialProfile,
+        ) -> shinespark::Result<SocialLoginOutcome> {
+            Err(shinespark::Error::NotImplemented)
+        }
+    }
+
+    #[test]
+    fn test_register_and_find() {
+        let mut registry = SocialLoginRegistry::new();
+        registry.register(Arc::new(StubSocialLoginUsecase(AuthProvider::Google))).unwrap();
+        registry
+            .register(Arc::new(StubSocialLoginUsecase(AuthProvider::Other(
+                "github".to_string(),
+            ))))
+            .unwrap();
+
+        assert_eq!(
+            registry.find("google").unwrap().provider(),
+            AuthProvider::Google
+        );
+        assert_eq!(
+            registry.find("github").unwrap().provider(),
+            AuthProvider::Other("github".to_string())
+        );
+        assert!(registry.find("apple").is_none());
+        assert!(registry.find("../etc").is_none());
+        assert_eq!(registry.providers().len(), 2);
+    }
+
+    #[test]
+    fn test_from_config_uses_table_key_as_name() {
+        let password_service = Arc::new(B64PasswordService::new());
+        let user_repository = Arc::new(MockUserRepository::new());
+        let user_usecase = Arc::new(DefaultUserUsecase::new(
+            user_repository.clone(),
+            password_service.clone(),
+        ));
+        let login_usecase = Arc::new(DefaultLoginUsecase::new(user_repository, password_service));
+        let rbac_usecase = Arc::new(MockRbacUsecase::new(&[]));
+        let from_config = |providers: &[(&str, SocialProviderConfig)]| {
+            SocialLoginRegistry::from_config(
+                &providers.iter().map(|(name, p)| (name.to_string(), p.clone())).collect(),
+                user_usecase.clone(),
+                login_usecase.clone(),
+                rbac_usecase.clone(),
+            )
+        };
+
+        let registry = from_config(&[
+            ("google", SocialProviderConfig::Google(Default::default())),
+            ("corp-sso", SocialProviderConfig::Oidc(Default::default())),
+        ])
+        .unwrap();
+        assert_eq!(
+            registry.find("corp-sso").unwrap().provider(),
+            AuthProvider::Other("corp-sso".to_string())
+        );
+        assert!(registry.find("google").is_some());
+
+        let renamed_google =
+            from_config(&[("gmail", SocialProviderConfig::Google(Default::default()))]);
+        assert!(matches!(
+            renamed_google,
+            Err(shinespark::Error::IllegalState(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_rejects_duplicate_and_local() {
+        let mut registry = SocialLoginRegistry::new();
+        registry.register(Arc::new(StubSocialLoginUsecase(AuthProvider::Google))).unwrap();
+
+        let duplicated = registry.register(Arc::new(StubSocialLoginUsecase(AuthProvider::Google)));
+        assert!(matches!(duplicated, Err(shinespark::Error::AlreadyExists)));
+
+        let local = registry.register(Arc::new(StubSocialLoginUsecase(AuthProvider::Local)));
+        assert!(matches!(local, Err(shinespark::Error::IllegalState(_))));
+    }
+
+    #[test]
+    fn test_auth_provider_name_round_trip() {
+        for name in ["local", "google", "apple", "github", "corp-sso"] {
+            let provider = AuthProvider::try_from(name.to_string()).unwrap();
+            assert_eq!(provider.as_str(), name);
+            assert_eq!(String::from(provider), name);
+        }
+        assert_eq!(
+            AuthProvider::try_from("apple".to_string()).unwrap(),
+            AuthProvider::Apple
+        );
+        assert!(AuthProvider::try_from(String::new()).is_err());
+        assert!(AuthProvider::try_from("GitHub".to_string()).is_err());
+
+        let json = serde_json::to_string(&AuthProvider::Other("github".to_string())).unwrap();
+        assert_eq!(json, "\"github\"");
+        let parsed: AuthProvider = serde_json::from_str("\"google\"").unwrap();
+        assert_eq!(parsed, AuthProvider::Google);
+    }
+}
@@ -0,0 +1,160 @@
+use shinespark::db::SqlStatement;
+
+use crate::entities::ApiKey;
+use crate::repositories::{ApiKeyRepository, NewApiKey};
+
+#[derive(Debug)]
+enum ApiKeyQuery {
+    CreateApiKey,
+    FindApiKeyByHash,
+    ListApiKeys,
+    DeleteApiKey,
+    TouchLastUsed,
+}
+
+impl SqlStatement for ApiKeyQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyQuery::CreateApiKey => {
+                shinespark::include_sql!("api_key_repository/create_api_key.sql")
+            }
+            ApiKeyQuery::FindApiKeyByHash => {
+                shinespark::include_sql!("api_key_repository/find_api_key_by_hash.sql")
+            }
+            ApiKeyQuery::ListApiKeys => {
+                shinespark::include_sql!("api_key_repository/list_api_keys.sql")
+            }
+            ApiKeyQuery::DeleteApiKey => {
+                shinespark::include_sql!("api_key_repository/delete_api_key.sql")
+            }
+            ApiKeyQuery::TouchLastUsed => {
+                shinespark::include_sql!("api_key_repository/touch_last_used.sql")
+            }
+        }
+    }
+}
+
+/// mysql 에는 `RETURNING` 이 없으므로 모든 드라이버에서 쓰고 난 뒤 `key_hash` 로 다시 읽는다.
+pub struct SqlxApiKeyRepository {}
+
+impl SqlxApiKeyRepository {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyRepository for SqlxApiKeyRepository {
+    async fn create_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        new_key: NewApiKey,
+    ) -> shinespark::Result<ApiKey> {
+        ApiKeyQuery::CreateApiKey
+            .as_query()
+            .bind(new_key.user_id)
+            .bind(new_key.name)
+            .bind(new_key.prefix)
+            .bind(&new_key.key_hash)
+            .bind(new_key.permissions)
+            .bind(new_key.expires_at)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        ApiKeyQuery::FindApiKeyByHash
+            .as_query_as::<ApiKey>()
+            .bind(&new_key.key_hash)
+            .fetch_one(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn find_api_key_by_hash(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        key_hash: &str,
+    ) -> shinespark::Result<Option<ApiKey>> {
+        ApiKeyQuery::FindApiKeyByHash
+            .as_query_as::<ApiKey>()
+            .bind(key_hash)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn list_api_keys(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<ApiKey>> {
+        ApiKeyQuery::ListApiKeys
+            .as_query_as::<ApiKey>()
+            .bind(user_id)
+            .fetch_all(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn delete_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        prefix: &str,
+    ) -> shinespark::Result<bool> {
+        let result = ApiKeyQuery::DeleteApiKey
+            .as_query()
+            .bind(prefix)
+            .bind(user_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch_last_used(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        id: i64,
+    ) -> shinespark::Result<()> {
+        ApiKeyQuery::TouchLastUsed
+            .as_query()
+            .bind(id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                ApiKeyQuery::CreateApiKey,
+                ApiKeyQuery::FindApiKeyByHash,
+                ApiKeyQuery::ListApiKeys,
+                ApiKeyQuery::DeleteApiKey,
+                ApiKeyQuery::TouchLastUsed,
+            ],
+            |statement, check| match statement {
+                ApiKeyQuery::CreateApiKey => check.params(6),
+                ApiKeyQuery::FindApiKeyByHash | ApiKeyQuery::ListApiKeys => {
+                    check.params(1).returns::<ApiKey>()
+                }
+                ApiKeyQuery::DeleteApiKey => check.params(2),
+                ApiKeyQuery::TouchLastUsed => check.params(1),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
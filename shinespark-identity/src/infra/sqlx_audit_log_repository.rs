@@ -0,0 +1,68 @@
+use shinespark::db::SqlStatement;
+
+use crate::repositories::{AuditLogRepository, NewUserAuditLog};
+
+#[derive(Debug)]
+enum AuditLogQuery {
+    Record,
+}
+
+impl SqlStatement for AuditLogQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditLogQuery::Record => shinespark::include_sql!("audit_log_repository/record.sql"),
+        }
+    }
+}
+
+pub struct SqlxAuditLogRepository {}
+
+impl SqlxAuditLogRepository {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditLogRepository for SqlxAuditLogRepository {
+    async fn record(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        log: NewUserAuditLog,
+    ) -> shinespark::Result<()> {
+        AuditLogQuery::Record
+            .as_query()
+            .bind(log.user_id)
+            .bind(log.action.as_str())
+            .bind(log.description)
+            .bind(log.ip_address)
+            .bind(log.user_agent)
+            .bind(log.is_success)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [AuditLogQuery::Record],
+            |statement, check| match statement {
+                AuditLogQuery::Record => check.params(6),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
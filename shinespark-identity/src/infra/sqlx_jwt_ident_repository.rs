@@ -3,6 +3,45 @@ use shinespark::db::SqlStatement;
 
 use crate::repositories::{JwtIdentRepository, RefreshTokenRow};
 
+#[derive(Debug)]
+enum JwtIdentQuery {
+    SaveRefreshToken,
+    FindRefreshToken,
+    DeleteByUserUid,
+    DeleteRefreshToken,
+    RevokeAccessToken,
+    DeleteExpiredRevokedTokens,
+    IsAccessTokenRevoked,
+}
+
+impl SqlStatement for JwtIdentQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JwtIdentQuery::SaveRefreshToken => {
+                shinespark::include_sql!("jwt_ident_repository/save_refresh_token.sql")
+            }
+            JwtIdentQuery::FindRefreshToken => {
+                shinespark::include_sql!("jwt_ident_repository/find_refresh_token.sql")
+            }
+            JwtIdentQuery::DeleteByUserUid => {
+                shinespark::include_sql!("jwt_ident_repository/delete_by_user_uid.sql")
+            }
+            JwtIdentQuery::DeleteRefreshToken => {
+                shinespark::include_sql!("jwt_ident_repository/delete_refresh_token.sql")
+            }
+            JwtIdentQuery::RevokeAccessToken => {
+                shinespark::include_sql!("jwt_ident_repository/revoke_access_token.sql")
+            }
+            JwtIdentQuery::DeleteExpiredRevokedTokens => {
+                shinespark::include_sql!("jwt_ident_repository/delete_expired_revoked_tokens.sql")
+            }
+            JwtIdentQuery::IsAccessTokenRevoked => {
+                shinespark::include_sql!("jwt_ident_repository/is_access_token_revoked.sql")
+            }
+        }
+    }
+}
+
 pub struct SqlxJwtIdentRepository {}
 
 impl SqlxJwtIdentRepository {
@@ -24,19 +63,14 @@ impl JwtIdentRepository for SqlxJwtIdentRepository {
             shinespark::Error::Internal(anyhow::anyhow!(e).context("invalid user_uid"))
         })?;
 
-        r#"
-        INSERT INTO
-            shs_iam_refresh_token (user_uid, token_hash, expires_at)
-        VALUES ($1, $2, $3)
-        ON CONFLICT (token_hash) DO NOTHING
-        "#
-        .as_query_as::<(i64,)>()
-        .bind(uid)
-        .bind(token_hash)
-        .bind(expires_at)
-        .fetch_optional(handle.inner())
-        .await
-        .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        JwtIdentQuery::SaveRefreshToken
+            .as_query()
+            .bind(uid)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
 
         Ok(())
     }
@@ -46,20 +80,12 @@ impl JwtIdentRepository for SqlxJwtIdentRepository {
         handle: &mut shinespark::db::Handle<'_>,
         token_hash: &str,
     ) -> shinespark::Result<Option<RefreshTokenRow>> {
-        r#"
-        SELECT
-            id, user_uid, token_hash, expires_at, created_at
-        FROM
-            shs_iam_refresh_token
-        WHERE 1=1
-            AND token_hash = $1
-            AND expires_at > NOW()
-        "#
-        .as_query_as::<RefreshTokenRow>()
-        .bind(token_hash)
-        .fetch_optional(handle.inner())
-        .await
-        .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+        JwtIdentQuery::FindRefreshToken
+            .as_query_as::<RefreshTokenRow>()
+            .bind(token_hash)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
     }
 
     async fn delete_by_user_uid(
@@ -71,24 +97,79 @@ impl JwtIdentRepository for SqlxJwtIdentRepository {
             shinespark::Error::Internal(anyhow::anyhow!(e).context("invalid user_uid"))
         })?;
 
-        "DELETE FROM shs_iam_refresh_token WHERE user_uid = $1"
-            .as_query_as::<(i64,)>()
+        JwtIdentQuery::DeleteByUserUid
+            .as_query()
             .bind(uid)
-            .fetch_optional(handle.inner())
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<()> {
+        JwtIdentQuery::DeleteRefreshToken
+            .as_query()
+            .bind(token_hash)
+            .execute(handle.inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
 
         Ok(())
     }
+
+    async fn revoke_access_token(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> shinespark::Result<()> {
+        JwtIdentQuery::DeleteExpiredRevokedTokens
+            .as_query()
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        JwtIdentQuery::RevokeAccessToken
+            .as_query()
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn is_access_token_revoked(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<bool> {
+        let row = JwtIdentQuery::IsAccessTokenRevoked
+            .as_query_as::<(i64,)>()
+            .bind(token_hash)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        Ok(row.is_some())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use shinespark::db::check_statements;
+
     use super::*;
     use crate::repositories::JwtIdentRepository;
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_save_and_find_refresh_token() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let repo = SqlxJwtIdentRepository::new();
@@ -105,7 +186,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_delete_by_user_uid() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let repo = SqlxJwtIdentRepository::new();
@@ -120,4 +201,48 @@ mod tests {
         let row = repo.find_refresh_token(&mut handle, &hash).await.unwrap();
         assert!(row.is_none());
     }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_revoke_access_token() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        let repo = SqlxJwtIdentRepository::new();
+        let mut handle = db.handle();
+
+        let hash = uuid::Uuid::new_v4().to_string();
+        let expires_at = Utc::now() + chrono::Duration::minutes(30);
+
+        assert!(!repo.is_access_token_revoked(&mut handle, &hash).await.unwrap());
+        repo.revoke_access_token(&mut handle, &hash, expires_at).await.unwrap();
+        assert!(repo.is_access_token_revoked(&mut handle, &hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                JwtIdentQuery::SaveRefreshToken,
+                JwtIdentQuery::FindRefreshToken,
+                JwtIdentQuery::DeleteByUserUid,
+                JwtIdentQuery::DeleteRefreshToken,
+                JwtIdentQuery::RevokeAccessToken,
+                JwtIdentQuery::DeleteExpiredRevokedTokens,
+                JwtIdentQuery::IsAccessTokenRevoked,
+            ],
+            |statement, check| match statement {
+                JwtIdentQuery::SaveRefreshToken => check.params(3),
+                JwtIdentQuery::FindRefreshToken => check.params(1).returns::<RefreshTokenRow>(),
+                JwtIdentQuery::DeleteByUserUid => check.params(1),
+                JwtIdentQuery::DeleteRefreshToken => check.params(1),
+                JwtIdentQuery::RevokeAccessToken => check.params(2),
+                JwtIdentQuery::DeleteExpiredRevokedTokens => check.params(0),
+                JwtIdentQuery::IsAccessTokenRevoked => check.params(1).returns::<(i64,)>(),
+            },
+        )
+        .await
+        .unwrap();
+    }
 }
@@ -0,0 +1,230 @@
+use shinespark::db::SqlStatement;
+
+use crate::entities::{MfaRecoveryCode, UserMfa};
+use crate::repositories::MfaRepository;
+
+#[derive(Debug)]
+enum MfaQuery {
+    FindMfa,
+    SavePendingMfa,
+    ConfirmMfa,
+    UpdateLastUsedStep,
+    DeleteMfa,
+    DeleteRecoveryCodes,
+    ListUnusedRecoveryCodes,
+    MarkRecoveryCodeUsed,
+}
+
+impl SqlStatement for MfaQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MfaQuery::FindMfa => shinespark::include_sql!("mfa_repository/find_mfa.sql"),
+            MfaQuery::SavePendingMfa => {
+                shinespark::include_sql!("mfa_repository/save_pending_mfa.sql")
+            }
+            MfaQuery::ConfirmMfa => shinespark::include_sql!("mfa_repository/confirm_mfa.sql"),
+            MfaQuery::UpdateLastUsedStep => {
+                shinespark::include_sql!("mfa_repository/update_last_used_step.sql")
+            }
+            MfaQuery::DeleteMfa => shinespark::include_sql!("mfa_repository/delete_mfa.sql"),
+            MfaQuery::DeleteRecoveryCodes => {
+                shinespark::include_sql!("mfa_repository/delete_recovery_codes.sql")
+            }
+            MfaQuery::ListUnusedRecoveryCodes => {
+                shinespark::include_sql!("mfa_repository/list_unused_recovery_codes.sql")
+            }
+            MfaQuery::MarkRecoveryCodeUsed => {
+                shinespark::include_sql!("mfa_repository/mark_recovery_code_used.sql")
+            }
+        }
+    }
+}
+
+/// mysql 에는 `RETURNING` 이 없으므로 모든 드라이버에서 쓰고 난 뒤 `user_id` 로 다시 읽는다.
+pub struct SqlxMfaRepository {}
+
+impl SqlxMfaRepository {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl MfaRepository for SqlxMfaRepository {
+    async fn find_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Option<UserMfa>> {
+        MfaQuery::FindMfa
+            .as_query_as::<UserMfa>()
+            .bind(user_id)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn save_pending_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        secret_encrypted: &str,
+    ) -> shinespark::Result<UserMfa> {
+        MfaQuery::SavePendingMfa
+            .as_query()
+            .bind(user_id)
+            .bind(secret_encrypted)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        MfaQuery::FindMfa
+            .as_query_as::<UserMfa>()
+            .bind(user_id)
+            .fetch_one(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn confirm_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        step: i64,
+    ) -> shinespark::Result<()> {
+        MfaQuery::ConfirmMfa
+            .as_query()
+            .bind(step)
+            .bind(user_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+
+    async fn update_last_used_step(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        step: i64,
+    ) -> shinespark::Result<bool> {
+        let result = MfaQuery::UpdateLastUsedStep
+            .as_query()
+            .bind(step)
+            .bind(user_id)
+            .bind(step)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<()> {
+        MfaQuery::DeleteRecoveryCodes
+            .as_query()
+            .bind(user_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        MfaQuery::DeleteMfa
+            .as_query()
+            .bind(user_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code_hashes: &[String],
+    ) -> shinespark::Result<()> {
+        MfaQuery::DeleteRecoveryCodes
+            .as_query()
+            .bind(user_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        shinespark::db::insert_values(
+            handle,
+            "INSERT INTO shs_iam_mfa_recovery_code (user_id, code_hash) ",
+            2,
+            code_hashes,
+            |mut row, code_hash| {
+                row.push_bind(user_id).push_bind(code_hash);
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn list_unused_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<MfaRecoveryCode>> {
+        MfaQuery::ListUnusedRecoveryCodes
+            .as_query_as::<MfaRecoveryCode>()
+            .bind(user_id)
+            .fetch_all(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn mark_recovery_code_used(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        id: i64,
+    ) -> shinespark::Result<bool> {
+        let result = MfaQuery::MarkRecoveryCodeUsed
+            .as_query()
+            .bind(id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                MfaQuery::FindMfa,
+                MfaQuery::SavePendingMfa,
+                MfaQuery::ConfirmMfa,
+                MfaQuery::UpdateLastUsedStep,
+                MfaQuery::DeleteMfa,
+                MfaQuery::DeleteRecoveryCodes,
+                MfaQuery::ListUnusedRecoveryCodes,
+                MfaQuery::MarkRecoveryCodeUsed,
+            ],
+            |statement, check| match statement {
+                MfaQuery::FindMfa => check.params(1).returns::<UserMfa>(),
+                MfaQuery::SavePendingMfa | MfaQuery::ConfirmMfa => check.params(2),
+                MfaQuery::UpdateLastUsedStep => check.params(3),
+                MfaQuery::DeleteMfa
+                | MfaQuery::DeleteRecoveryCodes
+                | MfaQuery::MarkRecoveryCodeUsed => check.params(1),
+                MfaQuery::ListUnusedRecoveryCodes => check.params(1).returns::<MfaRecoveryCode>(),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use shinespark::db::SqlStatement;
+
+use crate::entities::{OAuthAuthorizationCode, OAuthClient};
+use crate::repositories::{
+    AuthorizationCodeRepository, NewAuthorizationCode, NewOAuthClient, OAuthClientRepository,
+};
+
+#[derive(Debug)]
+enum OAuthClientQuery {
+    CreateClient,
+    FindClient,
+    DeleteExpiredCodes,
+    SaveCode,
+    #[cfg(not(feature = "db-driver-mysql"))]
+    TakeCode,
+    #[cfg(feature = "db-driver-mysql")]
+    FindCodeForUpdate,
+    #[cfg(feature = "db-driver-mysql")]
+    DeleteCode,
+}
+
+impl SqlStatement for OAuthClientQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OAuthClientQuery::CreateClient => {
+                shinespark::include_sql!("oauth_client_repository/create_client.sql")
+            }
+            OAuthClientQuery::FindClient => {
+                shinespark::include_sql!("oauth_client_repository/find_client.sql")
+            }
+            OAuthClientQuery::DeleteExpiredCodes => {
+                shinespark::include_sql!("oauth_client_repository/delete_expired_codes.sql")
+            }
+            OAuthClientQuery::SaveCode => {
+                shinespark::include_sql!("oauth_client_repository/save_code.sql")
+            }
+            #[cfg(not(feature = "db-driver-mysql"))]
+            OAuthClientQuery::TakeCode => {
+                shinespark::include_sql!("oauth_client_repository/take_code.sql")
+            }
+            #[cfg(feature = "db-driver-mysql")]
+            OAuthClientQuery::FindCodeForUpdate => {
+                shinespark::include_sql!("oauth_client_repository/find_code_for_update.sql")
+            }
+            #[cfg(feature = "db-driver-mysql")]
+            OAuthClientQuery::DeleteCode => {
+                shinespark::include_sql!("oauth_client_repository/delete_code.sql")
+            }
+        }
+    }
+}
+
+/// mysql 에는 `RETURNING` 이 없으므로 client 는 쓰고 난 뒤 `client_id` 로 다시 읽는다.
+/// code 는 postgres, sqlite 에서 `DELETE ... RETURNING` 한 번으로, mysql 에서는 트랜잭션 안에서
+/// `SELECT ... FOR UPDATE` 로 잠근 뒤 지워서 한 번만 꺼낼 수 있게 한다.
+pub struct SqlxOAuthClientRepository {}
+
+impl SqlxOAuthClientRepository {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthClientRepository for SqlxOAuthClientRepository {
+    async fn create_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        new_client: NewOAuthClient,
+    ) -> shinespark::Result<OAuthClient> {
+        OAuthClientQuery::CreateClient
+            .as_query()
+            .bind(&new_client.client_id)
+            .bind(new_client.client_secret_hash)
+            .bind(new_client.name)
+            .bind(new_client.scopes)
+            .bind(new_client.redirect_uris)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        OAuthClientQuery::FindClient
+            .as_query_as::<OAuthClient>()
+            .bind(&new_client.client_id)
+            .fetch_one(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    async fn find_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+    ) -> shinespark::Result<Option<OAuthClient>> {
+        OAuthClientQuery::FindClient
+            .as_query_as::<OAuthClient>()
+            .bind(client_id)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeRepository for SqlxOAuthClientRepository {
+    async fn save_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        new_code: NewAuthorizationCode,
+    ) -> shinespark::Result<()> {
+        OAuthClientQuery::DeleteExpiredCodes
+            .as_query()
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        OAuthClientQuery::SaveCode
+            .as_query()
+            .bind(new_code.code_hash)
+            .bind(new_code.client_id)
+            .bind(new_code.user_id)
+            .bind(new_code.redirect_uri)
+            .bind(new_code.scope)
+            .bind(new_code.code_challenge)
+            .bind(new_code.nonce)
+            .bind(new_code.expires_at)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "db-driver-mysql"))]
+    async fn take_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        code_hash: &str,
+    ) -> shinespark::Result<Option<OAuthAuthorizationCode>> {
+        OAuthClientQuery::TakeCode
+            .as_query_as::<OAuthAuthorizationCode>()
+            .bind(code_hash)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    #[cfg(feature = "db-driver-mysql")]
+    async fn take_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        code_hash: &str,
+    ) -> shinespark::Result<Option<OAuthAuthorizationCode>> {
+        let mut tx = handle.begin().await?;
+        let code = OAuthClientQuery::FindCodeForUpdate
+            .as_query_as::<OAuthAuthorizationCode>()
+            .bind(code_hash)
+            .fetch_optional(tx.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        let Some(code) = code else {
+            return Ok(None);
+        };
+        let deleted = OAuthClientQuery::DeleteCode
+            .as_query()
+            .bind(code.id)
+            .execute(tx.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        tx.commit().await?;
+        Ok((deleted.rows_affected() == 1).then_some(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                OAuthClientQuery::CreateClient,
+                OAuthClientQuery::FindClient,
+                OAuthClientQuery::DeleteExpiredCodes,
+                OAuthClientQuery::SaveCode,
+                #[cfg(not(feature = "db-driver-mysql"))]
+                OAuthClientQuery::TakeCode,
+                #[cfg(feature = "db-driver-mysql")]
+                OAuthClientQuery::FindCodeForUpdate,
+                #[cfg(feature = "db-driver-mysql")]
+                OAuthClientQuery::DeleteCode,
+            ],
+            |statement, check| match statement {
+                OAuthClientQuery::CreateClient => check.params(5),
+                OAuthClientQuery::FindClient => check.params(1).returns::<OAuthClient>(),
+                OAuthClientQuery::DeleteExpiredCodes => check.params(0),
+                OAuthClientQuery::SaveCode => check.params(8),
+                #[cfg(not(feature = "db-driver-mysql"))]
+                OAuthClientQuery::TakeCode => check.params(1).returns::<OAuthAuthorizationCode>(),
+                #[cfg(feature = "db-driver-mysql")]
+                OAuthClientQuery::FindCodeForUpdate => {
+                    check.params(1).returns::<OAuthAuthorizationCode>()
+                }
+                #[cfg(feature = "db-driver-mysql")]
+                OAuthClientQuery::DeleteCode => check.params(1),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -5,20 +5,78 @@ use crate::{
     repositories::RbacRepository,
 };
 
+#[derive(Debug)]
 enum RbacQuery {
     LoadRolePermissions,
+    FindRoleByName,
+    AssignRoleToUser,
+    RemoveRoleFromUser,
+    AddPermissionToRole,
+    RemovePermissionFromRole,
+    CreatePermission,
+    DeletePermission,
+    ListPermissions,
+    FindPermissionByCode,
+    DeleteRolePermissionsByPermissionId,
+    CreateRole,
+    DeleteRole,
+    ListRoles,
+    DeleteRolePermissionsByRoleId,
+    DeleteUserRolesByRoleId,
 }
 
 impl SqlStatement for RbacQuery {
     fn as_str(&self) -> &'static str {
         match self {
             RbacQuery::LoadRolePermissions => {
-                include_str!("../../sql/rbac_repository/load_role_permissions.sql")
+                shinespark::include_sql!("rbac_repository/load_role_permissions.sql")
+            }
+            RbacQuery::FindRoleByName => {
+                shinespark::include_sql!("rbac_repository/find_role_by_name.sql")
+            }
+            RbacQuery::AssignRoleToUser => {
+                shinespark::include_sql!("rbac_repository/assign_role_to_user.sql")
+            }
+            RbacQuery::RemoveRoleFromUser => {
+                shinespark::include_sql!("rbac_repository/remove_role_from_user.sql")
+            }
+            RbacQuery::AddPermissionToRole => {
+                shinespark::include_sql!("rbac_repository/add_permission_to_role.sql")
+            }
+            RbacQuery::RemovePermissionFromRole => {
+                shinespark::include_sql!("rbac_repository/remove_permission_from_role.sql")
+            }
+            RbacQuery::CreatePermission => {
+                shinespark::include_sql!("rbac_repository/create_permission.sql")
+            }
+            RbacQuery::DeletePermission => {
+                shinespark::include_sql!("rbac_repository/delete_permission.sql")
+            }
+            RbacQuery::ListPermissions => {
+                shinespark::include_sql!("rbac_repository/list_permissions.sql")
+            }
+            RbacQuery::FindPermissionByCode => {
+                shinespark::include_sql!("rbac_repository/find_permission_by_code.sql")
+            }
+            RbacQuery::DeleteRolePermissionsByPermissionId => {
+                shinespark::include_sql!(
+                    "rbac_repository/delete_role_permissions_by_permission_id.sql"
+                )
+            }
+            RbacQuery::CreateRole => shinespark::include_sql!("rbac_repository/create_role.sql"),
+            RbacQuery::DeleteRole => shinespark::include_sql!("rbac_repository/delete_role.sql"),
+            RbacQuery::ListRoles => shinespark::include_sql!("rbac_repository/list_roles.sql"),
+            RbacQuery::DeleteRolePermissionsByRoleId => {
+                shinespark::include_sql!("rbac_repository/delete_role_permissions_by_role_id.sql")
+            }
+            RbacQuery::DeleteUserRolesByRoleId => {
+                shinespark::include_sql!("rbac_repository/delete_user_roles_by_role_id.sql")
             }
         }
     }
 }
 
+/// 생성한 권한/역할은 `RETURNING` 대신 유일 키(code, name)로 다시 읽는다. (mysql)
 pub struct SqlxRbacRepository {}
 
 impl SqlxRbacRepository {
@@ -33,6 +91,7 @@ impl RbacRepository for SqlxRbacRepository {
         &self,
         handle: &mut shinespark::db::Handle<'_>,
     ) -> shinespark::Result<Vec<(i64, String)>> {
+        // 쓰기 직후 캐시를 다시 적재하므로 replica 가 아닌 handle 이 가리키는 DB 에서 읽는다.
         RbacQuery::LoadRolePermissions
             .as_query_as::<(i64, String)>()
             .fetch_all(handle.inner())
@@ -45,7 +104,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         name: &str,
     ) -> shinespark::Result<Option<Role>> {
-        "SELECT * FROM shs_iam_role WHERE name = $1"
+        RbacQuery::FindRoleByName
             .as_query_as::<Role>()
             .bind(name)
             .fetch_optional(handle.inner())
@@ -59,14 +118,13 @@ impl RbacRepository for SqlxRbacRepository {
         user_id: i64,
         role_id: i64,
     ) -> shinespark::Result<()> {
-        sqlx::query(
-            "INSERT INTO shs_iam_user_role (user_id, role_id) VALUES ($1, $2) ON CONFLICT (user_id, role_id) DO NOTHING",
-        )
-        .bind(user_id)
-        .bind(role_id)
-        .execute(handle.inner())
-        .await
-        .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        RbacQuery::AssignRoleToUser
+            .as_query()
+            .bind(user_id)
+            .bind(role_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
         Ok(())
     }
 
@@ -76,7 +134,7 @@ impl RbacRepository for SqlxRbacRepository {
         user_id: i64,
         role_id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_user_role WHERE user_id = $1 AND role_id = $2"
+        RbacQuery::RemoveRoleFromUser
             .as_query()
             .bind(user_id)
             .bind(role_id)
@@ -92,18 +150,13 @@ impl RbacRepository for SqlxRbacRepository {
         role_id: i64,
         permission_id: i64,
     ) -> shinespark::Result<()> {
-        r#"
-        INSERT INTO
-            shs_iam_role_permission (role_id, permission_id)
-        VALUES ($1, $2)
-        ON CONFLICT (role_id, permission_id) DO NOTHING
-        "#
-        .as_query()
-        .bind(role_id)
-        .bind(permission_id)
-        .execute(handle.inner())
-        .await
-        .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        RbacQuery::AddPermissionToRole
+            .as_query()
+            .bind(role_id)
+            .bind(permission_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
         Ok(())
     }
 
@@ -113,7 +166,7 @@ impl RbacRepository for SqlxRbacRepository {
         role_id: i64,
         permission_id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_role_permission WHERE role_id = $1 AND permission_id = $2"
+        RbacQuery::RemovePermissionFromRole
             .as_query()
             .bind(role_id)
             .bind(permission_id)
@@ -131,10 +184,16 @@ impl RbacRepository for SqlxRbacRepository {
         code: &str,
         description: &str,
     ) -> shinespark::Result<Permission> {
-        "INSERT INTO shs_iam_permission (code, description) VALUES ($1, $2) RETURNING *"
-            .as_query_as::<Permission>()
+        RbacQuery::CreatePermission
+            .as_query()
             .bind(code)
             .bind(description)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        RbacQuery::FindPermissionByCode
+            .as_query_as::<Permission>()
+            .bind(code)
             .fetch_one(handle.inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
@@ -145,7 +204,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_permission WHERE id = $1"
+        RbacQuery::DeletePermission
             .as_query()
             .bind(id)
             .execute(handle.inner())
@@ -158,7 +217,7 @@ impl RbacRepository for SqlxRbacRepository {
         &self,
         handle: &mut shinespark::db::Handle<'_>,
     ) -> shinespark::Result<Vec<Permission>> {
-        "SELECT * FROM shs_iam_permission ORDER BY code"
+        RbacQuery::ListPermissions
             .as_query_as::<Permission>()
             .fetch_all(handle.inner())
             .await
@@ -170,7 +229,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         code: &str,
     ) -> shinespark::Result<Option<Permission>> {
-        "SELECT * FROM shs_iam_permission WHERE code = $1"
+        RbacQuery::FindPermissionByCode
             .as_query_as::<Permission>()
             .bind(code)
             .fetch_optional(handle.inner())
@@ -183,7 +242,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         permission_id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_role_permission WHERE permission_id = $1"
+        RbacQuery::DeleteRolePermissionsByPermissionId
             .as_query()
             .bind(permission_id)
             .execute(handle.inner())
@@ -200,10 +259,16 @@ impl RbacRepository for SqlxRbacRepository {
         name: &str,
         description: &str,
     ) -> shinespark::Result<Role> {
-        "INSERT INTO shs_iam_role (name, description) VALUES ($1, $2) RETURNING *"
-            .as_query_as::<Role>()
+        RbacQuery::CreateRole
+            .as_query()
             .bind(name)
             .bind(description)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        RbacQuery::FindRoleByName
+            .as_query_as::<Role>()
+            .bind(name)
             .fetch_one(handle.inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
@@ -214,7 +279,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_role WHERE id = $1"
+        RbacQuery::DeleteRole
             .as_query()
             .bind(id)
             .execute(handle.inner())
@@ -227,9 +292,9 @@ impl RbacRepository for SqlxRbacRepository {
         &self,
         handle: &mut shinespark::db::Handle<'_>,
     ) -> shinespark::Result<Vec<Role>> {
-        "SELECT * FROM shs_iam_role ORDER BY name"
+        RbacQuery::ListRoles
             .as_query_as::<Role>()
-            .fetch_all(handle.inner())
+            .fetch_all(handle.read_inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
     }
@@ -239,7 +304,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         role_id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_role_permission WHERE role_id = $1"
+        RbacQuery::DeleteRolePermissionsByRoleId
             .as_query()
             .bind(role_id)
             .execute(handle.inner())
@@ -253,7 +318,7 @@ impl RbacRepository for SqlxRbacRepository {
         handle: &mut shinespark::db::Handle<'_>,
         role_id: i64,
     ) -> shinespark::Result<()> {
-        "DELETE FROM shs_iam_user_role WHERE role_id = $1"
+        RbacQuery::DeleteUserRolesByRoleId
             .as_query()
             .bind(role_id)
             .execute(handle.inner())
@@ -265,11 +330,13 @@ impl RbacRepository for SqlxRbacRepository {
 
 #[cfg(test)]
 mod tests {
+    use shinespark::db::check_statements;
+
     use super::*;
     use crate::repositories::RbacRepository;
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_load_role_permissions() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let repo = SqlxRbacRepository::new();
@@ -282,7 +349,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_find_role_by_name() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let repo = SqlxRbacRepository::new();
@@ -300,7 +367,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_assign_and_remove_role_to_user() {
         let db = shinespark::db::Database::new_dotenv().await.unwrap();
         let repo = SqlxRbacRepository::new();
@@ -348,4 +415,51 @@ mod tests {
 
         handle.rollback().await.unwrap();
     }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                RbacQuery::LoadRolePermissions,
+                RbacQuery::FindRoleByName,
+                RbacQuery::AssignRoleToUser,
+                RbacQuery::RemoveRoleFromUser,
+                RbacQuery::AddPermissionToRole,
+                RbacQuery::RemovePermissionFromRole,
+                RbacQuery::CreatePermission,
+                RbacQuery::DeletePermission,
+                RbacQuery::ListPermissions,
+                RbacQuery::FindPermissionByCode,
+                RbacQuery::DeleteRolePermissionsByPermissionId,
+                RbacQuery::CreateRole,
+                RbacQuery::DeleteRole,
+                RbacQuery::ListRoles,
+                RbacQuery::DeleteRolePermissionsByRoleId,
+                RbacQuery::DeleteUserRolesByRoleId,
+            ],
+            |statement, check| match statement {
+                RbacQuery::LoadRolePermissions => check.params(0).returns::<(i64, String)>(),
+                RbacQuery::FindRoleByName => check.params(1).returns::<Role>(),
+                RbacQuery::AssignRoleToUser => check.params(2),
+                RbacQuery::RemoveRoleFromUser => check.params(2),
+                RbacQuery::AddPermissionToRole => check.params(2),
+                RbacQuery::RemovePermissionFromRole => check.params(2),
+                RbacQuery::CreatePermission => check.params(2),
+                RbacQuery::DeletePermission => check.params(1),
+                RbacQuery::ListPermissions => check.params(0).returns::<Permission>(),
+                RbacQuery::FindPermissionByCode => check.params(1).returns::<Permission>(),
+                RbacQuery::DeleteRolePermissionsByPermissionId => check.params(1),
+                RbacQuery::CreateRole => check.params(2),
+                RbacQuery::DeleteRole => check.params(1),
+                RbacQuery::ListRoles => check.params(0).returns::<Role>(),
+                RbacQuery::DeleteRolePermissionsByRoleId => check.params(1),
+                RbacQuery::DeleteUserRolesByRoleId => check.params(1),
+            },
+        )
+        .await
+        .unwrap();
+    }
 }
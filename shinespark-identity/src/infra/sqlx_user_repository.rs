@@ -1,29 +1,47 @@
-use shinespark::db::{SqlBuilderExt, SqlStatement};
+use shinespark::db::{Compare, Filter, SqlBuilderExt, SqlComposer, SqlStatement};
 
 use crate::entities::{AuthProvider, User, UserAggregate, UserIdentity, UserStatus};
 use crate::repositories::UserRepository;
 use crate::usecases::{FindUserQuery, UpdateUserCommand};
 
+#[derive(Debug)]
 enum UserQuery {
+    CreateUser,
+    GetUserByUid,
+    GetUserById,
     CreateIdentity,
+    GetIdentity,
     FindUser,
     FindUserByIdentity,
+    UpdateSignCount,
 }
 
 impl SqlStatement for UserQuery {
     fn as_str(&self) -> &'static str {
         match self {
+            UserQuery::CreateUser => shinespark::include_sql!("user_repository/create_user.sql"),
+            UserQuery::GetUserByUid => {
+                shinespark::include_sql!("user_repository/get_user_by_uid.sql")
+            }
+            UserQuery::GetUserById => {
+                shinespark::include_sql!("user_repository/get_user_by_id.sql")
+            }
             UserQuery::CreateIdentity => {
-                include_str!("../../sql/user_repository/create_identity.sql")
+                shinespark::include_sql!("user_repository/create_identity.sql")
             }
-            UserQuery::FindUser => include_str!("../../sql/user_repository/find_user.sql"),
+            UserQuery::GetIdentity => shinespark::include_sql!("user_repository/get_identity.sql"),
+            UserQuery::FindUser => shinespark::include_sql!("user_repository/find_user.sql"),
             UserQuery::FindUserByIdentity => {
-                include_str!("../../sql/user_repository/find_user_by_identity.sql")
+                shinespark::include_sql!("user_repository/find_user_by_identity.sql")
+            }
+            UserQuery::UpdateSignCount => {
+                shinespark::include_sql!("user_repository/update_sign_count.sql")
             }
         }
     }
 }
 
+/// mysql 에는 `RETURNING` 이 없으므로 모든 드라이버에서 쓰고 난 뒤 유일 키로 다시 읽는다.
 pub struct SqlxUserRepository {}
 
 impl SqlxUserRepository {
@@ -61,16 +79,21 @@ impl UserRepository for SqlxUserRepository {
         handle: &mut shinespark::db::Handle<'_>,
         user: User,
     ) -> shinespark::Result<User> {
-        let user = "INSERT INTO shs_iam_user (uid, name, email, status) VALUES ($1, $2, $3, $4) RETURNING *"
-            .as_query_as::<User>()
+        UserQuery::CreateUser
+            .as_query()
             .bind(&user.uid)
             .bind(&user.name)
             .bind(&user.email)
             .bind(&user.status.as_str())
-            .fetch_one(handle.inner())
+            .execute(handle.inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
-        Ok(user)
+        UserQuery::GetUserByUid
+            .as_query_as::<User>()
+            .bind(user.uid)
+            .fetch_one(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
     }
 
     async fn create_identity(
@@ -78,16 +101,25 @@ impl UserRepository for SqlxUserRepository {
         handle: &mut shinespark::db::Handle<'_>,
         user_identity: UserIdentity,
     ) -> shinespark::Result<UserIdentity> {
-        let user_identity = UserQuery::CreateIdentity
-            .as_query_as::<UserIdentity>()
+        UserQuery::CreateIdentity
+            .as_query()
             .bind(&user_identity.user_id)
             .bind(&user_identity.provider.as_str())
             .bind(&user_identity.provider_uid)
             .bind(&user_identity.credential_hash)
-            .fetch_one(handle.inner())
+            .bind(&user_identity.credential_public_key)
+            .bind(user_identity.sign_count)
+            .execute(handle.inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
-        Ok(user_identity)
+        UserQuery::GetIdentity
+            .as_query_as::<UserIdentity>()
+            .bind(user_identity.user_id)
+            .bind(user_identity.provider.as_str())
+            .bind(&user_identity.provider_uid)
+            .fetch_one(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
     }
 
     async fn find_user(
@@ -95,16 +127,19 @@ impl UserRepository for SqlxUserRepository {
         handle: &mut shinespark::db::Handle<'_>,
         query: FindUserQuery,
     ) -> shinespark::Result<Option<UserAggregate>> {
+        let filter = Filter::after_where()
+            .and_option(query.id.map(|id| Compare::eq("u.id", id)))
+            .and_option(query.uid.map(|uid| Compare::eq("u.uid", uid)))
+            .and_option(query.email.map(|email| Compare::eq("u.email", email)))
+            .and_option(
+                (!query.with_deleted)
+                    .then(|| Compare::ne("u.status", UserStatus::Deleted.as_str().to_string())),
+            );
         let mut b = UserQuery::FindUser.as_builder();
-        b.push_option(" AND u.id = ", &query.id);
-        b.push_option(" AND u.uid = ", &query.uid);
-        b.push_option(" AND u.email = ", &query.email);
-        if !query.with_deleted {
-            b.push(" AND u.status != ").push_bind(UserStatus::Deleted.as_str());
-        }
+        filter.compose(&mut b)?;
         let row = b
             .build_query_as::<rows::UserAggregateRow>()
-            .fetch_optional(handle.inner())
+            .fetch_optional(handle.read_inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
         Ok(row.map(rows::UserAggregateRow::into))
@@ -116,20 +151,22 @@ impl UserRepository for SqlxUserRepository {
         command: UpdateUserCommand,
     ) -> shinespark::Result<User> {
         let status = command.status.as_ref().map(|s| s.as_str());
-        let user = "UPDATE shs_iam_user SET updated_at = NOW()"
+        "UPDATE shs_iam_user SET updated_at = CURRENT_TIMESTAMP"
             .as_builder()
             .push_option(", status = ", &status)
             .push(" where id = ")
             .push_bind(&command.id)
-            .push(" returning *")
-            .build_query_as::<User>()
-            .fetch_optional(handle.inner())
+            .build()
+            .execute(handle.inner())
             .await
             .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
-        if user.is_none() {
-            return Err(shinespark::Error::NotFound);
-        }
-        Ok(user.unwrap())
+        UserQuery::GetUserById
+            .as_query_as::<User>()
+            .bind(command.id)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?
+            .ok_or(shinespark::Error::NotFound)
     }
 
     async fn find_user_by_identity(
@@ -151,4 +188,74 @@ impl UserRepository for SqlxUserRepository {
             identities: r.identities.0,
         }))
     }
+
+    async fn update_sign_count(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        identity_id: i64,
+        sign_count: i64,
+    ) -> shinespark::Result<()> {
+        UserQuery::UpdateSignCount
+            .as_query()
+            .bind(sign_count)
+            .bind(identity_id)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                UserQuery::CreateUser,
+                UserQuery::GetUserByUid,
+                UserQuery::GetUserById,
+                UserQuery::CreateIdentity,
+                UserQuery::GetIdentity,
+                UserQuery::FindUser,
+                UserQuery::FindUserByIdentity,
+                UserQuery::UpdateSignCount,
+            ],
+            |statement, check| match statement {
+                UserQuery::CreateUser => check.params(4),
+                UserQuery::GetUserByUid => {
+                    check.params(1).sample("status", "active").returns::<User>()
+                }
+                UserQuery::GetUserById => {
+                    check.params(1).sample("status", "active").returns::<User>()
+                }
+                UserQuery::CreateIdentity => check.params(6),
+                UserQuery::GetIdentity => {
+                    check.params(3).sample("provider", "local").returns::<UserIdentity>()
+                }
+                UserQuery::FindUser => check
+                    .params(0)
+                    .sample("status", "active")
+                    .sample("role_ids", "[]")
+                    .sample("identities", "[]")
+                    .returns::<rows::UserAggregateRow>(),
+                UserQuery::FindUserByIdentity => check
+                    .params(2)
+                    .sample("status", "active")
+                    .sample("role_ids", "[]")
+                    .sample("identities", "[]")
+                    .returns::<rows::UserAggregateRow>(),
+                UserQuery::UpdateSignCount => check.params(2),
+            },
+        )
+        .await
+        .unwrap();
+    }
 }
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use shinespark::db::SqlStatement;
+
+use crate::entities::{WebAuthnCeremony, WebAuthnChallenge};
+use crate::repositories::WebAuthnRepository;
+
+#[derive(Debug)]
+enum WebAuthnQuery {
+    DeleteExpiredChallenges,
+    SaveChallenge,
+    #[cfg(not(feature = "db-driver-mysql"))]
+    TakeChallenge,
+    #[cfg(feature = "db-driver-mysql")]
+    FindChallengeForUpdate,
+    #[cfg(feature = "db-driver-mysql")]
+    DeleteChallenge,
+}
+
+impl SqlStatement for WebAuthnQuery {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WebAuthnQuery::DeleteExpiredChallenges => {
+                shinespark::include_sql!("webauthn_repository/delete_expired_challenges.sql")
+            }
+            WebAuthnQuery::SaveChallenge => {
+                shinespark::include_sql!("webauthn_repository/save_challenge.sql")
+            }
+            #[cfg(not(feature = "db-driver-mysql"))]
+            WebAuthnQuery::TakeChallenge => {
+                shinespark::include_sql!("webauthn_repository/take_challenge.sql")
+            }
+            #[cfg(feature = "db-driver-mysql")]
+            WebAuthnQuery::FindChallengeForUpdate => {
+                shinespark::include_sql!("webauthn_repository/find_challenge_for_update.sql")
+            }
+            #[cfg(feature = "db-driver-mysql")]
+            WebAuthnQuery::DeleteChallenge => {
+                shinespark::include_sql!("webauthn_repository/delete_challenge.sql")
+            }
+        }
+    }
+}
+
+/// challenge 는 postgres, sqlite 에서 `DELETE ... RETURNING` 한 번으로, mysql 에서는 트랜잭션
+/// 안에서 `SELECT ... FOR UPDATE` 로 잠근 뒤 지워서 한 번만 꺼낼 수 있게 한다.
+pub struct SqlxWebAuthnRepository {}
+
+impl SqlxWebAuthnRepository {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl WebAuthnRepository for SqlxWebAuthnRepository {
+    async fn save_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge: &str,
+        user_id: Option<i64>,
+        ceremony: WebAuthnCeremony,
+        expires_at: DateTime<Utc>,
+    ) -> shinespark::Result<()> {
+        // 완료되지 않은 ceremony 의 challenge 는 여기서 정리한다.
+        WebAuthnQuery::DeleteExpiredChallenges
+            .as_query()
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+
+        WebAuthnQuery::SaveChallenge
+            .as_query()
+            .bind(challenge)
+            .bind(user_id)
+            .bind(ceremony)
+            .bind(expires_at)
+            .execute(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "db-driver-mysql"))]
+    async fn take_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge: &str,
+        ceremony: WebAuthnCeremony,
+    ) -> shinespark::Result<Option<WebAuthnChallenge>> {
+        WebAuthnQuery::TakeChallenge
+            .as_query_as::<WebAuthnChallenge>()
+            .bind(challenge)
+            .bind(ceremony)
+            .fetch_optional(handle.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))
+    }
+
+    #[cfg(feature = "db-driver-mysql")]
+    async fn take_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge: &str,
+        ceremony: WebAuthnCeremony,
+    ) -> shinespark::Result<Option<WebAuthnChallenge>> {
+        let mut tx = handle.begin().await?;
+        let found = WebAuthnQuery::FindChallengeForUpdate
+            .as_query_as::<WebAuthnChallenge>()
+            .bind(challenge)
+            .bind(ceremony)
+            .fetch_optional(tx.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        let Some(found) = found else {
+            return Ok(None);
+        };
+        let deleted = WebAuthnQuery::DeleteChallenge
+            .as_query()
+            .bind(found.id)
+            .execute(tx.inner())
+            .await
+            .map_err(|e| shinespark::Error::DatabaseError(anyhow::anyhow!(e)))?;
+        tx.commit().await?;
+        Ok((deleted.rows_affected() == 1).then_some(found))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shinespark::db::check_statements;
+
+    use super::*;
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_statements_match_schema() {
+        let db = shinespark::db::Database::new_dotenv().await.unwrap();
+        check_statements(
+            &mut db.handle(),
+            [
+                WebAuthnQuery::DeleteExpiredChallenges,
+                WebAuthnQuery::SaveChallenge,
+                #[cfg(not(feature = "db-driver-mysql"))]
+                WebAuthnQuery::TakeChallenge,
+                #[cfg(feature = "db-driver-mysql")]
+                WebAuthnQuery::FindChallengeForUpdate,
+                #[cfg(feature = "db-driver-mysql")]
+                WebAuthnQuery::DeleteChallenge,
+            ],
+            |statement, check| match statement {
+                WebAuthnQuery::DeleteExpiredChallenges => check.params(0),
+                WebAuthnQuery::SaveChallenge => check.params(4),
+                #[cfg(not(feature = "db-driver-mysql"))]
+                WebAuthnQuery::TakeChallenge => check
+                    .params(2)
+                    .sample("ceremony", "registration")
+                    .returns::<WebAuthnChallenge>(),
+                #[cfg(feature = "db-driver-mysql")]
+                WebAuthnQuery::FindChallengeForUpdate => check
+                    .params(2)
+                    .sample("ceremony", "registration")
+                    .returns::<WebAuthnChallenge>(),
+                #[cfg(feature = "db-driver-mysql")]
+                WebAuthnQuery::DeleteChallenge => check.params(1),
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,336 @@
+//! infra 단위 테스트에서 함께 쓰는 mock.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use crate::entities::{OAuthAuthorizationCode, OAuthClient, Permission, Role, UserAggregate};
+use crate::infra::jwt_service::{JwtClaims, SubjectType};
+use crate::repositories::{
+    AuthorizationCodeRepository, JwtIdentRepository, NewAuthorizationCode, NewOAuthClient,
+    OAuthClientRepository, RefreshTokenRow,
+};
+use crate::usecases::{CreatePermissionCommand, CreateRoleCommand, RbacUsecase};
+
+#[cfg(feature = "db-driver-postgres")]
+const UNUSED_DATABASE_URL: &str = "postgres://localhost/unused";
+#[cfg(feature = "db-driver-sqlite")]
+const UNUSED_DATABASE_URL: &str = "sqlite::memory:";
+#[cfg(feature = "db-driver-mysql")]
+const UNUSED_DATABASE_URL: &str = "mysql://localhost/unused";
+
+/// mock repository 만 쓰므로 실제로 연결하지 않는 pool
+pub(crate) fn mock_handle() -> shinespark::db::Handle<'static> {
+    shinespark::db::Handle::Pool(
+        sqlx::Pool::<shinespark::db::Driver>::connect_lazy(UNUSED_DATABASE_URL).unwrap(),
+        false,
+    )
+}
+
+/// `aggregate` 로 로그인한 1st party access token 의 claims
+pub(crate) fn access_claims(aggregate: &UserAggregate) -> JwtClaims {
+    JwtClaims {
+        sub: aggregate.user.uid.to_string(),
+        sub_type: SubjectType::User,
+        exp: usize::MAX,
+        roles: Some(aggregate.role_ids.clone()),
+        token_type: "access".to_string(),
+        permissions: None,
+        client_id: None,
+        act: None,
+    }
+}
+
+/// role_id → 권한 코드 목록을 고정해 둔 RbacUsecase. 조회 외의 기능은 지원하지 않는다.
+pub(crate) struct MockRbacUsecase {
+    grants: HashMap<i64, Vec<String>>,
+}
+
+impl MockRbacUsecase {
+    pub(crate) fn new(grants: &[(i64, &[&str])]) -> Self {
+        Self {
+            grants: grants
+                .iter()
+                .map(|(role_id, codes)| (*role_id, codes.iter().map(|c| c.to_string()).collect()))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RbacUsecase for MockRbacUsecase {
+    async fn load(&self, _handle: &mut shinespark::db::Handle<'_>) -> shinespark::Result<()> {
+        Ok(())
+    }
+
+    fn check_perm(&self, role_ids: &[i64], permission: &str) -> bool {
+        role_ids.iter().filter_map(|id| self.grants.get(id)).flatten().any(|p| p == permission)
+    }
+
+    async fn create_permission(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _command: CreatePermissionCommand,
+    ) -> shinespark::Result<Permission> {
+        unimplemented!()
+    }
+
+    async fn delete_permission(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _permission_id: i64,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+
+    async fn list_permissions(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+    ) -> shinespark::Result<Vec<Permission>> {
+        unimplemented!()
+    }
+
+    async fn find_permission_by_code(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        code: &str,
+    ) -> shinespark::Result<Option<Permission>> {
+        let exists = self.grants.values().flatten().any(|p| p == code);
+        Ok(exists.then(|| Permission {
+            id: 0,
+            code: code.to_string(),
+            description: String::new(),
+            created_at: chrono::Utc::now(),
+        }))
+    }
+
+    async fn create_role(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _command: CreateRoleCommand,
+    ) -> shinespark::Result<Role> {
+        unimplemented!()
+    }
+
+    async fn delete_role(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _role_id: i64,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+
+    async fn list_roles(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+    ) -> shinespark::Result<Vec<Role>> {
+        unimplemented!()
+    }
+
+    async fn add_permission_to_role(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _role_id: i64,
+        _permission_id: i64,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+
+    async fn remove_permission_from_role(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _role_id: i64,
+        _permission_id: i64,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+
+    async fn assign_permission_to_role(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _role_name: &str,
+        _permission_code: &str,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+
+    async fn revoke_permission_from_role(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _role_name: &str,
+        _permission_code: &str,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+
+    async fn assign_role_to_user(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _user_id: i64,
+        _role_name: &str,
+    ) -> shinespark::Result<()> {
+        unimplemented!()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MockOAuthClientRepository {
+    clients: Mutex<Vec<OAuthClient>>,
+    codes: Mutex<Vec<OAuthAuthorizationCode>>,
+}
+
+#[async_trait::async_trait]
+impl OAuthClientRepository for MockOAuthClientRepository {
+    async fn create_client(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        new_client: NewOAuthClient,
+    ) -> shinespark::Result<OAuthClient> {
+        let mut clients = self.clients.lock().unwrap();
+        let row = OAuthClient {
+            id: clients.len() as i64 + 1,
+            client_id: new_client.client_id,
+            client_secret_hash: new_client.client_secret_hash,
+            name: new_client.name,
+            scopes: new_client.scopes,
+            redirect_uris: new_client.redirect_uris,
+            created_at: Utc::now(),
+        };
+        clients.push(row.clone());
+        Ok(row)
+    }
+
+    async fn find_client(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+    ) -> shinespark::Result<Option<OAuthClient>> {
+        Ok(self.clients.lock().unwrap().iter().find(|c| c.client_id == client_id).cloned())
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthorizationCodeRepository for MockOAuthClientRepository {
+    async fn save_code(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        new_code: NewAuthorizationCode,
+    ) -> shinespark::Result<()> {
+        let mut codes = self.codes.lock().unwrap();
+        let row = OAuthAuthorizationCode {
+            id: codes.len() as i64 + 1,
+            code_hash: new_code.code_hash,
+            client_id: new_code.client_id,
+            user_id: new_code.user_id,
+            redirect_uri: new_code.redirect_uri,
+            scope: new_code.scope,
+            code_challenge: new_code.code_challenge,
+            nonce: new_code.nonce,
+            expires_at: new_code.expires_at,
+            created_at: Utc::now(),
+        };
+        codes.push(row);
+        Ok(())
+    }
+
+    async fn take_code(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        code_hash: &str,
+    ) -> shinespark::Result<Option<OAuthAuthorizationCode>> {
+        let mut codes = self.codes.lock().unwrap();
+        let Some(pos) = codes.iter().position(|c| c.code_hash == code_hash) else {
+            return Ok(None);
+        };
+        let code = codes.remove(pos);
+        Ok((code.expires_at > Utc::now()).then_some(code))
+    }
+}
+
+/// refresh token 과 access token denylist 를 메모리에 두는 JwtIdentRepository
+#[derive(Default)]
+pub(crate) struct MockJwtIdentRepository {
+    pub(crate) tokens: Mutex<Vec<(String, String)>>, // (user_uid, token_hash)
+    revoked: Mutex<Vec<String>>,                     // access token_hash
+}
+
+impl MockJwtIdentRepository {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn is_revoked(&self, token_hash: &str) -> bool {
+        self.revoked.lock().unwrap().iter().any(|h| h == token_hash)
+    }
+}
+
+#[async_trait::async_trait]
+impl JwtIdentRepository for MockJwtIdentRepository {
+    async fn save_refresh_token(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        user_uid: &str,
+        token_hash: &str,
+        _expires_at: DateTime<Utc>,
+    ) -> shinespark::Result<()> {
+        self.tokens.lock().unwrap().push((user_uid.to_string(), token_hash.to_string()));
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<Option<RefreshTokenRow>> {
+        let tokens = self.tokens.lock().unwrap();
+        let found = tokens.iter().any(|(_, h)| h == token_hash);
+        if found {
+            Ok(Some(RefreshTokenRow {
+                id: 1,
+                user_uid: uuid::Uuid::new_v4(),
+                token_hash: token_hash.to_string(),
+                expires_at: Utc::now() + chrono::Duration::hours(1),
+                created_at: Utc::now(),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn delete_by_user_uid(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        user_uid: &str,
+    ) -> shinespark::Result<()> {
+        self.tokens.lock().unwrap().retain(|(uid, _)| uid != user_uid);
+        Ok(())
+    }
+
+    async fn delete_refresh_token(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<()> {
+        self.tokens.lock().unwrap().retain(|(_, h)| h != token_hash);
+        Ok(())
+    }
+
+    async fn revoke_access_token(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+        _expires_at: DateTime<Utc>,
+    ) -> shinespark::Result<()> {
+        self.revoked.lock().unwrap().push(token_hash.to_string());
+        Ok(())
+    }
+
+    async fn is_access_token_revoked(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<bool> {
+        Ok(self.is_revoked(token_hash))
+    }
+}
@@ -0,0 +1,401 @@
+//! WebAuthn ceremony 응답 해석과 서명 검증.
+//!
+//! attestation 은 `none` 으로 요청하므로 attestation statement 는 검증하지 않고
+//! authenticator data 의 공개키만 사용한다.
+
+use std::io::Cursor;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ciborium::value::Value;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+pub const CLIENT_DATA_CREATE: &str = "webauthn.create";
+pub const CLIENT_DATA_GET: &str = "webauthn.get";
+
+/// 지원하는 COSE 알고리즘 (ES256, EdDSA, RS256). 브라우저에 선호 순서대로 전달한다.
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [-7, -8, -257];
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+fn invalid(reason: &str) -> shinespark::Error {
+    tracing::debug!("invalid webauthn response: {}", reason);
+    shinespark::Error::InvalidCredentials
+}
+
+/// 32 byte 난수 challenge (base64url)
+pub fn generate_challenge() -> shinespark::Result<String> {
+    let mut challenge = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut challenge)
+        .map_err(|_| shinespark::Error::IllegalState("failed to generate challenge".into()))?;
+    Ok(encode(&challenge))
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn decode(value: &str) -> shinespark::Result<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')).map_err(|_| invalid("base64url"))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectedClientData {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub challenge: String,
+    pub origin: String,
+}
+
+impl CollectedClientData {
+    pub fn parse(client_data_json: &[u8]) -> shinespark::Result<Self> {
+        serde_json::from_slice(client_data_json).map_err(|_| invalid("clientDataJSON"))
+    }
+
+    /// ceremony 종류와 origin 을 확인한다.
+    pub fn verify(&self, expected_type: &str, expected_origin: &str) -> shinespark::Result<()> {
+        if self.type_ != expected_type {
+            return Err(invalid("clientData type"));
+        }
+        if self.origin != expected_origin {
+            return Err(invalid("clientData origin"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key CBOR 원본
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn parse(bytes: &[u8]) -> shinespark::Result<Self> {
+        if bytes.len() < 37 {
+            return Err(invalid("authenticatorData too short"));
+        }
+        let mut rp_id_hash = [0u8; 32];
+        rp_id_hash.copy_from_slice(&bytes[..32]);
+        let flags = bytes[32];
+        let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            // aaguid(16) + credentialIdLength(2) + credentialId + credentialPublicKey
+            let rest = &bytes[37..];
+            if rest.len() < 18 {
+                return Err(invalid("attested credential data too short"));
+            }
+            let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+            let rest = &rest[18..];
+            if rest.len() < id_len {
+                return Err(invalid("credential id too short"));
+            }
+            let (credential_id, rest) = rest.split_at(id_len);
+
+            // 공개키 뒤에 extension 이 붙을 수 있으므로 CBOR 값 하나만 읽는다.
+            let mut cursor = Cursor::new(rest);
+            let _: Value =
+                ciborium::de::from_reader(&mut cursor).map_err(|_| invalid("credential key"))?;
+            let key_len = cursor.position() as usize;
+            Some(AttestedCredential {
+                credential_id: credential_id.to_vec(),
+                public_key: rest[..key_len].to_vec(),
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            rp_id_hash,
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_USER_VERIFIED != 0
+    }
+
+    /// rp id 와 사용자 확인 여부를 검사한다.
+    pub fn verify(&self, rp_id: &str, require_user_verification: bool) -> shinespark::Result<()> {
+        if self.rp_id_hash.as_slice() != Sha256::digest(rp_id.as_bytes()).as_slice() {
+            return Err(invalid("rp id hash"));
+        }
+        if !self.user_present() {
+            return Err(invalid("user not present"));
+        }
+        if require_user_verification && !self.user_verified() {
+            return Err(invalid("user not verified"));
+        }
+        Ok(())
+    }
+}
+
+/// attestationObject (`{fmt, attStmt, authData}`) 에서 authData 를 꺼낸다.
+pub fn parse_attestation_object(bytes: &[u8]) -> shinespark::Result<Vec<u8>> {
+    let value: Value =
+        ciborium::de::from_reader(bytes).map_err(|_| invalid("attestationObject"))?;
+    let Value::Map(entries) = value else {
+        return Err(invalid("attestationObject is not a map"));
+    };
+    entries
+        .into_iter()
+        .find_map(|(key, value)| match (key, value) {
+            (Value::Text(key), Value::Bytes(auth_data)) if key == "authData" => Some(auth_data),
+            _ => None,
+        })
+        .ok_or_else(|| invalid("authData missing"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoseKey {
+    Es256 { x: Vec<u8>, y: Vec<u8> },
+    EdDsa { x: Vec<u8> },
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+impl CoseKey {
+    pub fn parse(bytes: &[u8]) -> shinespark::Result<Self> {
+        let value: Value = ciborium::de::from_reader(bytes).map_err(|_| invalid("COSE key"))?;
+        let Value::Map(entries) = value else {
+            return Err(invalid("COSE key is not a map"));
+        };
+        let get = |label: i128| {
+            entries.iter().find_map(|(key, value)| match key {
+                Value::Integer(key) if i128::from(*key) == label => Some(value),
+                _ => None,
+            })
+        };
+        let int = |label: i128| match get(label) {
+            Some(Value::Integer(value)) => Some(i128::from(*value)),
+            _ => None,
+        };
+        let bytes = |label: i128| match get(label) {
+            Some(Value::Bytes(value)) => Ok(value.clone()),
+            _ => Err(invalid("COSE key parameter")),
+        };
+
+        // 1: kty, 3: alg, -1: crv | n, -2: x | e, -3: y
+        match (int(1), int(3)) {
+            (Some(2), Some(-7)) if int(-1) == Some(1) => Ok(Self::Es256 {
+                x: bytes(-2)?,
+                y: bytes(-3)?,
+            }),
+            (Some(1), Some(-8)) if int(-1) == Some(6) => Ok(Self::EdDsa { x: bytes(-2)? }),
+            (Some(3), Some(-257)) => Ok(Self::Rs256 {
+                n: bytes(-1)?,
+                e: bytes(-2)?,
+            }),
+            _ => Err(invalid("unsupported COSE algorithm")),
+        }
+    }
+
+    pub fn verify(&self, message: &[u8], sig: &[u8]) -> shinespark::Result<()> {
+        let result = match self {
+            Self::Es256 { x, y } => {
+                let mut point = Vec::with_capacity(65);
+                point.push(0x04);
+                point.extend_from_slice(x);
+                point.extend_from_slice(y);
+                signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                    .verify(message, sig)
+            }
+            Self::EdDsa { x } => {
+                signature::UnparsedPublicKey::new(&signature::ED25519, x).verify(message, sig)
+            }
+            Self::Rs256 { n, e } => signature::RsaPublicKeyComponents { n, e }.verify(
+                &signature::RSA_PKCS1_2048_8192_SHA256,
+                message,
+                sig,
+            ),
+        };
+        result.map_err(|_| invalid("signature"))
+    }
+}
+
+/// assertion 서명 대상: `authenticatorData || SHA-256(clientDataJSON)`
+pub fn signed_message(authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+    let mut message = authenticator_data.to_vec();
+    message.extend_from_slice(&Sha256::digest(client_data_json));
+    message
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    //! 브라우저 없이 ceremony 응답을 만드는 ES256 authenticator
+
+    use ring::signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair};
+
+    use super::*;
+
+    pub struct TestAuthenticator {
+        key_pair: EcdsaKeyPair,
+        pub credential_id: Vec<u8>,
+        pub sign_count: u32,
+    }
+
+    impl TestAuthenticator {
+        pub fn new() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+            Self {
+                key_pair: EcdsaKeyPair::from_pkcs8(
+                    &ECDSA_P256_SHA256_ASN1_SIGNING,
+                    pkcs8.as_ref(),
+                    &rng,
+                )
+                .unwrap(),
+                credential_id: uuid::Uuid::new_v4().as_bytes().to_vec(),
+                sign_count: 0,
+            }
+        }
+
+        pub fn cose_key(&self) -> Vec<u8> {
+            let point = self.key_pair.public_key().as_ref();
+            let key = Value::Map(vec![
+                (Value::Integer(1.into()), Value::Integer(2.into())),
+                (Value::Integer(3.into()), Value::Integer((-7).into())),
+                (Value::Integer((-1).into()), Value::Integer(1.into())),
+                (
+                    Value::Integer((-2).into()),
+                    Value::Bytes(point[1..33].to_vec()),
+                ),
+                (
+                    Value::Integer((-3).into()),
+                    Value::Bytes(point[33..65].to_vec()),
+                ),
+            ]);
+            let mut out = vec![];
+            ciborium::ser::into_writer(&key, &mut out).unwrap();
+            out
+        }
+
+        pub fn client_data(type_: &str, challenge: &str, origin: &str) -> Vec<u8> {
+            serde_json::json!({ "type": type_, "challenge": challenge, "origin": origin })
+                .to_string()
+                .into_bytes()
+        }
+
+        pub fn auth_data(&self, rp_id: &str, flags: u8, attested: bool) -> Vec<u8> {
+            let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+            data.push(if attested {
+                flags | FLAG_ATTESTED_CREDENTIAL
+            } else {
+                flags
+            });
+            data.extend_from_slice(&self.sign_count.to_be_bytes());
+            if attested {
+                data.extend_from_slice(&[0u8; 16]);
+                data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+                data.extend_from_slice(&self.credential_id);
+                data.extend_from_slice(&self.cose_key());
+            }
+            data
+        }
+
+        pub fn attestation_object(&self, rp_id: &str) -> Vec<u8> {
+            let object = Value::Map(vec![
+                (Value::Text("fmt".into()), Value::Text("none".into())),
+                (Value::Text("attStmt".into()), Value::Map(vec![])),
+                (
+                    Value::Text("authData".into()),
+                    Value::Bytes(self.auth_data(
+                        rp_id,
+                        FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+                        true,
+                    )),
+                ),
+            ]);
+            let mut out = vec![];
+            ciborium::ser::into_writer(&object, &mut out).unwrap();
+            out
+        }
+
+        pub fn sign(&self, auth_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+            self.key_pair
+                .sign(
+                    &SystemRandom::new(),
+                    &signed_message(auth_data, client_data_json),
+                )
+                .unwrap()
+                .as_ref()
+                .to_vec()
+        }
+    }
+
+    pub const USER_PRESENT_VERIFIED: u8 = FLAG_USER_PRESENT | FLAG_USER_VERIFIED;
+    pub const USER_PRESENT: u8 = FLAG_USER_PRESENT;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::*;
+    use super::*;
+
+    #[test]
+    fn test_parse_attestation_and_verify_assertion() {
+        let authenticator = TestAuthenticator::new();
+        let auth_data =
+            parse_attestation_object(&authenticator.attestation_object("localhost")).unwrap();
+        let parsed = AuthenticatorData::parse(&auth_data).unwrap();
+        parsed.verify("localhost", true).unwrap();
+        assert!(parsed.verify("evil.example", false).is_err());
+
+        let credential = parsed.attested_credential.unwrap();
+        assert_eq!(credential.credential_id, authenticator.credential_id);
+        let key = CoseKey::parse(&credential.public_key).unwrap();
+
+        let client_data =
+            TestAuthenticator::client_data(CLIENT_DATA_GET, "abc", "http://localhost");
+        let assertion = authenticator.auth_data("localhost", USER_PRESENT, false);
+        let sig = authenticator.sign(&assertion, &client_data);
+        key.verify(&signed_message(&assertion, &client_data), &sig).unwrap();
+
+        let tampered = TestAuthenticator::client_data(CLIENT_DATA_GET, "abd", "http://localhost");
+        assert!(key.verify(&signed_message(&assertion, &tampered), &sig).is_err());
+    }
+
+    #[test]
+    fn test_user_verification_flag() {
+        let authenticator = TestAuthenticator::new();
+        let data =
+            AuthenticatorData::parse(&authenticator.auth_data("localhost", USER_PRESENT, false))
+                .unwrap();
+        assert!(data.verify("localhost", false).is_ok());
+        assert!(data.verify("localhost", true).is_err());
+    }
+
+    #[test]
+    fn test_client_data_type_and_origin() {
+        let client_data = CollectedClientData::parse(&TestAuthenticator::client_data(
+            CLIENT_DATA_CREATE,
+            "abc",
+            "http://localhost:8085",
+        ))
+        .unwrap();
+        assert!(client_data.verify(CLIENT_DATA_CREATE, "http://localhost:8085").is_ok());
+        assert!(client_data.verify(CLIENT_DATA_GET, "http://localhost:8085").is_err());
+        assert!(client_data.verify(CLIENT_DATA_CREATE, "https://evil.example").is_err());
+    }
+}
@@ -1,7 +1,17 @@
+mod api_key_repository;
+mod audit_log_repository;
 mod jwt_ident_repository;
+mod mfa_repository;
+mod oauth_client_repository;
 mod rbac_repository;
 mod user_repository;
+mod webauthn_repository;
 
+pub use api_key_repository::*;
+pub use audit_log_repository::*;
 pub use jwt_ident_repository::*;
+pub use mfa_repository::*;
+pub use oauth_client_repository::*;
 pub use rbac_repository::*;
 pub use user_repository::*;
+pub use webauthn_repository::*;
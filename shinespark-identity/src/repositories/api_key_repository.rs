@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+
+use crate::entities::ApiKey;
+
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub user_id: i64,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub permissions: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait::async_trait]
+pub trait ApiKeyRepository: Send + Sync + 'static {
+    async fn create_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        new_key: NewApiKey,
+    ) -> shinespark::Result<ApiKey>;
+
+    async fn find_api_key_by_hash(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        key_hash: &str,
+    ) -> shinespark::Result<Option<ApiKey>>;
+
+    async fn list_api_keys(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<ApiKey>>;
+
+    /// 다른 사용자의 키이거나 없으면 false
+    async fn delete_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        prefix: &str,
+    ) -> shinespark::Result<bool>;
+
+    /// 요청마다 쓰지 않도록 마지막 갱신 후 1 분이 지났을 때만 갱신한다.
+    async fn touch_last_used(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        id: i64,
+    ) -> shinespark::Result<()>;
+}
@@ -0,0 +1,20 @@
+use crate::entities::UserAction;
+
+#[derive(Debug, Clone)]
+pub struct NewUserAuditLog {
+    pub user_id: i64,
+    pub action: UserAction,
+    pub description: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub is_success: bool,
+}
+
+#[async_trait::async_trait]
+pub trait AuditLogRepository: Send + Sync + 'static {
+    async fn record(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        log: NewUserAuditLog,
+    ) -> shinespark::Result<()>;
+}
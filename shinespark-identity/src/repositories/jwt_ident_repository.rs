@@ -31,4 +31,24 @@ pub trait JwtIdentRepository: Send + Sync + 'static {
         handle: &mut shinespark::db::Handle<'_>,
         user_uid: &str,
     ) -> shinespark::Result<()>;
+
+    async fn delete_refresh_token(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<()>;
+
+    /// access token 을 만료 시각까지 denylist 에 올린다.
+    async fn revoke_access_token(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> shinespark::Result<()>;
+
+    async fn is_access_token_revoked(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token_hash: &str,
+    ) -> shinespark::Result<bool>;
 }
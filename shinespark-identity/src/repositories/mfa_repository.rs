@@ -0,0 +1,61 @@
+use crate::entities::{MfaRecoveryCode, UserMfa};
+
+#[async_trait::async_trait]
+pub trait MfaRepository: Send + Sync + 'static {
+    async fn find_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Option<UserMfa>>;
+
+    /// 새 secret 으로 등록을 (다시) 시작한다. 확인 전 상태(`confirmed_at = NULL`)로 저장된다.
+    async fn save_pending_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        secret_encrypted: &str,
+    ) -> shinespark::Result<UserMfa>;
+
+    async fn confirm_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        step: i64,
+    ) -> shinespark::Result<()>;
+
+    /// `step` 이 마지막 사용 step 보다 클 때만 갱신한다. 갱신되지 않으면 false (재사용된 코드)
+    async fn update_last_used_step(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        step: i64,
+    ) -> shinespark::Result<bool>;
+
+    /// TOTP 정보와 복구 코드를 모두 삭제한다.
+    async fn delete_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<()>;
+
+    /// 기존 복구 코드를 모두 지우고 새 해시 목록으로 교체한다.
+    async fn replace_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code_hashes: &[String],
+    ) -> shinespark::Result<()>;
+
+    async fn list_unused_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<MfaRecoveryCode>>;
+
+    /// 사용 처리. 이미 사용된 코드면 false
+    async fn mark_recovery_code_used(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        id: i64,
+    ) -> shinespark::Result<bool>;
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+
+use crate::entities::{OAuthAuthorizationCode, OAuthClient};
+
+#[derive(Debug, Clone)]
+pub struct NewOAuthClient {
+    pub client_id: String,
+    pub client_secret_hash: String,
+    pub name: String,
+    pub scopes: String,
+    pub redirect_uris: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAuthorizationCode {
+    pub code_hash: String,
+    pub client_id: String,
+    pub user_id: i64,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub code_challenge: String,
+    pub nonce: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[async_trait::async_trait]
+pub trait OAuthClientRepository: Send + Sync + 'static {
+    async fn create_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        new_client: NewOAuthClient,
+    ) -> shinespark::Result<OAuthClient>;
+
+    async fn find_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+    ) -> shinespark::Result<Option<OAuthClient>>;
+}
+
+#[async_trait::async_trait]
+pub trait AuthorizationCodeRepository: Send + Sync + 'static {
+    async fn save_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        new_code: NewAuthorizationCode,
+    ) -> shinespark::Result<()>;
+
+    /// 만료되지 않은 code 를 꺼내고 삭제한다(1회용).
+    async fn take_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        code_hash: &str,
+    ) -> shinespark::Result<Option<OAuthAuthorizationCode>>;
+}
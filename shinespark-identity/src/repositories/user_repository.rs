@@ -35,4 +35,12 @@ pub trait UserRepository: Send + Sync + 'static {
         handle: &mut shinespark::db::Handle<'_>,
         command: UpdateUserCommand,
     ) -> shinespark::Result<User>;
+
+    /// passkey 인증 성공 후 authenticator 의 서명 카운터를 갱신한다.
+    async fn update_sign_count(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        identity_id: i64,
+        sign_count: i64,
+    ) -> shinespark::Result<()>;
 }
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+
+use crate::entities::{WebAuthnCeremony, WebAuthnChallenge};
+
+#[async_trait::async_trait]
+pub trait WebAuthnRepository: Send + Sync + 'static {
+    async fn save_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge: &str,
+        user_id: Option<i64>,
+        ceremony: WebAuthnCeremony,
+        expires_at: DateTime<Utc>,
+    ) -> shinespark::Result<()>;
+
+    /// 만료되지 않은 challenge 를 꺼내고 삭제한다(1회용).
+    async fn take_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge: &str,
+        ceremony: WebAuthnCeremony,
+    ) -> shinespark::Result<Option<WebAuthnChallenge>>;
+}
@@ -1,11 +1,23 @@
+mod api_key_usecase;
+mod authorization_server_usecase;
+mod impersonation_usecase;
 mod jwt_ident_usecase;
 mod login_usecase;
+mod mfa_usecase;
+mod oauth_client_usecase;
 mod rbac_usecase;
 mod social_login_usecase;
 mod user_usecase;
+mod webauthn_usecase;
 
+pub use api_key_usecase::*;
+pub use authorization_server_usecase::*;
+pub use impersonation_usecase::*;
 pub use jwt_ident_usecase::*;
 pub use login_usecase::*;
+pub use mfa_usecase::*;
+pub use oauth_client_usecase::*;
 pub use rbac_usecase::*;
 pub use social_login_usecase::*;
 pub use user_usecase::*;
+pub use webauthn_usecase::*;
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::entities::ApiKey;
+use crate::infra::jwt_service::JwtClaims;
+
+// ==========================================
+// 1. ApiKeyUsecase Cqrs
+// ==========================================
+#[derive(Debug)]
+pub struct CreateApiKeyCommand {
+    pub name: String,
+    /// 발급자가 가진 권한 중 키에 허용할 권한 코드
+    pub permissions: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// 발급 직후에만 평문 키를 돌려준다. 서버에는 해시만 저장된다.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedApiKey {
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+// ==========================================
+// 2. ApiKeyUsecase Trait
+// ==========================================
+// 스크립트, CI 같은 machine client 의 장기 인증 수단 관리에 집중합니다.
+#[async_trait::async_trait]
+pub trait ApiKeyUsecase: Send + Sync + 'static {
+    /// `claims` 는 요청한 사용자의 access token 이다. 토큰이 갖지 않은 권한(`JwtClaims::has_perm`)이
+    /// 포함되어 있으면 `UnAuthorized`. OAuth client 에 위임된 토큰은 승인된 scope 안에서만 허용된다.
+    async fn create_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+        command: CreateApiKeyCommand,
+    ) -> shinespark::Result<IssuedApiKey>;
+
+    async fn list_api_keys(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<Vec<ApiKey>>;
+
+    /// `prefix` 로 키를 찾는다. 다른 사용자의 키이거나 없으면 `NotFound`.
+    async fn revoke_api_key(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        prefix: &str,
+    ) -> shinespark::Result<()>;
+
+    /// 키를 검증하고 access token 과 같은 모양의 claims 를 만든다.
+    /// `token_type` 은 "api_key" 이고 `permissions` 에 키의 권한이 담긴다.
+    async fn authenticate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        key: &str,
+    ) -> shinespark::Result<JwtClaims>;
+}
@@ -0,0 +1,122 @@
+use jsonwebtoken::jwk::JwkSet;
+use serde::Serialize;
+
+use crate::entities::{OAuthClient, UserAggregate};
+use crate::infra::jwt_service::JwtClaims;
+
+// ==========================================
+// 1. AuthorizationServerUsecase Cqrs
+// ==========================================
+/// `/oauth2/authorize` 요청 파라미터 (RFC 6749 4.1.1, RFC 7636 4.3)
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    /// 공백으로 구분된 요청 scope. 없으면 client 에 허용된 scope 전체
+    pub scope: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub nonce: Option<String>,
+}
+
+/// 검증을 통과한 authorization 요청. 동의 화면에 보여주고, 승인하면 code 를 발급한다.
+#[derive(Debug, Clone)]
+pub struct ValidatedAuthorization {
+    pub client: OAuthClient,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: String,
+    pub nonce: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct AuthorizationCodeCommand {
+    pub client_id: String,
+    pub client_secret: String,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+}
+
+/// RFC 6749 5.1 + OIDC Core 3.1.3.3 token 응답. refresh token 은 발급하지 않는다.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorizationCodeToken {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+/// OIDC Core 5.3.2 UserInfo 응답. scope 에 따라 claim 이 빠진다.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserInfo {
+    pub sub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// OIDC Discovery 1.0 provider metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub scopes_supported: Vec<String>,
+    pub response_types_supported: Vec<String>,
+    pub grant_types_supported: Vec<String>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub code_challenge_methods_supported: Vec<String>,
+}
+
+// ==========================================
+// 2. AuthorizationServerUsecase Trait
+// ==========================================
+// shinespark 를 다른 앱의 OAuth2 / OIDC provider 로 쓰는 authorization_code + PKCE 흐름에 집중합니다.
+#[async_trait::async_trait]
+pub trait AuthorizationServerUsecase: Send + Sync + 'static {
+    /// client 와 redirect_uri 가 잘못되면 `NotFound` (redirect 하면 안 되는 오류),
+    /// 허용되지 않은 scope 는 `UnAuthorized`, 지원하지 않는 response_type 은 `NotImplemented`,
+    /// 그 외 잘못된 요청(PKCE 누락 등)은 `IllegalState`.
+    async fn validate_authorization_request(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        request: AuthorizationRequest,
+    ) -> shinespark::Result<ValidatedAuthorization>;
+
+    /// 사용자가 동의한 요청에 대해 1회용 authorization code 를 발급한다.
+    async fn approve(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+        authorization: &ValidatedAuthorization,
+    ) -> shinespark::Result<String>;
+
+    /// `authorization_code` grant. client 인증 실패는 `InvalidCredentials`,
+    /// code / redirect_uri / code_verifier 가 맞지 않으면 `UnAuthorized`.
+    async fn exchange_code(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: AuthorizationCodeCommand,
+    ) -> shinespark::Result<AuthorizationCodeToken>;
+
+    /// `openid` scope 로 위임된 access token 의 사용자 정보
+    async fn userinfo(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+    ) -> shinespark::Result<UserInfo>;
+
+    fn discovery(&self) -> OpenIdConfiguration;
+
+    /// ID token 서명을 검증할 공개키 (`jwks_uri`)
+    fn jwks(&self) -> JwkSet;
+}
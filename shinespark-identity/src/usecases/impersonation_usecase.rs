@@ -0,0 +1,50 @@
+use serde::Serialize;
+
+use crate::entities::User;
+use crate::infra::jwt_service::JwtClaims;
+
+/// 대리 로그인에 필요한 권한. 이 권한을 가진 사용자는 대리 로그인 대상이 될 수 없다.
+pub const IMPERSONATE_PERMISSION: &str = "user.impersonate.all";
+
+// ==========================================
+// 1. ImpersonationUsecase Cqrs
+// ==========================================
+#[derive(Debug)]
+pub struct ImpersonateCommand {
+    pub target_uid: uuid::Uuid,
+    /// 감사 로그에 남길 사유 (예: 문의 번호). 비어 있으면 안 된다.
+    pub reason: String,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpersonationToken {
+    pub access_token: String,
+    pub expires_in: i64,
+    pub target: User,
+}
+
+// ==========================================
+// 2. ImpersonationUsecase Trait
+// ==========================================
+// 지원 담당자가 특정 사용자의 화면을 그대로 보는 대리 로그인과 그 감사 기록에 집중합니다.
+#[async_trait::async_trait]
+pub trait ImpersonationUsecase: Send + Sync + 'static {
+    /// `claims` 는 관리자의 access token 이다. 토큰에 권한이 없거나(`JwtClaims::has_perm`)
+    /// 대상이 관리자면 `UnAuthorized`, 대상이 없으면 `NotFound`.
+    async fn impersonate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        claims: &JwtClaims,
+        command: ImpersonateCommand,
+    ) -> shinespark::Result<ImpersonationToken>;
+
+    /// 대리 로그인 종료를 기록하고 `access_token` 을 만료 시각까지 폐기한다.
+    /// 대리 로그인 토큰이 아니면 `IllegalState`.
+    async fn stop(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        access_token: &str,
+        user_agent: Option<String>,
+    ) -> shinespark::Result<()>;
+}
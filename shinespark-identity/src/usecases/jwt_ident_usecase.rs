@@ -1,5 +1,58 @@
-use crate::infra::jwt_service::JwtTokenPair;
-use crate::usecases::LoginCommand;
+use serde::Serialize;
+
+use crate::infra::jwt_service::{ActorClaim, JwtClaims, JwtTokenPair, SubjectType};
+use crate::usecases::{LoginCommand, MfaChallenge, MfaLoginCommand};
+
+#[derive(Debug)]
+pub enum JwtLoginOutcome {
+    Issued(JwtTokenPair),
+    /// 2 단계 인증 대기. `complete_mfa` 가 성공해야 토큰이 발급된다.
+    MfaRequired(MfaChallenge),
+}
+
+/// RFC 7662 2.2 introspection 응답. 비활성 토큰이면 `active: false` 외의 항목은 비어 있다.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenIntrospection {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// "access" | "refresh" | ... (JwtClaims 의 token_type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_type: Option<SubjectType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<i64>>,
+    /// 대리 로그인 토큰이면 관리자 (RFC 8693 4.1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+}
+
+impl TokenIntrospection {
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+
+    pub fn active(claims: JwtClaims) -> Self {
+        Self {
+            active: true,
+            scope: claims.permissions.map(|p| p.join(" ")),
+            client_id: claims.client_id,
+            token_type: Some(claims.token_type),
+            exp: Some(claims.exp),
+            sub: Some(claims.sub),
+            sub_type: Some(claims.sub_type),
+            roles: claims.roles,
+            act: claims.act,
+        }
+    }
+}
 
 #[async_trait::async_trait]
 pub trait JwtIdentUsecase: Send + Sync + 'static {
@@ -7,6 +60,12 @@ pub trait JwtIdentUsecase: Send + Sync + 'static {
         &self,
         handle: &mut shinespark::db::Handle<'_>,
         command: LoginCommand,
+    ) -> shinespark::Result<JwtLoginOutcome>;
+
+    async fn complete_mfa(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: MfaLoginCommand,
     ) -> shinespark::Result<JwtTokenPair>;
 
     async fn logout(
@@ -20,4 +79,28 @@ pub trait JwtIdentUsecase: Send + Sync + 'static {
         handle: &mut shinespark::db::Handle<'_>,
         refresh_token: &str,
     ) -> shinespark::Result<JwtTokenPair>;
+
+    /// API 요청의 access token 을 확인한다. 서명과 만료 외에 denylist 에 오른 토큰도 `UnAuthorized`.
+    /// refresh, mfa_challenge 토큰으로는 API 를 호출할 수 없다.
+    async fn authenticate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token: &str,
+    ) -> shinespark::Result<JwtClaims>;
+
+    /// 서명과 만료 외에 refresh token 은 저장 여부를, access token 은 denylist 를 확인한다.
+    async fn introspect(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token: &str,
+    ) -> shinespark::Result<TokenIntrospection>;
+
+    /// refresh token 은 삭제하고 access token 은 만료 시각까지 denylist 에 올린다.
+    /// 유효하지 않거나 다른 client 에 발급된 토큰은 아무것도 하지 않는다. (RFC 7009 2.2)
+    async fn revoke(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        token: &str,
+        client_id: &str,
+    ) -> shinespark::Result<()>;
 }
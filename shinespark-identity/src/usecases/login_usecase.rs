@@ -1,9 +1,10 @@
 use crate::entities::UserAggregate;
+use crate::usecases::{MfaChallenge, MfaLoginCommand};
 
 // ==========================================
 // 1. LoginUsecase Cqrs
 // ==========================================
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum LoginCommand {
     Local {
         email: String,
@@ -13,6 +14,17 @@ pub enum LoginCommand {
         provider: crate::entities::AuthProvider,
         provider_uid: String,
     },
+    /// passwordless 로그인. user verification 을 거친 passkey 이므로 2 단계 인증을 생략한다.
+    Passkey {
+        credential: crate::usecases::AssertionCredential,
+    },
+}
+
+#[derive(Debug)]
+pub enum LoginOutcome {
+    Authenticated(UserAggregate),
+    /// 2 단계 인증을 사용하는 계정. `complete_mfa` 로 코드를 검증해야 로그인이 끝난다.
+    MfaRequired(MfaChallenge),
 }
 
 // ==========================================
@@ -26,4 +38,22 @@ pub trait LoginUsecase: Send + Sync + 'static {
         handle: &mut shinespark::db::Handle<'_>,
         command: LoginCommand,
     ) -> shinespark::Result<UserAggregate>;
+
+    /// 1 단계 인증 후 2 단계 인증이 필요한지까지 판단한다.
+    /// `login` 은 자격 증명만 확인하므로 토큰이나 세션을 만드는 곳에서는 이 메서드를 사용한다.
+    async fn authenticate(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: LoginCommand,
+    ) -> shinespark::Result<LoginOutcome> {
+        self.login(handle, command).await.map(LoginOutcome::Authenticated)
+    }
+
+    async fn complete_mfa(
+        &self,
+        _handle: &mut shinespark::db::Handle<'_>,
+        _command: MfaLoginCommand,
+    ) -> shinespark::Result<UserAggregate> {
+        Err(shinespark::Error::NotImplemented)
+    }
 }
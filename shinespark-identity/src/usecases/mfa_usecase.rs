@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+use crate::entities::UserAggregate;
+use crate::usecases::AssertionCredential;
+
+/// 등록 시작 시 사용자에게 보여줄 값. secret 은 이 응답 이후 다시 조회할 수 없다.
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaEnrollment {
+    /// authenticator 앱에 직접 입력하는 base32 secret
+    pub secret: String,
+    /// QR 코드로 전달하는 `otpauth://` URI
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MfaMethod {
+    Totp,
+    Passkey,
+}
+
+/// 1 단계(비밀번호, social) 인증 후 2 단계 인증을 기다리는 상태.
+/// `challenge_token` 은 access token 으로 쓸 수 없는 짧은 수명의 토큰이다.
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaChallenge {
+    pub challenge_token: String,
+    pub expires_in: i64,
+    /// 사용자가 등록해 둔 2 단계 인증 수단
+    pub methods: Vec<MfaMethod>,
+}
+
+#[derive(Debug, Clone)]
+pub enum MfaFactor {
+    /// 6 자리 TOTP 코드 또는 복구 코드
+    Code(String),
+    /// `/identity/webauthn/mfa/start` 로 받은 challenge 에 대한 passkey 서명
+    Passkey(AssertionCredential),
+}
+
+#[derive(Debug, Clone)]
+pub struct MfaLoginCommand {
+    pub challenge_token: String,
+    pub factor: MfaFactor,
+}
+
+// TOTP 2 단계 인증의 등록, 검증, 해제 처리에 집중합니다.
+#[async_trait::async_trait]
+pub trait MfaUsecase: Send + Sync + 'static {
+    /// 새 secret 을 만들어 확인 전 상태로 저장한다. 이미 사용 중이면 `AlreadyExists`.
+    async fn begin_enrollment(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+    ) -> shinespark::Result<MfaEnrollment>;
+
+    /// 앱에서 생성한 코드로 등록을 마치고 복구 코드(평문)를 반환한다.
+    async fn confirm_enrollment(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<Vec<String>>;
+
+    /// TOTP 등록이 끝났는지
+    async fn is_enabled(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+    ) -> shinespark::Result<bool>;
+
+    /// TOTP 코드 또는 복구 코드를 검증한다. 한 번 사용한 코드는 다시 쓸 수 없다.
+    async fn verify(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<()>;
+
+    async fn disable(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<()>;
+
+    async fn regenerate_recovery_codes(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user_id: i64,
+        code: &str,
+    ) -> shinespark::Result<Vec<String>>;
+
+    /// 사용할 수 있는 2 단계 인증 수단이 있으면 challenge 를 발급한다. 없으면 `None`.
+    async fn issue_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+    ) -> shinespark::Result<Option<MfaChallenge>>;
+
+    /// challenge token 의 주인. passkey 2 단계 인증 ceremony 를 시작할 때 쓴다.
+    async fn challenge_user(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        challenge_token: &str,
+    ) -> shinespark::Result<UserAggregate>;
+
+    /// challenge token 과 2 단계 인증 값을 검증하고 인증된 사용자를 반환한다.
+    async fn verify_challenge(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: MfaLoginCommand,
+    ) -> shinespark::Result<UserAggregate>;
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+
+use crate::entities::OAuthClient;
+
+/// 권한 코드가 아닌 OIDC 표준 scope. 권한 테이블에 없어도 client 에 허용할 수 있다.
+pub const OIDC_SCOPES: [&str; 3] = ["openid", "profile", "email"];
+
+// ==========================================
+// 1. OAuthClientUsecase Cqrs
+// ==========================================
+#[derive(Debug)]
+pub struct RegisterClientCommand {
+    pub name: String,
+    /// client 에 허용할 scope. 등록된 권한 코드 또는 OIDC scope(`openid`, `profile`, `email`)여야 한다.
+    pub scopes: Vec<String>,
+    /// authorization_code grant 에서 허용할 redirect_uri. client_credentials 만 쓰면 비워둔다.
+    pub redirect_uris: Vec<String>,
+}
+
+/// 등록 직후에만 평문 secret 을 돌려준다. 서버에는 해시만 저장된다.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredClient {
+    pub client: OAuthClient,
+    pub client_secret: String,
+}
+
+#[derive(Debug)]
+pub struct ClientCredentialsCommand {
+    pub client_id: String,
+    pub client_secret: String,
+    /// 공백으로 구분된 요청 scope. 없으면 허용된 scope 전체
+    pub scope: Option<String>,
+}
+
+/// RFC 6749 5.1 access token 응답
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientAccessToken {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub scope: String,
+}
+
+// ==========================================
+// 2. OAuthClientUsecase Trait
+// ==========================================
+// 사용자 없이 동작하는 service principal 의 등록과 토큰 발급에 집중합니다.
+#[async_trait::async_trait]
+pub trait OAuthClientUsecase: Send + Sync + 'static {
+    async fn register_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: RegisterClientCommand,
+    ) -> shinespark::Result<RegisteredClient>;
+
+    async fn find_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+    ) -> shinespark::Result<Option<OAuthClient>>;
+
+    /// client_id 와 secret 을 확인한다. 틀리면 `InvalidCredentials`.
+    async fn authenticate_client(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        client_id: &str,
+        client_secret: &str,
+    ) -> shinespark::Result<OAuthClient>;
+
+    /// `client_credentials` grant. 허용되지 않은 scope 를 요청하면 `UnAuthorized`.
+    async fn issue_client_token(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: ClientCredentialsCommand,
+    ) -> shinespark::Result<ClientAccessToken>;
+}
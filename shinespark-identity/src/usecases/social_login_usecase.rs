@@ -1,23 +1,98 @@
-use crate::entities::UserAggregate;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{AuthProvider, UserAggregate};
 
 #[derive(Debug)]
 pub struct SocialLoginCommand {
     pub state: String,
+    /// PKCE `S256` code_challenge. verifier 는 호출자가 보관한다.
+    pub code_challenge: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SocialCallbackCommand {
     pub code: String,
+    /// provider 가 callback 으로 돌려준 state
     pub state: String,
+    /// login 시점에 발급하여 보관해 둔 state. `state` 와 일치해야 한다.
+    pub expected_state: String,
+    /// login 시점에 생성한 PKCE code_verifier
+    pub code_verifier: String,
+}
+
+impl SocialCallbackCommand {
+    /// callback 으로 돌아온 state 가 발급한 값과 일치하는지 검사한다. (login CSRF 방지)
+    pub fn verify_state(&self) -> shinespark::Result<()> {
+        if self.expected_state.is_empty() || self.state != self.expected_state {
+            return Err(shinespark::Error::UnAuthorized);
+        }
+        Ok(())
+    }
+}
+
+/// provider 의 id_token 에서 확인된 사용자 정보
+#[derive(Debug, Clone)]
+pub struct SocialProfile {
+    pub provider: AuthProvider,
+    pub provider_uid: String,
+    pub email: Option<String>,
+    /// provider 가 email 소유를 확인했는지 (`email_verified` claim)
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+/// 같은 email 의 기존 계정에 연결을 기다리는 social identity.
+/// provider 가 email 소유를 확인해 주지 않은 경우 기존 계정의 비밀번호로 확인한 뒤 연결한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSocialLink {
+    pub user_uid: uuid::Uuid,
+    pub email: String,
+    pub provider: AuthProvider,
+    pub provider_uid: String,
+}
+
+#[derive(Debug)]
+pub enum SocialLoginOutcome {
+    /// 연결된 identity 로 로그인 (자동 연결, 자동 회원가입 포함)
+    LoggedIn(UserAggregate),
+    /// 기존 계정의 비밀번호 확인이 필요
+    LinkRequired(PendingSocialLink),
+}
+
+#[derive(Debug)]
+pub struct ConfirmSocialLinkCommand {
+    pub link: PendingSocialLink,
+    /// 기존 계정의 local 비밀번호
+    pub password: String,
 }
 
 #[async_trait::async_trait]
 pub trait SocialLoginUsecase: Send + Sync + 'static {
+    /// 이 usecase 가 처리하는 provider. 생성되는 `UserIdentity.provider` 값이다.
+    fn provider(&self) -> AuthProvider;
+
     async fn login(&self, cmd: SocialLoginCommand) -> shinespark::Result<String>;
 
-    async fn callback(
+    /// state 를 검사하고 authorization code 를 교환해 id_token 을 검증한다.
+    /// provider 와 HTTP 로 통신하므로 DB 트랜잭션 밖에서 호출한다.
+    async fn authenticate(&self, cmd: SocialCallbackCommand) -> shinespark::Result<SocialProfile>;
+
+    /// `authenticate` 로 확인한 사용자로 로그인한다. 처음이면 계정을 연결하거나 만든다.
+    async fn resolve(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        profile: SocialProfile,
+    ) -> shinespark::Result<SocialLoginOutcome>;
+}
+
+// social identity 를 기존 계정에 연결하는 처리에 집중합니다.
+#[async_trait::async_trait]
+pub trait SocialLinkUsecase: Send + Sync + 'static {
+    /// 비밀번호가 맞으면 identity 를 연결하고 연결된 사용자를 반환한다.
+    /// 기존 계정에 local identity 가 없으면 `InvalidCredentials`.
+    async fn confirm_link(
         &self,
         handle: &mut shinespark::db::Handle<'_>,
-        cmd: SocialCallbackCommand,
+        cmd: ConfirmSocialLinkCommand,
     ) -> shinespark::Result<UserAggregate>;
 }
@@ -1,4 +1,4 @@
-use crate::entities::{self, User, UserAggregate, UserWithIdentities};
+use crate::entities::{self, User, UserAggregate, UserIdentity, UserWithIdentities};
 
 // ==========================================
 // 1. UserUsecase Cqrs
@@ -30,6 +30,14 @@ pub struct FindUserQuery {
     pub with_deleted: bool,
 }
 
+/// 기존 사용자에 social identity 를 추가로 연결한다.
+#[derive(Debug)]
+pub struct LinkIdentityCommand {
+    pub user_id: i64,
+    pub provider: crate::entities::AuthProvider,
+    pub provider_uid: String,
+}
+
 #[derive(Debug)]
 pub struct UpdateUserCommand {
     pub id: i64,
@@ -59,6 +67,13 @@ pub trait UserUsecase: Send + Sync + 'static {
         handle: &mut shinespark::db::Handle<'_>,
         command: UpdateUserCommand,
     ) -> shinespark::Result<User>;
+
+    /// 이미 다른 사용자에 연결된 identity 이면 `AlreadyExists`
+    async fn link_identity(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        command: LinkIdentityCommand,
+    ) -> shinespark::Result<UserIdentity>;
 }
 
 impl FindUserQuery {
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{UserAggregate, UserIdentity};
+
+// ==========================================
+// 1. WebAuthnUsecase Cqrs
+// ==========================================
+// 브라우저 `navigator.credentials.*` API 의 JSON 형태를 그대로 따른다. 바이너리 값은 base64url 이다.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnUserEntity {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PubKeyCredParam {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub alg: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialDescriptor {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub id: String,
+}
+
+impl CredentialDescriptor {
+    pub fn public_key(id: String) -> Self {
+        Self {
+            type_: "public-key".to_string(),
+            id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelection {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+/// `navigator.credentials.create({ publicKey })` 에 전달하는 옵션
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialCreationOptions {
+    pub challenge: String,
+    pub rp: RelyingParty,
+    pub user: WebAuthnUserEntity,
+    pub pub_key_cred_params: Vec<PubKeyCredParam>,
+    pub timeout: u64,
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    pub authenticator_selection: AuthenticatorSelection,
+    pub attestation: String,
+}
+
+/// `navigator.credentials.get({ publicKey })` 에 전달하는 옵션
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRequestOptions {
+    pub challenge: String,
+    pub rp_id: String,
+    pub timeout: u64,
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    pub user_verification: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+/// 등록 ceremony 결과 (`PublicKeyCredential.toJSON()`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegistrationCredential {
+    pub id: String,
+    pub response: AttestationResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(default)]
+    pub user_handle: Option<String>,
+}
+
+/// 인증 ceremony 결과 (`PublicKeyCredential.toJSON()`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssertionCredential {
+    pub id: String,
+    pub response: AssertionResponse,
+}
+
+// ==========================================
+// 2. WebAuthnUsecase Trait
+// ==========================================
+// passkey 등록과 인증 ceremony 처리에 집중합니다.
+#[async_trait::async_trait]
+pub trait WebAuthnUsecase: Send + Sync + 'static {
+    async fn start_registration(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+    ) -> shinespark::Result<CredentialCreationOptions>;
+
+    /// 검증된 credential 을 `passkey` identity 로 저장한다.
+    async fn finish_registration(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: &UserAggregate,
+        credential: RegistrationCredential,
+    ) -> shinespark::Result<UserIdentity>;
+
+    /// `user` 가 없으면 passwordless 로그인(discoverable credential),
+    /// 있으면 해당 사용자의 passkey 로 2 단계 인증을 요청한다.
+    async fn start_authentication(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: Option<&UserAggregate>,
+    ) -> shinespark::Result<CredentialRequestOptions>;
+
+    /// 서명을 검증하고 credential 의 주인을 반환한다.
+    /// `user` 가 주어지면 그 사용자의 credential 이어야 한다. 없으면 user verification 을 요구한다.
+    async fn finish_authentication(
+        &self,
+        handle: &mut shinespark::db::Handle<'_>,
+        user: Option<&UserAggregate>,
+        credential: AssertionCredential,
+    ) -> shinespark::Result<UserAggregate>;
+}
@@ -1,6 +1,6 @@
 use config::{Config, Environment, File};
 use serde::Deserialize;
-use std::{env, path::PathBuf};
+use std::{collections::BTreeMap, env, path::PathBuf};
 
 #[derive(Debug, Deserialize, Clone, Copy, Default)]
 #[serde(rename_all = "lowercase")]
@@ -47,10 +47,57 @@ pub struct TraceConfig {
     pub file: Option<TraceFileConfig>,
 }
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
 pub struct DatabaseConfig {
     pub url: String,
     pub max_connections: u32,
+    /// 쓰지 않아도 열어 두는 커넥션 수
+    pub min_connections: u32,
+    /// pool 에서 커넥션을 얻을 때까지 기다리는 시간
+    pub acquire_timeout_secs: u64,
+    /// 이 시간 동안 쓰지 않은 커넥션을 닫는다. 0 이면 닫지 않는다.
+    pub idle_timeout_secs: u64,
+    /// 커넥션을 이 시간보다 오래 쓰지 않는다. 0 이면 제한하지 않는다.
+    pub max_lifetime_secs: u64,
+    /// 쿼리 하나의 최대 실행 시간. 0 이면 제한하지 않는다.
+    /// postgres 는 `statement_timeout`, mysql 은 SELECT 에만 적용되는 `max_execution_time`. sqlite 는 무시한다.
+    pub statement_timeout_ms: u64,
+    /// DB 에서 보이는 클라이언트 이름. postgres 만 지원한다.
+    pub application_name: Option<String>,
+    /// pool 에서 꺼낼 때마다 커넥션이 살아 있는지 확인한다.
+    pub test_before_acquire: bool,
+    /// 커넥션을 새로 열 때마다 실행하는 SQL (예: `SET search_path TO app, public`)
+    pub after_connect: Vec<String>,
+    /// 서버 시작 시 바이너리에 포함된 마이그레이션을 적용한다.
+    pub auto_migrate: bool,
+    /// 트랜잭션이 아닌 handle 을 commit, rollback 하면 실패하게 한다. 켜면 프로세스 전체에 적용된다.
+    pub strict_transactions: bool,
+    /// 읽기 전용 replica URL 목록. pool 설정은 primary 와 같다.
+    pub replicas: Vec<String>,
+    /// replica 상태를 확인하는 주기
+    pub replica_health_check_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+            max_lifetime_secs: 1800,
+            statement_timeout_ms: 0,
+            application_name: None,
+            test_before_acquire: true,
+            after_connect: vec![],
+            auto_migrate: false,
+            strict_transactions: false,
+            replicas: vec![],
+            replica_health_check_secs: 10,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -91,6 +138,7 @@ impl Default for JwtConfig {
 }
 
 #[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
 pub struct GoogleLoginConfig {
     pub client_id: String,
     pub client_secret: String,
@@ -109,6 +157,269 @@ impl Default for GoogleLoginConfig {
     }
 }
 
+/// `.well-known/openid-configuration` 을 제공하는 임의의 OIDC provider 설정.
+/// `name` 은 `/identity/oauth2/{provider}/...` 경로와 identity 의 provider 값으로 쓰인다.
+/// (소문자, 숫자, `-`, `_` 만 허용) `social_providers` 에서는 테이블 key 가 name 이 된다.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct OidcProviderConfig {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub scope: String,
+}
+
+impl Default for OidcProviderConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            issuer: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_uri: String::new(),
+            scope: "openid email profile".to_string(),
+        }
+    }
+}
+
+/// `[social_providers.{name}]` 항목. `kind` 로 구현체를 고른다.
+///
+/// provider 이름을 key 로 쓰므로 설정 파일끼리 같은 provider 를 key 단위로 합치고,
+/// `APP__SOCIAL_PROVIDERS__GOOGLE__CLIENT_SECRET` 처럼 환경 변수로 값을 덮어쓸 수 있다.
+///
+/// ```toml
+/// [social_providers.google]
+/// kind = "google"
+/// client_id = "..."
+///
+/// [social_providers.apple]
+/// kind = "oidc"
+/// issuer = "https://appleid.apple.com"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SocialProviderConfig {
+    Google(GoogleLoginConfig),
+    Oidc(OidcProviderConfig),
+}
+
+/// TOTP 2 단계 인증 설정
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MfaConfig {
+    /// authenticator 앱에 표시되는 발급자 이름
+    pub issuer: String,
+    /// TOTP secret 을 DB 에 암호화해 저장할 때 쓰는 key
+    pub encryption_key: String,
+    /// 비밀번호 확인 후 OTP 입력까지 허용하는 시간
+    pub challenge_ttl_secs: i64,
+    /// 앞뒤로 허용하는 30 초 step 수 (시계 오차)
+    pub allowed_skew_steps: u64,
+    pub recovery_code_count: usize,
+    /// challenge token 하나로 허용하는 코드 입력 실패 횟수. 넘으면 다시 로그인해야 한다.
+    pub max_challenge_attempts: u32,
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "Shinespark".to_string(),
+            encryption_key: "change-this-mfa-key-in-production".to_string(),
+            challenge_ttl_secs: 300,
+            allowed_skew_steps: 1,
+            recovery_code_count: 10,
+            max_challenge_attempts: 5,
+        }
+    }
+}
+
+/// WebAuthn (passkey) relying party 설정
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct WebAuthnConfig {
+    /// relying party id. 보통 origin 의 host (예: `example.com`)
+    pub rp_id: String,
+    /// authenticator 에 표시되는 서비스 이름
+    pub rp_name: String,
+    /// 허용하는 origin (예: `https://example.com`). clientDataJSON 의 origin 과 같아야 한다.
+    pub origin: String,
+    pub challenge_ttl_secs: i64,
+    /// 브라우저에 전달하는 ceremony 제한 시간
+    pub timeout_ms: u64,
+}
+
+impl Default for WebAuthnConfig {
+    fn default() -> Self {
+        Self {
+            rp_id: "localhost".to_string(),
+            rp_name: "Shinespark".to_string(),
+            origin: "http://localhost:8085".to_string(),
+            challenge_ttl_secs: 300,
+            timeout_ms: 60000,
+        }
+    }
+}
+
+/// shinespark 를 다른 앱의 OAuth2 / OIDC authorization server 로 쓸 때의 설정
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct OAuthServerConfig {
+    /// discovery 와 ID token 의 `iss`. 외부에서 접근하는 base URL (끝에 `/` 없이)
+    pub issuer: String,
+    pub authorization_code_ttl_secs: i64,
+    /// ID token 을 ES256 으로 서명하는 P-256 키 (PKCS#8 PEM). 비어 있으면 시작할 때마다 새로 만들므로
+    /// 재시작하거나 여러 인스턴스로 띄우면 `jwks_uri` 의 키가 바뀐다.
+    pub id_token_signing_key: String,
+}
+
+impl Default for OAuthServerConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "http://localhost:8085".to_string(),
+            authorization_code_ttl_secs: 300,
+            id_token_signing_key: String::new(),
+        }
+    }
+}
+
+/// 관리자가 다른 사용자로 대리 로그인(impersonation)할 때의 설정
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ImpersonationConfig {
+    /// 대리 로그인 토큰의 유효 시간. refresh token 은 발급하지 않는다.
+    pub token_ttl_secs: i64,
+}
+
+impl Default for ImpersonationConfig {
+    fn default() -> Self {
+        Self {
+            token_ttl_secs: 900,
+        }
+    }
+}
+
+/// session 모드 로그인(`SID` 쿠키)의 설정
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// 마지막 요청 이후 이 시간이 지나면 세션이 만료된다.
+    pub inactivity_ttl_secs: i64,
+    /// 만료된 세션을 DB 에서 지우는 주기
+    pub cleanup_interval_secs: u64,
+    /// 세션의 uid 로 읽은 사용자를 재사용하는 시간. 역할 변경은 이 시간 안에 반영된다.
+    pub user_cache_ttl_secs: u64,
+    pub secure_cookie: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            inactivity_ttl_secs: 86400,
+            cleanup_interval_secs: 600,
+            user_cache_ttl_secs: 30,
+            secure_cookie: false,
+        }
+    }
+}
+
+/// cookie / session 인증 요청의 CSRF 검사 설정
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CsrfConfig {
+    pub enabled: bool,
+    pub secure_cookie: bool,
+    /// 검사하지 않는 경로 prefix. client 인증을 쓰는 OAuth2 endpoint 처럼 쿠키를 쓰지 않는 곳만 넣는다.
+    pub exempt_paths: Vec<String>,
+}
+
+impl Default for CsrfConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            secure_cookie: false,
+            exempt_paths: vec![
+                "/oauth2/token".to_string(),
+                "/oauth2/introspect".to_string(),
+                "/oauth2/revoke".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKey {
+    /// 접속 IP (신뢰하는 proxy 뒤에서는 `X-Forwarded-For`)
+    #[default]
+    Ip,
+    /// 인증된 사용자 / client. 인증되지 않은 요청은 IP 로 센다.
+    Subject,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitStoreKind {
+    /// 인스턴스마다 따로 센다.
+    #[default]
+    Memory,
+    /// 여러 인스턴스가 `shs_http_rate_limit` 테이블을 공유한다.
+    #[serde(alias = "postgres")]
+    Database,
+}
+
+/// 같은 한도를 적용할 경로 묶음. token bucket 으로 `capacity` 만큼 몰아서 보낼 수 있고,
+/// 분당 `refill_per_minute` 개씩 다시 채워진다.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateLimitGroupConfig {
+    pub name: String,
+    /// 경로 prefix. 먼저 선언된 그룹이 우선한다.
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub key: RateLimitKey,
+    pub capacity: u32,
+    pub refill_per_minute: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub store: RateLimitStoreKind,
+    /// 이 주소에서 온 요청은 `X-Forwarded-For` 의 클라이언트 주소를 쓴다.
+    pub trusted_proxies: Vec<std::net::IpAddr>,
+    pub groups: Vec<RateLimitGroupConfig>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            store: RateLimitStoreKind::Memory,
+            trusted_proxies: vec![],
+            groups: vec![RateLimitGroupConfig {
+                name: "login".to_string(),
+                paths: [
+                    "/auth/login",
+                    "/auth/mfa",
+                    "/identity/session/login",
+                    "/identity/session/mfa",
+                    "/identity/jwt/login",
+                    "/identity/jwt/mfa",
+                    "/identity/webauthn/login/",
+                    "/identity/webauthn/mfa/",
+                ]
+                .map(String::from)
+                .to_vec(),
+                key: RateLimitKey::Ip,
+                capacity: 10,
+                refill_per_minute: 10,
+            }],
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct TemplateConfig {
     pub dir: String,
@@ -129,7 +440,15 @@ pub struct AppConfig {
     pub database: DatabaseConfig,
     pub http: HttpConfig,
     pub jwt: JwtConfig,
-    pub google_login: GoogleLoginConfig,
+    /// key: provider 이름
+    pub social_providers: BTreeMap<String, SocialProviderConfig>,
+    pub mfa: MfaConfig,
+    pub webauthn: WebAuthnConfig,
+    pub oauth_server: OAuthServerConfig,
+    pub impersonation: ImpersonationConfig,
+    pub session: SessionConfig,
+    pub csrf: CsrfConfig,
+    pub rate_limit: RateLimitConfig,
     pub template: TemplateConfig,
 }
 
@@ -191,7 +510,7 @@ impl AppConfig {
 mod tests {
     use serial_test::serial;
 
-    use crate::config::AppConfig;
+    use crate::config::{AppConfig, SocialProviderConfig};
 
     #[test]
     #[serial]
@@ -258,6 +577,58 @@ mod tests {
         assert!(matches!(file_cfg.format, crate::config::TraceFormat::Json));
     }
 
+    #[test]
+    #[serial]
+    fn test_social_providers_merge_by_name() {
+        let dir = std::env::temp_dir().join(format!("shinespark-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe_name = crate::util::base_executable_name();
+        std::fs::write(
+            dir.join(format!("{}.toml", exe_name)),
+            r#"
+            [social_providers.google]
+            kind = "google"
+            client_id = "base-client"
+
+            [social_providers.corp]
+            kind = "oidc"
+            issuer = "https://sso.example.com"
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(format!("{}-local.toml", exe_name)),
+            r#"
+            [social_providers.google]
+            redirect_uri = "http://localhost/callback"
+            "#,
+        )
+        .unwrap();
+        unsafe {
+            std::env::set_var("APP__SOCIAL_PROVIDERS__GOOGLE__CLIENT_SECRET", "from-env");
+        }
+
+        let config_result = AppConfig::load(dir.clone(), "test");
+
+        unsafe {
+            std::env::remove_var("APP__SOCIAL_PROVIDERS__GOOGLE__CLIENT_SECRET");
+        }
+        std::fs::remove_dir_all(&dir).ok();
+
+        let providers = config_result.expect("Failed to load config").social_providers;
+        assert_eq!(providers.len(), 2);
+        let Some(SocialProviderConfig::Google(google)) = providers.get("google") else {
+            panic!("google provider should exist");
+        };
+        assert_eq!(google.client_id, "base-client");
+        assert_eq!(google.client_secret, "from-env");
+        assert_eq!(google.redirect_uri, "http://localhost/callback");
+        assert!(matches!(
+            providers.get("corp"),
+            Some(SocialProviderConfig::Oidc(_))
+        ));
+    }
+
     #[test]
     #[serial]
     fn test_load_dotenv() {
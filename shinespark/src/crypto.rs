@@ -272,3 +272,237 @@ pub mod password {
         }
     }
 }
+
+pub mod pkce {
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use sha2::{Digest, Sha256};
+
+    /// RFC 7636 code_verifier — 32 byte 난수를 base64url(no padding) 로 인코딩한 43자 문자열.
+    pub fn generate_code_verifier() -> String {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// `S256` 방식 code_challenge = base64url(sha256(code_verifier)).
+    pub fn code_challenge_s256(code_verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_code_challenge_rfc7636_vector() {
+            // RFC 7636 Appendix B
+            let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+            assert_eq!(
+                code_challenge_s256(verifier),
+                "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+            );
+        }
+
+        #[test]
+        fn test_generate_code_verifier() {
+            let a = generate_code_verifier();
+            let b = generate_code_verifier();
+            assert_eq!(a.len(), 43);
+            assert_ne!(a, b);
+        }
+    }
+}
+
+/// RFC 6238 TOTP (HMAC-SHA1, 6 자리, 30 초)
+pub mod totp {
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+    use data_encoding::BASE32_NOPAD;
+    use hmac::{Hmac, Mac};
+    use sha1::Sha1;
+
+    pub const DIGITS: u32 = 6;
+    pub const STEP_SECS: u64 = 30;
+    const SECRET_LEN: usize = 20;
+
+    pub fn generate_secret() -> Vec<u8> {
+        let mut secret = vec![0u8; SECRET_LEN];
+        OsRng.fill_bytes(&mut secret);
+        secret
+    }
+
+    /// authenticator 앱에 입력하는 base32 문자열
+    pub fn encode_secret(secret: &[u8]) -> String {
+        BASE32_NOPAD.encode(secret)
+    }
+
+    pub fn decode_secret(encoded: &str) -> Option<Vec<u8>> {
+        BASE32_NOPAD.decode(encoded.trim_end_matches('=').as_bytes()).ok()
+    }
+
+    pub fn step(unix_secs: u64) -> u64 {
+        unix_secs / STEP_SECS
+    }
+
+    pub fn code_at_step(secret: &[u8], step: u64) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("hmac accepts any key length");
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            digest[offset] & 0x7f,
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]);
+        format!(
+            "{:0width$}",
+            binary % 10u32.pow(DIGITS),
+            width = DIGITS as usize
+        )
+    }
+
+    /// 현재 step 앞뒤 `skew` 만큼 허용하여 일치하는 step 을 돌려준다.
+    /// 재사용 방지를 위해 호출자는 마지막으로 사용한 step 보다 큰지 확인해야 한다.
+    pub fn verify(secret: &[u8], code: &str, unix_secs: u64, skew: u64) -> Option<u64> {
+        let code = code.trim();
+        if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let current = step(unix_secs);
+        (current.saturating_sub(skew)..=current + skew)
+            .find(|s| constant_time_eq(code_at_step(secret, *s).as_bytes(), code.as_bytes()))
+    }
+
+    /// `otpauth://totp/{issuer}:{account}?secret=...&issuer=...` (QR 코드로 전달)
+    pub fn otpauth_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            percent_encode(issuer),
+            percent_encode(account),
+            encode_secret(secret),
+            percent_encode(issuer),
+            DIGITS,
+            STEP_SECS,
+        )
+    }
+
+    fn percent_encode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_rfc6238_vectors() {
+            // RFC 6238 Appendix B (SHA1) 의 하위 6 자리
+            let secret = b"12345678901234567890";
+            assert_eq!(code_at_step(secret, step(59)), "287082");
+            assert_eq!(code_at_step(secret, step(1111111109)), "081804");
+            assert_eq!(code_at_step(secret, step(2000000000)), "279037");
+        }
+
+        #[test]
+        fn test_verify_with_skew() {
+            let secret = generate_secret();
+            let now = 1_700_000_000;
+            let previous = code_at_step(&secret, step(now) - 1);
+            assert_eq!(verify(&secret, &previous, now, 1), Some(step(now) - 1));
+            assert_eq!(verify(&secret, &previous, now, 0), None);
+            assert_eq!(verify(&secret, "12345", now, 1), None);
+            assert_eq!(verify(&secret, "abcdef", now, 1), None);
+        }
+
+        #[test]
+        fn test_secret_encoding_and_uri() {
+            let secret = generate_secret();
+            let encoded = encode_secret(&secret);
+            assert_eq!(decode_secret(&encoded), Some(secret.clone()));
+
+            let uri = otpauth_uri("Shine Spark", "user@example.com", &secret);
+            assert!(uri.starts_with("otpauth://totp/Shine%20Spark:user%40example.com?secret="));
+            assert!(uri.contains(&format!("secret={}", encoded)));
+        }
+    }
+}
+
+/// 저장용 비밀 값 암호화 (AES-256-GCM). 결과는 `base64(nonce || ciphertext)`.
+pub mod cipher {
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce, aead::Aead};
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use sha2::{Digest, Sha256};
+
+    const NONCE_LEN: usize = 12;
+
+    pub struct SecretCipher {
+        cipher: Aes256Gcm,
+    }
+
+    impl SecretCipher {
+        /// 설정의 문자열 key 를 SHA-256 으로 늘려 256 bit key 로 쓴다.
+        pub fn new(key: &str) -> Self {
+            let key = Sha256::digest(key.as_bytes());
+            Self {
+                cipher: Aes256Gcm::new(&key),
+            }
+        }
+
+        pub fn encrypt(&self, plaintext: &[u8]) -> crate::Result<String> {
+            let mut nonce = [0u8; NONCE_LEN];
+            OsRng.fill_bytes(&mut nonce);
+            let ciphertext = self
+                .cipher
+                .encrypt(Nonce::from_slice(&nonce), plaintext)
+                .map_err(|e| anyhow::anyhow!("{}", e).context("failed to encrypt secret"))?;
+            let mut out = nonce.to_vec();
+            out.extend_from_slice(&ciphertext);
+            Ok(STANDARD.encode(out))
+        }
+
+        pub fn decrypt(&self, encoded: &str) -> crate::Result<Vec<u8>> {
+            let bytes = STANDARD
+                .decode(encoded)
+                .map_err(|e| anyhow::anyhow!(e).context("invalid encrypted secret"))?;
+            if bytes.len() <= NONCE_LEN {
+                return Err(crate::Error::IllegalState(
+                    "encrypted secret too short".into(),
+                ));
+            }
+            let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+            self.cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|e| anyhow::anyhow!("{}", e).context("failed to decrypt secret").into())
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_encrypt_decrypt() {
+            let cipher = SecretCipher::new("test-key");
+            let encrypted = cipher.encrypt(b"totp-secret").unwrap();
+            assert_ne!(encrypted, cipher.encrypt(b"totp-secret").unwrap());
+            assert_eq!(cipher.decrypt(&encrypted).unwrap(), b"totp-secret");
+
+            let other = SecretCipher::new("other-key");
+            assert!(other.decrypt(&encrypted).is_err());
+        }
+    }
+}
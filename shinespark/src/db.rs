@@ -1,10 +1,25 @@
+mod compose;
 mod handle;
+mod migrate;
+mod pool;
+mod replica;
+mod transaction;
+mod verify;
 
+pub use compose::{
+    Compare, Cursor, CursorValue, Direction, Filter, In, Keyset, Like, Limit, Op, Page, Range,
+    Sort, escape_like,
+};
 use handle::*;
+pub use migrate::{MIGRATOR, MigrationStatus};
+pub use pool::PoolStats;
+pub use replica::{ReplicaSet, ReplicaStats};
 use sqlx::{
     QueryBuilder,
     query::{Query, QueryAs},
 };
+pub use transaction::{IsolationLevel, TxOptions, is_retryable};
+pub use verify::{StatementCheck, check_statements};
 
 #[cfg(not(any(
     feature = "db-driver-postgres",
@@ -39,23 +54,82 @@ pub type MySqlHandle<'c> = BasicHandle<'c, sqlx::MySql>;
 
 pub type Handle<'c> = BasicHandle<'c, Driver>;
 
+/// 호출한 crate 의 `sql/{driver}/` 아래에서 현재 드라이버에 맞는 SQL 파일을 읽는다.
+///
+/// `include_sql!("user_repository/find_user.sql")` 는 postgres 빌드에서
+/// `sql/postgres/user_repository/find_user.sql` 이 된다.
+#[cfg(feature = "db-driver-postgres")]
+#[macro_export]
+macro_rules! include_sql {
+    ($path:literal) => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/sql/postgres/", $path))
+    };
+}
+
+#[cfg(feature = "db-driver-sqlite")]
+#[macro_export]
+macro_rules! include_sql {
+    ($path:literal) => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/sql/sqlite/", $path))
+    };
+}
+
+#[cfg(feature = "db-driver-mysql")]
+#[macro_export]
+macro_rules! include_sql {
+    ($path:literal) => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/sql/mysql/", $path))
+    };
+}
+
 #[derive(Debug, Clone)]
 pub struct Database {
+    /// primary pool
     pub inner: sqlx::Pool<Driver>,
+    replicas: std::sync::Arc<ReplicaSet<Driver>>,
+    /// true 면 이 DB 의 트랜잭션이 아닌 handle 을 commit, rollback 할 때 `IllegalState`
+    strict_transactions: bool,
 }
 
 impl Database {
     pub async fn new(config: &crate::config::DatabaseConfig) -> crate::Result<Self> {
-        let inner = sqlx::pool::PoolOptions::<Driver>::new()
-            .max_connections(config.max_connections)
-            .connect(&config.url)
+        let options = pool::pool_options(config);
+        #[cfg(feature = "db-driver-sqlite")]
+        let in_memory = is_in_memory(&config.url);
+        // 연결이 모두 닫히면 in-memory DB 도 사라지므로 하나는 계속 열어 둔다.
+        #[cfg(feature = "db-driver-sqlite")]
+        let options = match in_memory {
+            true => options.min_connections(1).idle_timeout(None).max_lifetime(None),
+            false => options,
+        };
+        let inner = options
+            .connect_with(pool::connect_options(config, &config.url)?)
             .await
             .map_err(|e| crate::Error::DatabaseError(anyhow::anyhow!(e)))?;
-        Ok(Self { inner })
+        let database = Self {
+            inner,
+            replicas: std::sync::Arc::new(ReplicaSet::new(replica::connect_replicas(config)?)),
+            strict_transactions: config.strict_transactions,
+        };
+        database.check_replicas().await;
+        // in-memory DB 는 항상 비어 있으므로 연결하면서 스키마를 만든다.
+        #[cfg(feature = "db-driver-sqlite")]
+        if in_memory {
+            database.migrate().await?;
+        }
+        Ok(database)
     }
 
+    /// primary handle. replica 가 있으면 repository 가 `read_inner()` 로 읽을 때 replica 를 쓴다.
     pub fn handle(&self) -> Handle<'_> {
-        Handle::Pool(self.inner.clone())
+        match self.replicas.is_empty() {
+            true => Handle::Pool(self.inner.clone(), self.strict_transactions),
+            false => Handle::Replicated(
+                self.inner.clone(),
+                self.replicas.clone(),
+                self.strict_transactions,
+            ),
+        }
     }
 
     pub async fn tx(&self) -> crate::Result<Handle<'_>> {
@@ -65,20 +139,40 @@ impl Database {
 
     pub async fn conn(&self) -> crate::Result<Handle<'_>> {
         let conn = self.inner.acquire().await.map_err(map_err)?;
-        Ok(Handle::Conn(conn))
+        Ok(Handle::Conn(conn, self.strict_transactions))
     }
 
+    #[cfg(not(feature = "db-driver-sqlite"))]
     pub async fn new_dotenv() -> crate::Result<Self> {
         use std::env;
         dotenvy::dotenv().ok();
         let config = crate::config::DatabaseConfig {
             url: env::var("DATABASE_URL").unwrap(),
             max_connections: 1,
+            strict_transactions: true,
+            ..Default::default()
+        };
+        Self::new(&config).await
+    }
+
+    /// sqlite 빌드의 테스트는 외부 DB 없이 매번 새 in-memory DB 를 쓴다.
+    #[cfg(feature = "db-driver-sqlite")]
+    pub async fn new_dotenv() -> crate::Result<Self> {
+        let config = crate::config::DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            strict_transactions: true,
+            ..Default::default()
         };
         Self::new(&config).await
     }
 }
 
+#[cfg(feature = "db-driver-sqlite")]
+fn is_in_memory(url: &str) -> bool {
+    url.contains(":memory:") || url.contains("mode=memory")
+}
+
 // pub fn bind_opt<'q, T>(
 //     query_builder: &mut sqlx::QueryBuilder<'q, Driver>,
 //     sql: &str,
@@ -123,10 +217,72 @@ impl SqlStatement for &'static str {
     }
 }
 
+/// 한 문장에 bind 할 수 있는 최대 파라미터 수
+#[cfg(feature = "db-driver-postgres")]
+pub const MAX_BIND_PARAMS: usize = 65535;
+
+#[cfg(feature = "db-driver-sqlite")]
+pub const MAX_BIND_PARAMS: usize = 32766;
+
+#[cfg(feature = "db-driver-mysql")]
+pub const MAX_BIND_PARAMS: usize = 65535;
+
+/// `SqlBuilderExt::push_in` 에 넘길 수 있는 값. postgres 는 목록을 배열 하나로 bind 하므로
+/// 배열 타입이 있어야 한다.
+#[cfg(feature = "db-driver-postgres")]
+pub trait SqlListValue<'args>:
+    sqlx::Type<Driver> + sqlx::Encode<'args, Driver> + sqlx::postgres::PgHasArrayType + Send + Sync
+{
+}
+
+#[cfg(feature = "db-driver-postgres")]
+impl<'args, T> SqlListValue<'args> for T where
+    T: sqlx::Type<Driver>
+        + sqlx::Encode<'args, Driver>
+        + sqlx::postgres::PgHasArrayType
+        + Send
+        + Sync
+{
+}
+
+#[cfg(not(feature = "db-driver-postgres"))]
+pub trait SqlListValue<'args>:
+    sqlx::Type<Driver> + sqlx::Encode<'args, Driver> + Send + Sync
+{
+}
+
+#[cfg(not(feature = "db-driver-postgres"))]
+impl<'args, T> SqlListValue<'args> for T where
+    T: sqlx::Type<Driver> + sqlx::Encode<'args, Driver> + Send + Sync
+{
+}
+
+/// `sql` 은 그대로 SQL 에 들어가므로 `" AND u.id"` 처럼 코드에 적은 문자열만 넘긴다.
 pub trait SqlBuilderExt<'args> {
     fn push_option<T>(&mut self, sql: &str, value: &'args Option<T>) -> &mut Self
     where
         T: sqlx::Type<Driver> + sqlx::Encode<'args, Driver> + Send + Sync + 'args;
+
+    /// `sql IN (?, ?, ...)`. postgres 는 `sql = ANY($n)` 로 배열 하나를 bind 해 값 개수가 달라도
+    /// 같은 prepared statement 를 쓴다. 값이 없으면 어떤 행도 고르지 않는다.
+    fn push_in<T>(&mut self, sql: &str, values: &'args [T]) -> &mut Self
+    where
+        T: SqlListValue<'args> + 'args;
+
+    /// 있는 경계만으로 범위 조건을 덧붙인다. 둘 다 있으면 `sql BETWEEN ? AND ?` (양 끝 포함),
+    /// 하나만 있으면 `>=` / `<=`, 둘 다 없으면 아무것도 붙이지 않는다.
+    fn push_between_option<T>(
+        &mut self,
+        sql: &str,
+        from: &'args Option<T>,
+        to: &'args Option<T>,
+    ) -> &mut Self
+    where
+        T: sqlx::Type<Driver> + sqlx::Encode<'args, Driver> + Send + Sync + 'args;
+
+    /// `value` 가 `Some` 이면 `sql` 에 `value` 가 들어 있는 행만 남긴다. 대소문자를 무시하고
+    /// `%`, `_` 는 글자 그대로 찾는다.
+    fn push_ilike_option(&mut self, sql: &str, value: &Option<impl AsRef<str>>) -> &mut Self;
 }
 
 impl<'args> SqlBuilderExt<'args> for sqlx::QueryBuilder<'args, Driver> {
@@ -140,6 +296,94 @@ impl<'args> SqlBuilderExt<'args> for sqlx::QueryBuilder<'args, Driver> {
             self
         }
     }
+
+    #[cfg(feature = "db-driver-postgres")]
+    fn push_in<T>(&mut self, sql: &str, values: &'args [T]) -> &mut Self
+    where
+        T: SqlListValue<'args> + 'args,
+    {
+        self.push(sql).push(" = ANY(").push_bind(values).push(")")
+    }
+
+    #[cfg(not(feature = "db-driver-postgres"))]
+    fn push_in<T>(&mut self, sql: &str, values: &'args [T]) -> &mut Self
+    where
+        T: SqlListValue<'args> + 'args,
+    {
+        // `IN ()` 는 문법 오류라서 어떤 값과도 같지 않은 NULL 을 넣는다.
+        if values.is_empty() {
+            return self.push(sql).push(" IN (NULL)");
+        }
+        self.push(sql).push(" IN (");
+        let mut separated = self.separated(", ");
+        for value in values {
+            separated.push_bind(value);
+        }
+        self.push(")")
+    }
+
+    fn push_between_option<T>(
+        &mut self,
+        sql: &str,
+        from: &'args Option<T>,
+        to: &'args Option<T>,
+    ) -> &mut Self
+    where
+        T: sqlx::Type<Driver> + sqlx::Encode<'args, Driver> + Send + Sync + 'args,
+    {
+        match (from, to) {
+            (Some(from), Some(to)) => {
+                self.push(sql).push(" BETWEEN ").push_bind(from).push(" AND ").push_bind(to)
+            }
+            (Some(from), None) => self.push(sql).push(" >= ").push_bind(from),
+            (None, Some(to)) => self.push(sql).push(" <= ").push_bind(to),
+            (None, None) => self,
+        }
+    }
+
+    fn push_ilike_option(&mut self, sql: &str, value: &Option<impl AsRef<str>>) -> &mut Self {
+        match value {
+            Some(value) => self
+                .push(sql)
+                .push(compose::like_operator(true))
+                .push_bind(format!("%{}%", escape_like(value.as_ref())))
+                .push(" ESCAPE '!'"),
+            None => self,
+        }
+    }
+}
+
+/// `rows` 를 여러 행 `INSERT ... VALUES (..), (..)` 로 넣고 넣은 행 수를 반환한다.
+///
+/// `insert` 는 `VALUES` 앞까지의 SQL 이고 `push_row` 는 한 행의 값 `columns` 개를 bind 한다.
+/// bind 파라미터가 `MAX_BIND_PARAMS` 를 넘지 않도록 문장을 나누므로, 모두 넣거나 하나도
+/// 넣지 않아야 하면 트랜잭션 handle 을 넘긴다.
+///
+/// ```ignore
+/// db::insert_values(handle, "INSERT INTO t (user_id, code) ", 2, &codes, |mut row, code| {
+///     row.push_bind(user_id).push_bind(code);
+/// })
+/// .await?;
+/// ```
+pub async fn insert_values<'a, T, F>(
+    handle: &mut Handle<'_>,
+    insert: &str,
+    columns: usize,
+    rows: &'a [T],
+    mut push_row: F,
+) -> crate::Result<u64>
+where
+    T: Sync,
+    F: FnMut(sqlx::query_builder::Separated<'_, 'a, Driver, &'static str>, &'a T) + Send,
+{
+    let chunk_size = (MAX_BIND_PARAMS / columns.max(1)).max(1);
+    let mut inserted = 0;
+    for chunk in rows.chunks(chunk_size) {
+        let mut b = QueryBuilder::<Driver>::new(insert);
+        b.push_values(chunk, &mut push_row);
+        inserted += b.build().execute(handle.inner()).await.map_err(map_err)?.rows_affected();
+    }
+    Ok(inserted)
 }
 
 #[cfg(test)]
@@ -147,7 +391,7 @@ mod tests {
     use super::*;
 
     #[tokio::test]
-    #[ignore]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
     async fn test_new_database() {
         let database = Database::new_dotenv().await.unwrap();
         {
@@ -166,4 +410,155 @@ mod tests {
             sqlx::query("SELECT 1").execute(c.inner()).await.unwrap();
         }
     }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_savepoint() {
+        let database = Database::new_dotenv().await.unwrap();
+        let count = async |h: &mut Handle<'_>| -> i64 {
+            sqlx::query_scalar("SELECT COUNT(*) FROM savepoint_test")
+                .fetch_one(h.inner())
+                .await
+                .unwrap()
+        };
+
+        let mut tx = database.tx().await.unwrap();
+        sqlx::query("CREATE TEMPORARY TABLE savepoint_test (id INTEGER NOT NULL)")
+            .execute(tx.inner())
+            .await
+            .unwrap();
+        assert!(tx.is_transactional());
+        assert_eq!(tx.depth(), 1);
+        {
+            let mut sp = tx.savepoint().await.unwrap();
+            assert_eq!(sp.depth(), 2);
+            sqlx::query("INSERT INTO savepoint_test (id) VALUES (1)")
+                .execute(sp.inner())
+                .await
+                .unwrap();
+            sp.rollback().await.unwrap();
+        }
+        assert_eq!(count(&mut tx).await, 0);
+        {
+            let mut sp = tx.savepoint().await.unwrap();
+            sqlx::query("INSERT INTO savepoint_test (id) VALUES (2)")
+                .execute(sp.inner())
+                .await
+                .unwrap();
+            sp.commit().await.unwrap();
+        }
+        assert_eq!(tx.depth(), 1);
+        assert_eq!(count(&mut tx).await, 1);
+        tx.rollback().await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_strict_transactions() {
+        // new_dotenv 는 strict mode 를 켠다.
+        let database = Database::new_dotenv().await.unwrap();
+        assert!(database.strict_transactions);
+
+        let mut h = database.handle();
+        assert!(!h.is_transactional());
+        assert_eq!(h.depth(), 0);
+        assert!(matches!(
+            h.savepoint().await,
+            Err(crate::Error::IllegalState(_))
+        ));
+        assert!(matches!(
+            h.commit().await,
+            Err(crate::Error::IllegalState(_))
+        ));
+
+        // 같은 pool 을 써도 strict mode 는 Database 마다 따로 정해진다.
+        let lenient = Database {
+            strict_transactions: false,
+            ..database.clone()
+        };
+        lenient.handle().commit().await.unwrap();
+        lenient.conn().await.unwrap().rollback().await.unwrap();
+        assert!(matches!(
+            database.conn().await.unwrap().rollback().await,
+            Err(crate::Error::IllegalState(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_builder_ext() {
+        let database = Database::new_dotenv().await.unwrap();
+        let mut h = database.handle();
+        sqlx::query(
+            "CREATE TEMPORARY TABLE builder_test (id BIGINT NOT NULL, name VARCHAR(50) NOT NULL)",
+        )
+        .execute(h.inner())
+        .await
+        .unwrap();
+
+        let names = ["Alice", "bob", "50%_off", "carol"];
+        let rows: Vec<(i64, &str)> = (1..).zip(names).collect();
+        let inserted = insert_values(
+            &mut h,
+            "INSERT INTO builder_test (id, name) ",
+            2,
+            &rows,
+            |mut row, (id, name)| {
+                row.push_bind(*id).push_bind(name.to_string());
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(inserted, 4);
+
+        let select = || QueryBuilder::<Driver>::new("SELECT id FROM builder_test WHERE 1 = 1");
+        async fn ids(h: &mut Handle<'_>, mut b: QueryBuilder<'_, Driver>) -> Vec<i64> {
+            b.push(" ORDER BY id");
+            b.build_query_scalar().fetch_all(h.inner()).await.unwrap()
+        }
+
+        let (wanted, none) = (vec![1i64, 3, 9], Vec::<i64>::new());
+        let mut b = select();
+        b.push_in(" AND id", &wanted);
+        assert_eq!(ids(&mut h, b).await, vec![1, 3]);
+        let mut b = select();
+        b.push_in(" AND id", &none);
+        assert!(ids(&mut h, b).await.is_empty());
+
+        let (from, to, unbounded) = (Some(2i64), Some(3i64), None);
+        let mut b = select();
+        b.push_between_option(" AND id", &from, &to);
+        assert_eq!(ids(&mut h, b).await, vec![2, 3]);
+        let mut b = select();
+        b.push_between_option(" AND id", &from, &unbounded);
+        assert_eq!(ids(&mut h, b).await, vec![2, 3, 4]);
+        let mut b = select();
+        b.push_between_option(" AND id", &unbounded, &unbounded);
+        assert_eq!(ids(&mut h, b).await.len(), 4);
+
+        let mut b = select();
+        b.push_ilike_option(" AND name", &Some("ALI"));
+        assert_eq!(ids(&mut h, b).await, vec![1]);
+        let mut b = select();
+        b.push_ilike_option(" AND name", &Some("%_"));
+        assert_eq!(ids(&mut h, b).await, vec![3]);
+        let mut b = select();
+        b.push_ilike_option(" AND name", &None::<String>);
+        assert_eq!(ids(&mut h, b).await.len(), 4);
+
+        // bind 파라미터 한도를 넘으면 여러 문장으로 나눠 넣는다.
+        let many: Vec<i64> = (100..100 + MAX_BIND_PARAMS as i64 + 10).collect();
+        let inserted = insert_values(
+            &mut h,
+            "INSERT INTO builder_test (id, name) ",
+            2,
+            &many,
+            |mut row, id| {
+                row.push_bind(*id).push_bind("bulk");
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(inserted, many.len() as u64);
+    }
 }
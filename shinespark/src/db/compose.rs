@@ -0,0 +1,642 @@
+use std::ops::{Bound, RangeBounds};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use sqlx::QueryBuilder;
+
+use super::{Driver, SqlBuilderExt, SqlComposer, SqlListValue};
+
+// 컬럼 이름은 `&'static str` 만 받는다. 요청 값은 항상 bind 되고, SQL 에 그대로 들어가는
+// 식별자는 코드에 적힌 문자열뿐이다. 값은 builder 보다 오래 살아야 하므로 `&str` 대신 `String` 을 쓴다.
+
+/// 비교 연산자
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Op::Eq => " = ",
+            Op::Ne => " <> ",
+            Op::Lt => " < ",
+            Op::Le => " <= ",
+            Op::Gt => " > ",
+            Op::Ge => " >= ",
+        }
+    }
+}
+
+/// `column <op> value`
+#[derive(Debug, Clone)]
+pub struct Compare<T> {
+    column: &'static str,
+    op: Op,
+    value: T,
+}
+
+impl<T> Compare<T> {
+    pub fn new(column: &'static str, op: Op, value: T) -> Self {
+        Self { column, op, value }
+    }
+
+    pub fn eq(column: &'static str, value: T) -> Self {
+        Self::new(column, Op::Eq, value)
+    }
+
+    pub fn ne(column: &'static str, value: T) -> Self {
+        Self::new(column, Op::Ne, value)
+    }
+}
+
+impl<T> SqlComposer for Compare<T>
+where
+    T: sqlx::Type<Driver> + for<'q> sqlx::Encode<'q, Driver> + Send + Sync,
+{
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        query_builder.push(self.column).push(self.op.as_str()).push_bind(&self.value);
+        Ok(())
+    }
+}
+
+/// `column IN (v1, v2, ...)` (postgres 는 `column = ANY($n)`). 값이 없으면 어떤 행도 고르지 않는다.
+#[derive(Debug, Clone)]
+pub struct In<T> {
+    column: &'static str,
+    values: Vec<T>,
+}
+
+impl<T> In<T> {
+    pub fn new(column: &'static str, values: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            column,
+            values: values.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> SqlComposer for In<T>
+where
+    T: for<'q> SqlListValue<'q>,
+{
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        query_builder.push_in(self.column, &self.values);
+        Ok(())
+    }
+}
+
+/// LIKE 의 `%`, `_` 와 escape 문자 `!` 를 글자 그대로 찾도록 escape 한다. `ESCAPE '!'` 와 함께 쓴다.
+///
+/// mysql 은 문자열 안의 `\` 를 escape 로 해석하므로 드라이버마다 같은 SQL 을 쓰려고 `!` 를 쓴다.
+pub fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '!') {
+            escaped.push('!');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 대소문자를 무시하면 postgres 는 `ILIKE` 를 쓴다. sqlite 의 `LIKE` 는 ASCII 대소문자를,
+/// mysql 은 기본 `_ci` collation 에서 대소문자를 무시하므로 `LIKE` 그대로 쓴다.
+pub(super) fn like_operator(ignore_case: bool) -> &'static str {
+    match ignore_case && cfg!(feature = "db-driver-postgres") {
+        true => " ILIKE ",
+        false => " LIKE ",
+    }
+}
+
+/// `column LIKE pattern ESCAPE '!'`. 입력은 escape 되므로 `%`, `_` 가 wildcard 로 동작하지 않는다.
+///
+/// 대소문자 구분은 드라이버마다 다르다. `ignore_case()` 를 붙이면 모든 드라이버에서 무시하지만,
+/// 붙이지 않아도 대소문자를 구분하는 것은 postgres 뿐이다. sqlite 는 ASCII 대소문자를, mysql 은
+/// `_ci` collation 컬럼에서 대소문자를 무시한다. 구분해야 하면 `Compare` 나 `_bin` collation 을 쓴다.
+#[derive(Debug, Clone)]
+pub struct Like {
+    column: &'static str,
+    pattern: String,
+    ignore_case: bool,
+}
+
+impl Like {
+    pub fn contains(column: &'static str, value: &str) -> Self {
+        Self {
+            column,
+            pattern: format!("%{}%", escape_like(value)),
+            ignore_case: false,
+        }
+    }
+
+    pub fn starts_with(column: &'static str, value: &str) -> Self {
+        Self {
+            column,
+            pattern: format!("{}%", escape_like(value)),
+            ignore_case: false,
+        }
+    }
+
+    pub fn ends_with(column: &'static str, value: &str) -> Self {
+        Self {
+            column,
+            pattern: format!("%{}", escape_like(value)),
+            ignore_case: false,
+        }
+    }
+
+    /// postgres 도 대소문자를 무시하도록 `ILIKE` 를 쓴다.
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+}
+
+impl SqlComposer for Like {
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        query_builder
+            .push(self.column)
+            .push(like_operator(self.ignore_case))
+            .push_bind(&self.pattern)
+            .push(" ESCAPE '!'");
+        Ok(())
+    }
+}
+
+/// `column >= start AND column < end`. `Range::new("id", 10..20)` 처럼 Rust range 로 경계를 정한다.
+#[derive(Debug, Clone)]
+pub struct Range<T> {
+    column: &'static str,
+    start: Bound<T>,
+    end: Bound<T>,
+}
+
+impl<T: Clone> Range<T> {
+    pub fn new(column: &'static str, range: impl RangeBounds<T>) -> Self {
+        Self {
+            column,
+            start: range.start_bound().cloned(),
+            end: range.end_bound().cloned(),
+        }
+    }
+}
+
+impl<T> SqlComposer for Range<T>
+where
+    T: sqlx::Type<Driver> + for<'q> sqlx::Encode<'q, Driver> + Send + Sync,
+{
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        let bounds = [
+            match &self.start {
+                Bound::Included(value) => Some((Op::Ge, value)),
+                Bound::Excluded(value) => Some((Op::Gt, value)),
+                Bound::Unbounded => None,
+            },
+            match &self.end {
+                Bound::Included(value) => Some((Op::Le, value)),
+                Bound::Excluded(value) => Some((Op::Lt, value)),
+                Bound::Unbounded => None,
+            },
+        ];
+        let mut separated = query_builder.separated(" AND ");
+        let mut any = false;
+        for (op, value) in bounds.into_iter().flatten() {
+            separated.push(self.column).push_unseparated(op.as_str()).push_bind_unseparated(value);
+            any = true;
+        }
+        if !any {
+            query_builder.push("1 = 1");
+        }
+        Ok(())
+    }
+}
+
+/// 조건을 AND 로 묶는다. 조건이 없으면 아무것도 붙이지 않는다.
+///
+/// ```ignore
+/// let filter = Filter::new()
+///     .and(Compare::eq("u.status", "active".to_string()))
+///     .and_option(query.email.map(|email| Like::contains("u.email", &email)));
+/// let mut b = QueryBuilder::new("SELECT * FROM shs_iam_user u");
+/// filter.compose(&mut b)?;
+/// ```
+pub struct Filter<'a> {
+    prefix: &'static str,
+    predicates: Vec<Box<dyn SqlComposer + Send + Sync + 'a>>,
+}
+
+impl Default for Filter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Filter<'a> {
+    /// ` WHERE a AND b` 를 붙인다.
+    pub fn new() -> Self {
+        Self {
+            prefix: " WHERE ",
+            predicates: vec![],
+        }
+    }
+
+    /// 이미 `WHERE` 가 있는 SQL (예: `WHERE 1 = 1`) 뒤에 ` AND a AND b` 를 붙인다.
+    pub fn after_where() -> Self {
+        Self {
+            prefix: " AND ",
+            predicates: vec![],
+        }
+    }
+
+    pub fn and(mut self, predicate: impl SqlComposer + Send + Sync + 'a) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    pub fn and_option(self, predicate: Option<impl SqlComposer + Send + Sync + 'a>) -> Self {
+        match predicate {
+            Some(predicate) => self.and(predicate),
+            None => self,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.predicates.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Filter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Filter").field("predicates", &self.predicates.len()).finish()
+    }
+}
+
+impl SqlComposer for Filter<'_> {
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        for (i, predicate) in self.predicates.iter().enumerate() {
+            query_builder.push(if i == 0 { self.prefix } else { " AND " }).push("(");
+            predicate.compose(query_builder)?;
+            query_builder.push(")");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// `ORDER BY`. 요청에서 온 정렬 키는 `Sort::parse` 로 허용한 컬럼에만 대응시킨다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sort {
+    columns: Vec<(&'static str, Direction)>,
+}
+
+impl Sort {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `"name,-created_at"` 처럼 쉼표로 구분한 키를 읽는다. `-` 로 시작하면 내림차순이다.
+    ///
+    /// `allowed` 는 (요청 키, 컬럼) 목록이다. 목록에 없는 키는 `Error::InvalidArgument`.
+    pub fn parse(input: &str, allowed: &[(&str, &'static str)]) -> crate::Result<Self> {
+        let mut sort = Self::new();
+        for key in input.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            let (key, direction) = match key.strip_prefix('-') {
+                Some(key) => (key, Direction::Desc),
+                None => (key, Direction::Asc),
+            };
+            let Some((_, column)) = allowed.iter().find(|(k, _)| *k == key) else {
+                return Err(crate::Error::InvalidArgument(
+                    format!("unknown sort key: {}", key).into(),
+                ));
+            };
+            sort.columns.push((column, direction));
+        }
+        Ok(sort)
+    }
+
+    pub fn asc(mut self, column: &'static str) -> Self {
+        self.columns.push((column, Direction::Asc));
+        self
+    }
+
+    pub fn desc(mut self, column: &'static str) -> Self {
+        self.columns.push((column, Direction::Desc));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// `cursor` 다음 행만 고르는 keyset 조건. 정렬이 행마다 유일해야 하므로 마지막에 id 같은
+    /// 유일한 컬럼을 둔다.
+    pub fn after(&self, cursor: Cursor) -> Keyset {
+        Keyset {
+            sort: self.clone(),
+            cursor,
+        }
+    }
+}
+
+impl SqlComposer for Sort {
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        for (i, (column, direction)) in self.columns.iter().enumerate() {
+            query_builder.push(if i == 0 { " ORDER BY " } else { ", " }).push(*column);
+            query_builder.push(match direction {
+                Direction::Asc => " ASC",
+                Direction::Desc => " DESC",
+            });
+        }
+        Ok(())
+    }
+}
+
+/// `LIMIT n OFFSET m`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit {
+    limit: i64,
+    offset: i64,
+}
+
+impl Limit {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit: limit as i64,
+            offset: 0,
+        }
+    }
+
+    /// 다음 page 가 있는지 알 수 있도록 `limit + 1` 개를 조회한다. 결과는 `Page::from_rows` 로 자른다.
+    pub fn lookahead(limit: u32) -> Self {
+        Self::new(limit.saturating_add(1))
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset.min(i64::MAX as u64) as i64;
+        self
+    }
+}
+
+impl SqlComposer for Limit {
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        query_builder.push(" LIMIT ").push_bind(self.limit);
+        if self.offset > 0 {
+            query_builder.push(" OFFSET ").push_bind(self.offset);
+        }
+        Ok(())
+    }
+}
+
+/// cursor 에 담는 정렬 컬럼 값
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorValue {
+    Int(i64),
+    Text(String),
+    Uuid(uuid::Uuid),
+}
+
+impl From<i64> for CursorValue {
+    fn from(value: i64) -> Self {
+        CursorValue::Int(value)
+    }
+}
+
+impl From<String> for CursorValue {
+    fn from(value: String) -> Self {
+        CursorValue::Text(value)
+    }
+}
+
+impl From<&str> for CursorValue {
+    fn from(value: &str) -> Self {
+        CursorValue::Text(value.to_string())
+    }
+}
+
+impl From<uuid::Uuid> for CursorValue {
+    fn from(value: uuid::Uuid) -> Self {
+        CursorValue::Uuid(value)
+    }
+}
+
+/// 마지막으로 본 행의 정렬 컬럼 값. 클라이언트에는 base64url 문자열로 내보낸다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor(pub Vec<CursorValue>);
+
+impl Cursor {
+    pub fn new(values: impl IntoIterator<Item = CursorValue>) -> Self {
+        Self(values.into_iter().collect())
+    }
+
+    pub fn encode(&self) -> String {
+        // Vec<CursorValue> 직렬화는 실패하지 않는다.
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&self.0).unwrap_or_default())
+    }
+
+    /// 잘못된 cursor 는 `Error::InvalidArgument`.
+    pub fn decode(encoded: &str) -> crate::Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(encoded)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .map(Self)
+            .ok_or_else(|| crate::Error::InvalidArgument("invalid cursor".into()))
+    }
+}
+
+/// `Sort` 순서로 `cursor` 다음에 오는 행만 고르는 조건. `Sort::after` 로 만든다.
+///
+/// `(a, b) > (x, y)` 를 `a > x OR (a = x AND b > y)` 로 풀어 쓰므로 컬럼마다 방향이 달라도 된다.
+#[derive(Debug, Clone)]
+pub struct Keyset {
+    sort: Sort,
+    cursor: Cursor,
+}
+
+impl SqlComposer for Keyset {
+    fn compose<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Driver>) -> crate::Result<()> {
+        let columns = &self.sort.columns;
+        if columns.is_empty() || columns.len() != self.cursor.0.len() {
+            return Err(crate::Error::InvalidArgument("invalid cursor".into()));
+        }
+        for i in 0..columns.len() {
+            query_builder.push(if i == 0 { "(" } else { " OR (" });
+            for (j, ((column, direction), value)) in
+                columns.iter().zip(&self.cursor.0).take(i + 1).enumerate()
+            {
+                let op = match (j == i, direction) {
+                    (false, _) => Op::Eq,
+                    (true, Direction::Asc) => Op::Gt,
+                    (true, Direction::Desc) => Op::Lt,
+                };
+                if j > 0 {
+                    query_builder.push(" AND ");
+                }
+                query_builder.push(*column).push(op.as_str());
+                match value {
+                    CursorValue::Int(value) => query_builder.push_bind(value),
+                    CursorValue::Text(value) => query_builder.push_bind(value),
+                    CursorValue::Uuid(value) => query_builder.push_bind(value),
+                };
+            }
+            query_builder.push(")");
+        }
+        Ok(())
+    }
+}
+
+/// keyset pagination 결과
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 다음 page 를 요청할 때 넘기는 cursor. 마지막 page 면 `None`.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// `Limit::lookahead(limit)` 로 조회한 행으로 page 를 만든다. `limit` 보다 많으면 다음 page 가 있다.
+    pub fn from_rows(mut rows: Vec<T>, limit: u32, cursor: impl Fn(&T) -> Cursor) -> Self {
+        let has_more = rows.len() > limit as usize;
+        rows.truncate(limit as usize);
+        let next_cursor = match has_more {
+            true => rows.last().map(|row| cursor(row).encode()),
+            false => None,
+        };
+        Self {
+            items: rows,
+            next_cursor,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    const SORT_KEYS: &[(&str, &str)] = &[("name", "name"), ("id", "id")];
+
+    async fn setup() -> Database {
+        let database = Database::new_dotenv().await.unwrap();
+        sqlx::query(
+            "CREATE TEMPORARY TABLE compose_test (id BIGINT NOT NULL, name VARCHAR(50) NOT NULL)",
+        )
+        .execute(&database.inner)
+        .await
+        .unwrap();
+        for (id, name) in [(1, "a_1"), (2, "a%2"), (3, "ab3"), (4, "b"), (5, "b")] {
+            let mut b = QueryBuilder::<Driver>::new("INSERT INTO compose_test (id, name) VALUES (");
+            b.push_bind(id as i64).push(", ").push_bind(name).push(")");
+            b.build().execute(&database.inner).await.unwrap();
+        }
+        database
+    }
+
+    async fn ids(database: &Database, parts: &[&(dyn SqlComposer + Sync)]) -> Vec<i64> {
+        let mut b = QueryBuilder::new("SELECT id FROM compose_test");
+        for part in parts {
+            part.compose(&mut b).unwrap();
+        }
+        b.build_query_scalar().fetch_all(&database.inner).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_predicates() {
+        let database = setup().await;
+        let by_id = Sort::new().asc("id");
+
+        let filter = Filter::new().and(Like::contains("name", "_"));
+        assert_eq!(ids(&database, &[&filter, &by_id]).await, vec![1]);
+        let filter = Filter::new().and(Like::starts_with("name", "a%"));
+        assert_eq!(ids(&database, &[&filter, &by_id]).await, vec![2]);
+
+        let filter =
+            Filter::new().and(In::new("id", [2i64, 4])).and(Compare::ne("name", "b".to_string()));
+        assert_eq!(ids(&database, &[&filter, &by_id]).await, vec![2]);
+        let filter = Filter::new().and(In::new("id", Vec::<i64>::new()));
+        assert!(ids(&database, &[&filter]).await.is_empty());
+
+        let filter = Filter::new().and(Range::new("id", 2i64..4));
+        assert_eq!(ids(&database, &[&filter, &by_id]).await, vec![2, 3]);
+        let filter = Filter::new().and(Range::new("id", 4i64..)).and_option(None::<Compare<i64>>);
+        assert_eq!(ids(&database, &[&filter, &by_id]).await, vec![4, 5]);
+
+        let filter = Filter::new();
+        let limit = Limit::new(2).offset(1);
+        assert_eq!(ids(&database, &[&filter, &by_id, &limit]).await, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_keyset_pagination() {
+        let database = setup().await;
+        let sort = Sort::parse("-name, id", SORT_KEYS).unwrap();
+
+        let mut seen = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            let filter = match &cursor {
+                Some(c) => Filter::new().and(sort.after(Cursor::decode(c).unwrap())),
+                None => Filter::new(),
+            };
+            let mut b = QueryBuilder::new("SELECT id, name FROM compose_test");
+            filter.compose(&mut b).unwrap();
+            sort.compose(&mut b).unwrap();
+            let limit = Limit::lookahead(2);
+            limit.compose(&mut b).unwrap();
+            let rows: Vec<(i64, String)> =
+                b.build_query_as().fetch_all(&database.inner).await.unwrap();
+            let page = Page::from_rows(rows, 2, |(id, name)| {
+                Cursor::new([name.as_str().into(), (*id).into()])
+            });
+            assert!(page.items.len() <= 2);
+            seen.extend(page.items.iter().map(|(id, _)| *id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(seen, vec![4, 5, 3, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_and_cursor() {
+        assert!(matches!(
+            Sort::parse("password", SORT_KEYS),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+        assert_eq!(
+            Sort::parse("-name,id", SORT_KEYS).unwrap(),
+            Sort::new().desc("name").asc("id")
+        );
+
+        let cursor = Cursor::new(["b".into(), 5i64.into()]);
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(matches!(
+            Cursor::decode("not a cursor"),
+            Err(crate::Error::InvalidArgument(_))
+        ));
+
+        assert_eq!(escape_like("50%_off!"), "50!%!_off!!");
+    }
+}
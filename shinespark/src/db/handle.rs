@@ -1,11 +1,26 @@
+use std::sync::Arc;
+
 use futures_core::{future::BoxFuture, stream::BoxStream};
 
-use sqlx::Acquire;
+use sqlx::{Acquire, TransactionManager};
+
+use super::replica::ReplicaSet;
 
 pub fn map_err(e: sqlx::Error) -> crate::Error {
     crate::Error::DatabaseError(anyhow::Error::new(e))
 }
 
+/// strict mode 에서는 트랜잭션이 아닌 handle(`Pool`, `Conn`) 을 commit, rollback 하면
+/// 아무 일도 하지 않고 넘어가는 대신 `Error::IllegalState` 를 반환한다.
+fn not_transactional(op: &str, strict: bool) -> crate::Result<()> {
+    match strict {
+        true => Err(crate::Error::IllegalState(
+            format!("{} on a non-transactional handle", op).into(),
+        )),
+        false => Ok(()),
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct ExecutorImpl<'h, 'c, DB>
@@ -14,6 +29,8 @@ where
     for<'e> &'e mut DB::Connection: sqlx::Executor<'e, Database = DB>,
 {
     pub handle: &'h mut BasicHandle<'c, DB>,
+    /// true 면 `Replicated` handle 이 replica 로 읽는다.
+    read: bool,
 }
 
 #[derive(Debug)]
@@ -22,9 +39,12 @@ where
     DB: sqlx::Database,
     for<'e> &'e mut DB::Connection: sqlx::Executor<'e, Database = DB>,
 {
-    Pool(sqlx::Pool<DB>),
+    /// `bool` 은 strict mode. `Database` 의 `strict_transactions` 설정을 따른다.
+    Pool(sqlx::Pool<DB>, bool),
     Tx(sqlx::Transaction<'c, DB>),
-    Conn(sqlx::pool::PoolConnection<DB>),
+    Conn(sqlx::pool::PoolConnection<DB>, bool),
+    /// replica 가 설정된 primary pool. `Pool` 처럼 동작하고 `read_inner()` 로 실행한 쿼리만 replica 로 간다.
+    Replicated(sqlx::Pool<DB>, Arc<ReplicaSet<DB>>, bool),
 }
 
 impl<'c, DB> BasicHandle<'c, DB>
@@ -32,34 +52,80 @@ where
     DB: sqlx::Database,
     for<'e> &'e mut DB::Connection: sqlx::Executor<'e, Database = DB>,
 {
+    /// 트랜잭션 안이면 true. `Pool`, `Conn` 은 쿼리마다 auto-commit 된다.
+    pub fn is_transactional(&self) -> bool {
+        matches!(self, BasicHandle::Tx(_))
+    }
+
+    /// 열려 있는 트랜잭션 깊이. 0 은 트랜잭션 밖, 1 은 최상위 트랜잭션, 2 이상은 savepoint 이다.
+    pub fn depth(&self) -> usize {
+        match self {
+            BasicHandle::Pool(..) | BasicHandle::Replicated(..) => 0,
+            BasicHandle::Tx(tx) => DB::TransactionManager::get_transaction_depth(tx),
+            BasicHandle::Conn(conn, _) => DB::TransactionManager::get_transaction_depth(conn),
+        }
+    }
+
+    /// 트랜잭션 밖이면 새 트랜잭션을, 안이면 savepoint 를 시작한다.
     pub async fn begin(&mut self) -> crate::Result<BasicHandle<'_, DB>> {
         let tx = match self {
-            BasicHandle::Pool(pool) => pool.begin().await,
+            BasicHandle::Pool(pool, _) | BasicHandle::Replicated(pool, ..) => pool.begin().await,
             BasicHandle::Tx(tx) => tx.begin().await,
-            BasicHandle::Conn(conn) => conn.begin().await,
+            BasicHandle::Conn(conn, _) => conn.begin().await,
         }
         .map_err(map_err)?;
         Ok(BasicHandle::Tx(tx))
     }
 
+    /// 현재 트랜잭션 안에 savepoint 를 만든다. 트랜잭션 밖이면 `Error::IllegalState`.
+    ///
+    /// 돌려받은 handle 을 commit 하면 savepoint 를 release 하고, rollback 하거나 drop 하면
+    /// savepoint 이후의 변경만 되돌린다. 바깥 트랜잭션은 그대로 열려 있다.
+    pub async fn savepoint(&mut self) -> crate::Result<BasicHandle<'_, DB>> {
+        match self {
+            BasicHandle::Tx(tx) => Ok(BasicHandle::Tx(tx.begin().await.map_err(map_err)?)),
+            _ => Err(crate::Error::IllegalState(
+                "savepoint on a non-transactional handle".into(),
+            )),
+        }
+    }
+
+    /// 트랜잭션(또는 savepoint) 을 commit 한다. 트랜잭션이 아니면 strict mode 에서만 실패한다.
     pub async fn commit(self) -> crate::Result<()> {
         match self {
-            BasicHandle::Pool(_) => Ok(()),
             BasicHandle::Tx(tx) => tx.commit().await.map_err(map_err),
-            BasicHandle::Conn(_) => Ok(()),
+            BasicHandle::Pool(_, strict)
+            | BasicHandle::Conn(_, strict)
+            | BasicHandle::Replicated(.., strict) => not_transactional("commit", strict),
         }
     }
 
+    /// 트랜잭션(또는 savepoint) 을 rollback 한다. 트랜잭션이 아니면 strict mode 에서만 실패한다.
     pub async fn rollback(self) -> crate::Result<()> {
         match self {
-            BasicHandle::Pool(_) => Ok(()),
             BasicHandle::Tx(tx) => tx.rollback().await.map_err(map_err),
-            BasicHandle::Conn(_) => Ok(()),
+            BasicHandle::Pool(_, strict)
+            | BasicHandle::Conn(_, strict)
+            | BasicHandle::Replicated(.., strict) => not_transactional("rollback", strict),
         }
     }
 
     pub fn inner<'h>(&'h mut self) -> ExecutorImpl<'h, 'c, DB> {
-        ExecutorImpl { handle: self }
+        ExecutorImpl {
+            handle: self,
+            read: false,
+        }
+    }
+
+    /// 조회용 executor. replica 가 있는 handle 이면 healthy replica 로, 아니면 `inner()` 와 같다.
+    ///
+    /// 트랜잭션과 커넥션은 항상 primary 를 쓴다. replica 는 primary 보다 늦을 수 있으므로
+    /// 방금 쓴 값을 다시 읽는 곳에서는 `inner()` 를 쓴다.
+    pub fn read_inner<'h>(&'h mut self) -> ExecutorImpl<'h, 'c, DB> {
+        ExecutorImpl {
+            handle: self,
+            read: true,
+        }
     }
 }
 
@@ -83,10 +149,22 @@ where
 //         'c: 'h;
 
 //     fn as_executor<'h>(&'h mut self) -> Self::Executor<'h> {
-//         ExecutorImpl { handle: self }
+//         ExecutorImpl { handle: self, read: false }
 //     }
 // }
 
+/// `read` 면 healthy replica 를, 없거나 쓰기면 primary 를 고른다.
+fn route<'p, DB: sqlx::Database>(
+    primary: &'p sqlx::Pool<DB>,
+    replicas: &'p ReplicaSet<DB>,
+    read: bool,
+) -> &'p sqlx::Pool<DB> {
+    match read {
+        true => replicas.pick().unwrap_or(primary),
+        false => primary,
+    }
+}
+
 impl<'h, 'c, DB> sqlx::Executor<'h> for ExecutorImpl<'h, 'c, DB>
 where
     DB: sqlx::Database,
@@ -115,9 +193,12 @@ where
         E: 'q + sqlx::Execute<'q, Self::Database>,
     {
         match self.handle {
-            BasicHandle::Pool(pool) => pool.fetch_many(query),
+            BasicHandle::Pool(pool, _) => pool.fetch_many(query),
             BasicHandle::Tx(tx) => tx.fetch_many(query),
-            BasicHandle::Conn(conn) => conn.fetch_many(query),
+            BasicHandle::Conn(conn, _) => conn.fetch_many(query),
+            BasicHandle::Replicated(primary, replicas, _) => {
+                route(primary, replicas, self.read).fetch_many(query)
+            }
         }
     }
 
@@ -132,9 +213,12 @@ where
         E: 'q + sqlx::Execute<'q, Self::Database>,
     {
         match self.handle {
-            BasicHandle::Pool(pool) => pool.fetch_optional(query),
+            BasicHandle::Pool(pool, _) => pool.fetch_optional(query),
             BasicHandle::Tx(tx) => tx.fetch_optional(query),
-            BasicHandle::Conn(conn) => conn.fetch_optional(query),
+            BasicHandle::Conn(conn, _) => conn.fetch_optional(query),
+            BasicHandle::Replicated(primary, replicas, _) => {
+                route(primary, replicas, self.read).fetch_optional(query)
+            }
         }
     }
 
@@ -153,9 +237,12 @@ where
         'h: 'e,
     {
         match self.handle {
-            BasicHandle::Pool(pool) => pool.prepare_with(sql, parameters),
+            BasicHandle::Pool(pool, _) => pool.prepare_with(sql, parameters),
             BasicHandle::Tx(tx) => tx.prepare_with(sql, parameters),
-            BasicHandle::Conn(conn) => conn.prepare_with(sql, parameters),
+            BasicHandle::Conn(conn, _) => conn.prepare_with(sql, parameters),
+            BasicHandle::Replicated(primary, replicas, _) => {
+                route(primary, replicas, self.read).prepare_with(sql, parameters)
+            }
         }
     }
 
@@ -168,9 +255,12 @@ where
         'h: 'e,
     {
         match self.handle {
-            BasicHandle::Pool(pool) => pool.describe(sql),
+            BasicHandle::Pool(pool, _) => pool.describe(sql),
             BasicHandle::Tx(tx) => tx.describe(sql),
-            BasicHandle::Conn(conn) => conn.describe(sql),
+            BasicHandle::Conn(conn, _) => conn.describe(sql),
+            BasicHandle::Replicated(primary, replicas, _) => {
+                route(primary, replicas, self.read).describe(sql)
+            }
         }
     }
 }
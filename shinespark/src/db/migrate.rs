@@ -0,0 +1,148 @@
+use std::fmt;
+
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+
+use super::Database;
+
+/// 현재 드라이버의 마이그레이션. 빌드할 때 바이너리에 포함된다.
+#[cfg(feature = "db-driver-postgres")]
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+#[cfg(feature = "db-driver-sqlite")]
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations/sqlite");
+
+#[cfg(feature = "db-driver-mysql")]
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations/mysql");
+
+fn migrate_err(e: MigrateError) -> crate::Error {
+    crate::Error::DatabaseError(anyhow::anyhow!(e))
+}
+
+/// 바이너리에 포함된 마이그레이션과 DB 에 적용된 마이그레이션의 차이
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// 적용된 버전 수
+    pub applied: usize,
+    /// 바이너리에는 있지만 아직 적용되지 않은 버전 (스키마가 바이너리보다 뒤처짐)
+    pub pending: Vec<i64>,
+    /// DB 에는 적용되어 있지만 바이너리에 없는 버전 (스키마가 바이너리보다 앞섬)
+    pub unknown: Vec<i64>,
+    /// 적용된 뒤 파일 내용이 바뀐 버전
+    pub modified: Vec<i64>,
+    /// 적용하다 실패해 남아 있는 버전
+    pub dirty: Option<i64>,
+}
+
+impl MigrationStatus {
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+            && self.unknown.is_empty()
+            && self.modified.is_empty()
+            && self.dirty.is_none()
+    }
+}
+
+impl fmt::Display for MigrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "applied={}", self.applied)?;
+        if !self.pending.is_empty() {
+            write!(f, ", pending={:?}", self.pending)?;
+        }
+        if !self.unknown.is_empty() {
+            write!(f, ", unknown={:?}", self.unknown)?;
+        }
+        if !self.modified.is_empty() {
+            write!(f, ", modified={:?}", self.modified)?;
+        }
+        if let Some(version) = self.dirty {
+            write!(f, ", dirty={}", version)?;
+        }
+        Ok(())
+    }
+}
+
+impl Database {
+    /// 아직 적용하지 않은 마이그레이션을 적용한다.
+    ///
+    /// DB 에 바이너리가 모르는 버전이 있거나 적용된 파일이 바뀌었으면 아무것도 하지 않고 실패한다.
+    pub async fn migrate(&self) -> crate::Result<()> {
+        MIGRATOR.run(&self.inner).await.map_err(migrate_err)
+    }
+
+    pub async fn migration_status(&self) -> crate::Result<MigrationStatus> {
+        let mut conn = self.inner.acquire().await.map_err(super::map_err)?;
+        conn.ensure_migrations_table().await.map_err(migrate_err)?;
+        let dirty = conn.dirty_version().await.map_err(migrate_err)?;
+        let applied = conn.list_applied_migrations().await.map_err(migrate_err)?;
+
+        let embedded: Vec<_> =
+            MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()).collect();
+
+        let mut status = MigrationStatus {
+            applied: applied.len(),
+            dirty,
+            ..Default::default()
+        };
+        for migration in &embedded {
+            match applied.iter().find(|a| a.version == migration.version) {
+                None => status.pending.push(migration.version),
+                Some(a) if a.checksum != migration.checksum => {
+                    status.modified.push(migration.version)
+                }
+                Some(_) => {}
+            }
+        }
+        status.unknown = applied
+            .iter()
+            .filter(|a| !embedded.iter().any(|m| m.version == a.version))
+            .map(|a| a.version)
+            .collect();
+        Ok(status)
+    }
+
+    /// 스키마가 바이너리와 다르면(뒤처지거나 앞서 있으면) 실패한다. 서버 시작 전에 호출한다.
+    pub async fn ensure_migrated(&self) -> crate::Result<()> {
+        let status = self.migration_status().await?;
+        if !status.is_up_to_date() {
+            return Err(crate::Error::IllegalState(
+                format!("database schema does not match this binary ({})", status).into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// _sqlx_migrations 를 고치므로 매번 새 in-memory DB 를 쓰는 sqlite 빌드에서만 실행한다.
+#[cfg(all(test, feature = "db-driver-sqlite"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_migration_status() {
+        let database = Database::new_dotenv().await.unwrap();
+        database.migrate().await.unwrap();
+
+        let status = database.migration_status().await.unwrap();
+        assert!(status.is_up_to_date(), "{}", status);
+        assert_eq!(status.applied, MIGRATOR.iter().count());
+        database.ensure_migrated().await.unwrap();
+
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = (SELECT MAX(version) FROM _sqlx_migrations)")
+            .execute(&database.inner)
+            .await
+            .unwrap();
+        let status = database.migration_status().await.unwrap();
+        assert_eq!(status.pending.len(), 1);
+        assert!(database.ensure_migrated().await.is_err());
+
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES (99990101000000, 'future', TRUE, x'00', 0)",
+        )
+        .execute(&database.inner)
+        .await
+        .unwrap();
+        let status = database.migration_status().await.unwrap();
+        assert_eq!(status.unknown, vec![99990101000000]);
+        assert!(database.migrate().await.is_err());
+    }
+}
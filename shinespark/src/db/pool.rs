@@ -0,0 +1,146 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use sqlx::{Executor, pool::PoolOptions};
+
+use super::{Database, Driver, map_err};
+use crate::config::DatabaseConfig;
+
+type ConnectOptions = <<Driver as sqlx::Database>::Connection as sqlx::Connection>::Options;
+
+/// health, metrics 에 내보내는 pool 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PoolStats {
+    /// 열려 있는 커넥션 수 (사용 중 + 유휴)
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    pub min_connections: u32,
+    pub max_connections: u32,
+}
+
+pub(super) fn pool_options(config: &DatabaseConfig) -> PoolOptions<Driver> {
+    let after_connect = Arc::new(after_connect_sql(config));
+    PoolOptions::<Driver>::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout(non_zero_secs(config.idle_timeout_secs))
+        .max_lifetime(non_zero_secs(config.max_lifetime_secs))
+        .test_before_acquire(config.test_before_acquire)
+        .after_connect(move |conn, _meta| {
+            let after_connect = after_connect.clone();
+            Box::pin(async move {
+                for sql in after_connect.iter() {
+                    conn.execute(sql.as_str()).await?;
+                }
+                Ok(())
+            })
+        })
+}
+
+/// `url` 로 연결 옵션을 만든다. primary 와 replica 가 같은 `config` 설정을 쓴다.
+#[cfg_attr(not(feature = "db-driver-postgres"), allow(unused_variables))]
+pub(super) fn connect_options(config: &DatabaseConfig, url: &str) -> crate::Result<ConnectOptions> {
+    let options: ConnectOptions = url.parse().map_err(map_err)?;
+    #[cfg(feature = "db-driver-postgres")]
+    let options = {
+        let options = match &config.application_name {
+            Some(name) => options.application_name(name),
+            None => options,
+        };
+        match config.statement_timeout_ms {
+            0 => options,
+            ms => options.options([("statement_timeout", ms.to_string())]),
+        }
+    };
+    Ok(options)
+}
+
+/// 커넥션을 열 때마다 실행하는 SQL. mysql 은 statement timeout 을 세션 변수로 설정한다.
+#[cfg(feature = "db-driver-mysql")]
+fn after_connect_sql(config: &DatabaseConfig) -> Vec<String> {
+    let mut sql = vec![];
+    // max_execution_time 은 SELECT 에만 적용된다.
+    if config.statement_timeout_ms > 0 {
+        sql.push(format!(
+            "SET SESSION max_execution_time = {}",
+            config.statement_timeout_ms
+        ));
+    }
+    sql.extend(config.after_connect.iter().cloned());
+    sql
+}
+
+/// 커넥션을 열 때마다 실행하는 SQL
+#[cfg(not(feature = "db-driver-mysql"))]
+fn after_connect_sql(config: &DatabaseConfig) -> Vec<String> {
+    config.after_connect.clone()
+}
+
+fn non_zero_secs(secs: u64) -> Option<Duration> {
+    match secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+pub(super) fn stats(pool: &sqlx::Pool<Driver>) -> PoolStats {
+    let size = pool.size();
+    let idle = pool.num_idle() as u32;
+    let options = pool.options();
+    PoolStats {
+        size,
+        idle,
+        in_use: size.saturating_sub(idle),
+        min_connections: options.get_min_connections(),
+        max_connections: options.get_max_connections(),
+    }
+}
+
+pub(super) async fn ping(pool: &sqlx::Pool<Driver>) -> crate::Result<()> {
+    let mut conn = pool.acquire().await.map_err(map_err)?;
+    sqlx::Connection::ping(&mut *conn).await.map_err(map_err)
+}
+
+impl Database {
+    /// primary pool 상태
+    pub fn pool_stats(&self) -> PoolStats {
+        stats(&self.inner)
+    }
+
+    /// primary 에서 커넥션을 하나 얻어 DB 가 응답하는지 확인한다.
+    pub async fn ping(&self) -> crate::Result<()> {
+        ping(&self.inner).await
+    }
+}
+
+// 외부 DB 없이 설정을 바꿔 가며 연결하므로 sqlite 빌드에서만 실행한다.
+#[cfg(all(test, feature = "db-driver-sqlite"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pool_stats_and_after_connect() {
+        // sqlx 는 sqlite 커넥션의 foreign_keys 를 켜서 연다.
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 2,
+            after_connect: vec!["PRAGMA foreign_keys = OFF".to_string()],
+            ..Default::default()
+        };
+        let database = Database::new(&config).await.unwrap();
+        database.ping().await.unwrap();
+
+        let stats = database.pool_stats();
+        assert_eq!(stats.max_connections, 2);
+        assert_eq!(stats.size, stats.idle + stats.in_use);
+
+        let mut conn = database.inner.acquire().await.unwrap();
+        assert!(database.pool_stats().in_use >= 1);
+        let foreign_keys: i64 =
+            sqlx::query_scalar("PRAGMA foreign_keys").fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(foreign_keys, 0);
+    }
+}
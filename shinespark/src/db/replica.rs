@@ -0,0 +1,209 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{Database, Handle, PoolStats, pool};
+use crate::config::DatabaseConfig;
+
+/// 이 시간 안에 응답하지 않는 replica 는 unhealthy 로 본다.
+const REPLICA_PING_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug)]
+struct Replica<DB: sqlx::Database> {
+    pool: sqlx::Pool<DB>,
+    healthy: AtomicBool,
+}
+
+/// 읽기 전용 replica pool 목록. healthy 인 replica 를 돌아가며 고른다.
+#[derive(Debug)]
+pub struct ReplicaSet<DB: sqlx::Database> {
+    replicas: Vec<Replica<DB>>,
+    next: AtomicUsize,
+}
+
+impl<DB: sqlx::Database> ReplicaSet<DB> {
+    pub(super) fn new(pools: Vec<sqlx::Pool<DB>>) -> Self {
+        Self {
+            replicas: pools
+                .into_iter()
+                .map(|pool| Replica {
+                    pool,
+                    healthy: AtomicBool::new(false),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.replicas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replicas.is_empty()
+    }
+
+    /// 다음 healthy replica. 모두 unhealthy 면 `None`.
+    pub fn pick(&self) -> Option<&sqlx::Pool<DB>> {
+        let len = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..len)
+            .map(|i| &self.replicas[(start + i) % len])
+            .find(|replica| replica.healthy.load(Ordering::Relaxed))
+            .map(|replica| &replica.pool)
+    }
+}
+
+/// health, metrics 에 내보내는 replica 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReplicaStats {
+    pub healthy: bool,
+    pub pool: PoolStats,
+}
+
+pub(super) fn connect_replicas(
+    config: &DatabaseConfig,
+) -> crate::Result<Vec<sqlx::Pool<super::Driver>>> {
+    // 시작할 때 replica 가 내려가 있어도 primary 로 동작하도록 연결은 처음 쓸 때 연다.
+    config
+        .replicas
+        .iter()
+        .map(|url| {
+            let options = pool::connect_options(config, url)?;
+            Ok(pool::pool_options(config).connect_lazy_with(options))
+        })
+        .collect()
+}
+
+impl Database {
+    /// 읽기 전용 handle. healthy replica 를 돌아가며 쓰고, 없으면 primary 를 쓴다.
+    ///
+    /// replica 는 primary 보다 늦을 수 있으므로 방금 쓴 값을 읽어야 하면 `handle()` 이나 트랜잭션을 쓴다.
+    pub fn read_handle(&self) -> Handle<'_> {
+        match self.replicas.pick() {
+            Some(replica) => Handle::Pool(replica.clone(), self.strict_transactions),
+            None => Handle::Pool(self.inner.clone(), self.strict_transactions),
+        }
+    }
+
+    /// 모든 replica 에 ping 을 보내 healthy 여부를 갱신한다.
+    pub async fn check_replicas(&self) {
+        for (i, replica) in self.replicas.replicas.iter().enumerate() {
+            let healthy =
+                match tokio::time::timeout(REPLICA_PING_TIMEOUT, pool::ping(&replica.pool)).await {
+                    Ok(Ok(())) => true,
+                    Ok(Err(e)) => {
+                        tracing::warn!(replica = i, error = %e, "replica health check failed");
+                        false
+                    }
+                    Err(_) => {
+                        tracing::warn!(replica = i, "replica health check timed out");
+                        false
+                    }
+                };
+            let was_healthy = replica.healthy.swap(healthy, Ordering::Relaxed);
+            if healthy && !was_healthy {
+                tracing::info!(replica = i, "replica is healthy");
+            }
+        }
+    }
+
+    /// `interval` 마다 `check_replicas` 를 실행한다. replica 가 없으면 아무것도 하지 않는다.
+    pub fn spawn_replica_health_check(&self, interval: Duration) {
+        if self.replicas.is_empty() {
+            return;
+        }
+        let database = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                database.check_replicas().await;
+            }
+        });
+    }
+
+    pub fn replica_stats(&self) -> Vec<ReplicaStats> {
+        self.replicas
+            .replicas
+            .iter()
+            .map(|replica| ReplicaStats {
+                healthy: replica.healthy.load(Ordering::Relaxed),
+                pool: pool::stats(&replica.pool),
+            })
+            .collect()
+    }
+}
+
+// 파일 DB 두 개를 primary, replica 로 써서 읽기가 어디로 가는지 확인하므로 sqlite 빌드에서만 실행한다.
+#[cfg(all(test, feature = "db-driver-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db::Driver;
+
+    async fn which<'e>(executor: impl sqlx::Executor<'e, Database = Driver>) -> String {
+        sqlx::query_scalar("SELECT name FROM replica_test").fetch_one(executor).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_routing() {
+        let dir = std::env::temp_dir().join(format!("shinespark-replica-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = |name: &str| format!("sqlite://{}?mode=rwc", dir.join(name).display());
+        for name in ["primary", "replica"] {
+            let database = Database::new(&DatabaseConfig {
+                url: url(name),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+            sqlx::query("DROP TABLE IF EXISTS replica_test")
+                .execute(&database.inner)
+                .await
+                .unwrap();
+            sqlx::query("CREATE TABLE replica_test (name TEXT NOT NULL)")
+                .execute(&database.inner)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO replica_test (name) VALUES (?)")
+                .bind(name)
+                .execute(&database.inner)
+                .await
+                .unwrap();
+        }
+
+        let database = Database::new(&DatabaseConfig {
+            url: url("primary"),
+            replicas: vec![
+                url("replica"),
+                "sqlite:///nonexistent/replica.db".to_string(),
+            ],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let healthy: Vec<_> = database.replica_stats().iter().map(|s| s.healthy).collect();
+        assert_eq!(healthy, vec![true, false]);
+
+        // unhealthy replica 는 건너뛴다.
+        for _ in 0..3 {
+            assert_eq!(which(database.read_handle().inner()).await, "replica");
+            assert_eq!(which(database.handle().read_inner()).await, "replica");
+        }
+        assert_eq!(which(database.handle().inner()).await, "primary");
+        let mut tx = database.tx().await.unwrap();
+        assert_eq!(which(tx.read_inner()).await, "primary");
+        tx.rollback().await.unwrap();
+
+        // healthy replica 가 없으면 primary 로 읽는다.
+        database.replicas.replicas[0].healthy.store(false, Ordering::Relaxed);
+        assert_eq!(which(database.read_handle().inner()).await, "primary");
+        assert_eq!(which(database.handle().read_inner()).await, "primary");
+
+        database.check_replicas().await;
+        assert_eq!(which(database.handle().read_inner()).await, "replica");
+
+        database.inner.close().await;
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
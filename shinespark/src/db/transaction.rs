@@ -0,0 +1,317 @@
+use std::fmt;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use super::{Database, Handle, map_err};
+
+/// 트랜잭션 격리 수준. sqlite 는 항상 serializable 로 동작하므로 무시한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl fmt::Display for IsolationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        })
+    }
+}
+
+/// `Database::transaction_with` 옵션
+#[derive(Debug, Clone)]
+pub struct TxOptions {
+    /// `None` 이면 DB 기본 격리 수준
+    pub isolation: Option<IsolationLevel>,
+    /// 직렬화 실패, deadlock 으로 실패했을 때 다시 시도하는 횟수
+    pub max_retries: u32,
+    /// 첫 재시도 전 대기 시간. 재시도할 때마다 두 배가 된다.
+    pub backoff: Duration,
+}
+
+impl Default for TxOptions {
+    fn default() -> Self {
+        Self {
+            isolation: None,
+            max_retries: 3,
+            backoff: Duration::from_millis(10),
+        }
+    }
+}
+
+impl TxOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn isolation(mut self, isolation: IsolationLevel) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << attempt.min(10))
+    }
+}
+
+/// 다시 실행하면 성공할 수 있는 오류인지. postgres 의 serialization_failure(40001),
+/// deadlock_detected(40P01) 와 mysql 의 deadlock(40001) 이 해당한다.
+pub fn is_retryable(error: &crate::Error) -> bool {
+    let crate::Error::DatabaseError(e) = error else {
+        return false;
+    };
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(e)) => matches!(e.code().as_deref(), Some("40001" | "40P01")),
+        _ => false,
+    }
+}
+
+impl Database {
+    /// `f` 를 하나의 트랜잭션 안에서 실행한다. `Ok` 면 commit, `Err` 면 rollback 한다.
+    ///
+    /// `f` 가 panic 하면 트랜잭션이 commit 되지 않은 채 drop 되므로 커넥션이 pool 로 돌아가면서
+    /// rollback 된다. 재시도할 수 있는 오류면 `f` 를 처음부터 다시 실행하므로
+    /// `f` 안에서는 DB 밖의 부수 효과를 만들지 않는다.
+    ///
+    /// ```ignore
+    /// let user = db
+    ///     .transaction(|h| Box::pin(user_usecase.create_user(h, command.clone())))
+    ///     .await?;
+    /// ```
+    pub async fn transaction<'a, T, F>(&'a self, f: F) -> crate::Result<T>
+    where
+        F: for<'h> FnMut(&'h mut Handle<'a>) -> BoxFuture<'h, crate::Result<T>>,
+    {
+        self.transaction_with(TxOptions::default(), f).await
+    }
+
+    pub async fn transaction_with<'a, T, F>(
+        &'a self,
+        options: TxOptions,
+        mut f: F,
+    ) -> crate::Result<T>
+    where
+        F: for<'h> FnMut(&'h mut Handle<'a>) -> BoxFuture<'h, crate::Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.run_transaction(options.isolation, &mut f).await {
+                Err(e) if attempt < options.max_retries && is_retryable(&e) => {
+                    tracing::warn!(attempt, error = %e, "transaction conflict, retrying");
+                    tokio::time::sleep(options.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn run_transaction<'a, T, F>(
+        &'a self,
+        isolation: Option<IsolationLevel>,
+        f: &mut F,
+    ) -> crate::Result<T>
+    where
+        F: for<'h> FnMut(&'h mut Handle<'a>) -> BoxFuture<'h, crate::Result<T>>,
+    {
+        let mut handle: Handle<'a> = self.begin_isolated(isolation).await?;
+        match f(&mut handle).await {
+            Ok(value) => {
+                handle.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback) = handle.rollback().await {
+                    tracing::warn!(error = %rollback, "transaction rollback failed");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn begin_isolated(
+        &self,
+        isolation: Option<IsolationLevel>,
+    ) -> crate::Result<Handle<'static>> {
+        let tx = match isolation {
+            #[cfg(feature = "db-driver-postgres")]
+            Some(level) => self.inner.begin_with(format!("BEGIN ISOLATION LEVEL {}", level)).await,
+            // mysql 은 START TRANSACTION 에 격리 수준을 줄 수 없어 다음 트랜잭션에만 적용되는 SET 을 먼저 보낸다.
+            #[cfg(feature = "db-driver-mysql")]
+            Some(level) => {
+                self.inner
+                    .begin_with(format!(
+                        "SET TRANSACTION ISOLATION LEVEL {}; START TRANSACTION",
+                        level
+                    ))
+                    .await
+            }
+            _ => self.inner.begin().await,
+        }
+        .map_err(map_err)?;
+        Ok(Handle::Tx(tx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::error::Error as StdError;
+
+    use super::*;
+
+    async fn setup() -> Database {
+        let database = Database::new_dotenv().await.unwrap();
+        sqlx::query("CREATE TEMPORARY TABLE tx_test (id INTEGER NOT NULL)")
+            .execute(&database.inner)
+            .await
+            .unwrap();
+        database
+    }
+
+    async fn count(database: &Database) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM tx_test").fetch_one(&database.inner).await.unwrap()
+    }
+
+    async fn insert(handle: &mut Handle<'_>) -> crate::Result<()> {
+        sqlx::query("INSERT INTO tx_test (id) VALUES (1)")
+            .execute(handle.inner())
+            .await
+            .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_transaction_commit_and_rollback() {
+        let database = setup().await;
+
+        database.transaction(|h| Box::pin(insert(h))).await.unwrap();
+        assert_eq!(count(&database).await, 1);
+
+        let result: crate::Result<()> = database
+            .transaction(|h| {
+                Box::pin(async move {
+                    insert(h).await?;
+                    Err(crate::Error::NotFound)
+                })
+            })
+            .await;
+        assert!(matches!(result, Err(crate::Error::NotFound)));
+        assert_eq!(count(&database).await, 1);
+
+        let panicked = {
+            let database = database.clone();
+            tokio::spawn(async move {
+                database
+                    .transaction::<(), _>(|h| {
+                        Box::pin(async move {
+                            insert(h).await?;
+                            panic!("boom");
+                        })
+                    })
+                    .await
+            })
+            .await
+        };
+        assert!(panicked.unwrap_err().is_panic());
+        assert_eq!(count(&database).await, 1);
+    }
+
+    #[derive(Debug)]
+    struct SerializationFailure;
+
+    impl fmt::Display for SerializationFailure {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("could not serialize access")
+        }
+    }
+
+    impl StdError for SerializationFailure {}
+
+    impl sqlx::error::DatabaseError for SerializationFailure {
+        fn message(&self) -> &str {
+            "could not serialize access"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some("40001".into())
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    #[tokio::test]
+    #[cfg_attr(not(feature = "db-driver-sqlite"), ignore)]
+    async fn test_transaction_retry() {
+        let database = setup().await;
+        let options = TxOptions::new()
+            .isolation(IsolationLevel::Serializable)
+            .backoff(Duration::from_millis(1));
+
+        let mut attempts = 0;
+        database
+            .transaction_with(options.clone(), |h| {
+                attempts += 1;
+                let attempt = attempts;
+                Box::pin(async move {
+                    insert(h).await?;
+                    if attempt < 3 {
+                        return Err(map_err(sqlx::Error::Database(Box::new(
+                            SerializationFailure,
+                        ))));
+                    }
+                    Ok(())
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(attempts, 3);
+        assert_eq!(count(&database).await, 1);
+
+        let mut attempts = 0;
+        let result: crate::Result<()> = database
+            .transaction_with(options.max_retries(1), |_| {
+                attempts += 1;
+                Box::pin(async {
+                    Err(map_err(sqlx::Error::Database(Box::new(
+                        SerializationFailure,
+                    ))))
+                })
+            })
+            .await;
+        assert!(result.as_ref().is_err_and(is_retryable));
+        assert_eq!(attempts, 2);
+    }
+}
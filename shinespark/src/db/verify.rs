@@ -0,0 +1,233 @@
+use std::fmt::Debug;
+
+#[cfg(not(feature = "db-driver-mysql"))]
+use sqlx::{Column, TypeInfo};
+use sqlx::{Either, Executor};
+
+use super::{Driver, Handle, SqlStatement};
+
+type Row = <Driver as sqlx::Database>::Row;
+/// `returns::<O>()` 가 기억하는 decode 함수
+type DecodeFn = fn(&Row) -> Result<(), sqlx::Error>;
+
+/// `SqlStatement` 를 마이그레이션한 DB 에 prepare 해서 SQL 과 Rust 코드가 맞는지 확인한다.
+///
+/// SQL 파일은 런타임에 읽으므로 컬럼 이름이 바뀌어도 컴파일은 된다. 저장소 테스트에서 모든
+/// statement 를 `check_statements` 로 확인해 실행하기 전에 어긋난 곳을 찾는다.
+///
+/// - `describe` 로 prepare 한다 (문법, 없는 테이블/컬럼)
+/// - `params` 가 주어지면 bind 파라미터 수를 비교한다
+/// - `returns::<O>()` 는 결과 컬럼 이름과 타입으로 만든 행을 `O` 로 decode 한다. 한 번은 모든
+///   컬럼에 값을 넣고, 한 번은 nullable 컬럼을 NULL 로 두므로 `Option` 이 빠진 필드도 찾는다.
+///   (postgres, sqlite. mysql 은 식의 결과 타입을 알려주지 않아 prepare 와 파라미터 수만 확인한다.)
+///
+/// ```ignore
+/// StatementCheck::new(&UserQuery::GetUserById)
+///     .params(1)
+///     .sample("status", "active")
+///     .returns::<User>()
+///     .run(&mut handle)
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatementCheck {
+    label: String,
+    sql: &'static str,
+    params: Option<usize>,
+    samples: Vec<(&'static str, &'static str)>,
+    decode: Option<DecodeFn>,
+}
+
+impl StatementCheck {
+    pub fn new<S: SqlStatement + Debug>(statement: &S) -> Self {
+        Self {
+            label: format!("{:?}", statement),
+            sql: statement.as_str(),
+            params: None,
+            samples: vec![],
+            decode: None,
+        }
+    }
+
+    /// 기대하는 bind 파라미터 수. postgres 는 같은 `$n` 을 여러 번 써도 하나로 센다.
+    pub fn params(mut self, params: usize) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// 결과 행을 만들 때 `column` 에 넣을 값. 기본값(`''`, `0` 등) 으로 decode 할 수 없는
+    /// 컬럼(예: 문자열 enum, JSON 집계) 에 쓴다. sample 을 준 컬럼은 NULL 로 두지 않는다.
+    pub fn sample(mut self, column: &'static str, value: &'static str) -> Self {
+        self.samples.push((column, value));
+        self
+    }
+
+    /// 결과 컬럼을 `O` 로 decode 할 수 있어야 한다.
+    pub fn returns<O>(mut self) -> Self
+    where
+        O: for<'r> sqlx::FromRow<'r, Row>,
+    {
+        self.decode = Some(|row| O::from_row(row).map(drop));
+        self
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> crate::Error {
+        crate::Error::IllegalState(format!("{}: {}", self.label, message).into())
+    }
+
+    /// prepare 하고 파라미터 수와 (`returns` 가 있으면) 결과 컬럼을 확인한다.
+    pub async fn run(&self, handle: &mut Handle<'_>) -> crate::Result<()> {
+        let describe = self.prepare(handle).await?;
+        let Some(_decode) = self.decode else {
+            return Ok(());
+        };
+        if describe.columns().is_empty() {
+            return Err(self.error("statement returns no columns"));
+        }
+        #[cfg(not(feature = "db-driver-mysql"))]
+        for nulls in [false, true] {
+            let sql = self.sample_row_sql(&describe, nulls);
+            let row = sqlx::query(&sql).fetch_one(handle.inner()).await.map_err(super::map_err)?;
+            _decode(&row).map_err(|e| match nulls {
+                false => self.error(format!("result columns do not match: {}", e)),
+                true => self.error(format!("nullable result column is not an Option: {}", e)),
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn prepare(&self, handle: &mut Handle<'_>) -> crate::Result<sqlx::Describe<Driver>> {
+        let describe = handle
+            .inner()
+            .describe(self.sql)
+            .await
+            .map_err(|e| self.error(format!("failed to prepare: {}", e)))?;
+        let actual = match describe.parameters() {
+            Some(Either::Left(types)) => Some(types.len()),
+            Some(Either::Right(count)) => Some(count),
+            None => None,
+        };
+        if let (Some(expected), Some(actual)) = (self.params, actual)
+            && expected != actual
+        {
+            return Err(self.error(format!(
+                "expected {} parameters, statement has {}",
+                expected, actual
+            )));
+        }
+        Ok(describe)
+    }
+
+    /// describe 한 컬럼 이름과 타입 그대로 값을 채운 `SELECT` 한 행.
+    /// `nulls` 면 nullable 로 describe 된 컬럼은 (sample 이 없으면) NULL 로 둔다.
+    #[cfg(not(feature = "db-driver-mysql"))]
+    fn sample_row_sql(&self, describe: &sqlx::Describe<Driver>, nulls: bool) -> String {
+        let columns: Vec<String> = describe
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let type_name = column.type_info().name();
+                let sample = self.samples.iter().find(|(name, _)| *name == column.name());
+                let value = match (sample, nulls && describe.nullable(i) == Some(true)) {
+                    (Some((_, value)), _) => Some(format!("'{}'", value.replace('\'', "''"))),
+                    (None, true) => None,
+                    (None, false) => default_value(type_name).map(str::to_string),
+                };
+                let value = value.unwrap_or_else(|| "NULL".to_string());
+                format!(
+                    "{} AS \"{}\"",
+                    typed(value, type_name),
+                    column.name().replace('"', "\"\"")
+                )
+            })
+            .collect();
+        format!("SELECT {}", columns.join(", "))
+    }
+}
+
+/// 마이그레이션한 DB 에 `statements` 를 모두 prepare 하고 어긋난 것을 한 번에 돌려준다.
+///
+/// 실행하지 않고 prepare 만 하므로 스키마만 있으면 된다. `check` 를 variant 별 `match` 로 쓰면
+/// variant 를 추가했을 때 검사도 고쳐야 컴파일된다.
+///
+/// ```ignore
+/// check_statements(&mut handle, [UserQuery::CreateUser, UserQuery::GetUserById], |statement, check| {
+///     match statement {
+///         UserQuery::CreateUser => check.params(4),
+///         UserQuery::GetUserById => check.params(1).returns::<User>(),
+///     }
+/// })
+/// .await?;
+/// ```
+pub async fn check_statements<S, F>(
+    handle: &mut Handle<'_>,
+    statements: impl IntoIterator<Item = S>,
+    check: F,
+) -> crate::Result<()>
+where
+    S: SqlStatement + Debug,
+    F: Fn(&S, StatementCheck) -> StatementCheck,
+{
+    let mut errors = vec![];
+    for statement in statements {
+        let statement_check = check(&statement, StatementCheck::new(&statement));
+        if let Err(e) = statement_check.run(handle).await {
+            errors.push(e.to_string());
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(crate::Error::IllegalState(errors.join("\n").into())),
+    }
+}
+
+/// postgres 는 describe 한 타입으로 CAST 한다.
+#[cfg(feature = "db-driver-postgres")]
+fn typed(value: String, type_name: &str) -> String {
+    format!("CAST({} AS {})", value, type_name)
+}
+
+/// sqlite 는 값의 저장 타입으로 decode 하므로 CAST 하지 않고 리터럴을 그대로 쓴다.
+/// (`CAST('2000-01-01' AS DATETIME)` 은 NUMERIC affinity 라 숫자 2000 이 된다.)
+#[cfg(feature = "db-driver-sqlite")]
+fn typed(value: String, _type_name: &str) -> String {
+    value
+}
+
+/// 타입마다 decode 할 수 있는 값. 모르는 타입은 NULL 이므로 `Option` 이 아니면 `sample` 이 필요하다.
+#[cfg(feature = "db-driver-postgres")]
+fn default_value(type_name: &str) -> Option<&'static str> {
+    if type_name.ends_with("[]") {
+        return Some("'{}'");
+    }
+    match type_name {
+        "INT2" | "INT4" | "INT8" | "FLOAT4" | "FLOAT8" | "NUMERIC" | "OID" => Some("'0'"),
+        "BOOL" => Some("'false'"),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CITEXT" | "BYTEA" => Some("''"),
+        "TIMESTAMPTZ" | "TIMESTAMP" => Some("'2000-01-01 00:00:00'"),
+        "DATE" => Some("'2000-01-01'"),
+        "TIME" | "TIMETZ" => Some("'00:00:00'"),
+        "UUID" => Some("'00000000-0000-0000-0000-000000000000'"),
+        "JSON" | "JSONB" => Some("'[]'"),
+        "INET" => Some("'0.0.0.0'"),
+        _ => None,
+    }
+}
+
+/// 선언된 컬럼 타입마다 decode 할 수 있는 리터럴. 식의 결과처럼 타입이 `NULL` 이면 `sample` 이 필요하다.
+///
+/// 시각은 TEXT, uuid 는 BLOB 으로 저장하므로 TEXT 는 문자열로도 시각으로도 읽히는 값을,
+/// BLOB 은 16 바이트를 쓴다.
+#[cfg(feature = "db-driver-sqlite")]
+fn default_value(type_name: &str) -> Option<&'static str> {
+    match type_name {
+        "INTEGER" | "BOOLEAN" | "NUMERIC" => Some("0"),
+        "REAL" => Some("0.0"),
+        "TEXT" | "DATETIME" => Some("'2000-01-01 00:00:00'"),
+        "BLOB" => Some("zeroblob(16)"),
+        "DATE" => Some("'2000-01-01'"),
+        "TIME" => Some("'00:00:00'"),
+        _ => None,
+    }
+}
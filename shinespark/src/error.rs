@@ -10,6 +10,10 @@ pub enum Error {
     #[error("illegal state: {0}")]
     IllegalState(Cow<'static, str>),
 
+    /// 클라이언트가 보낸 값(정렬 키, cursor 등) 이 잘못됐다.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(Cow<'static, str>),
+
     #[error("not implemented")]
     NotImplemented,
 
@@ -36,6 +40,7 @@ impl Error {
         match self {
             Error::Internal(_) => "INTERNAL",
             Error::IllegalState(_) => "ILLEGAL_STATE",
+            Error::InvalidArgument(_) => "INVALID_ARGUMENT",
             Error::NotImplemented => "NOT_IMPLEMENTED",
             Error::UnAuthorized => "UNAUTHORIZED",
             Error::DatabaseError(_) => "DATABASE_ERROR",
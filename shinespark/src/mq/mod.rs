@@ -1,4 +1,9 @@
+#[cfg(feature = "db-driver-mysql")]
+pub mod mysql;
+#[cfg(feature = "db-driver-postgres")]
 pub mod pg;
+#[cfg(feature = "db-driver-sqlite")]
+pub mod sqlite;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
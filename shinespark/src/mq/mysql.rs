@@ -0,0 +1,216 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::{Consumer, Message, MessageQueue, Publisher};
+use crate::db::Database;
+
+fn db_err(e: sqlx::Error) -> crate::Error {
+    crate::Error::DatabaseError(anyhow::anyhow!(e))
+}
+
+/// mysql 은 `UPDATE ... RETURNING` 이 없으므로 한 트랜잭션 안에서
+/// `SELECT ... FOR UPDATE SKIP LOCKED` 로 잡은 행을 `UPDATE` 한다. (mysql 8 이상)
+pub struct MySqlMessageQueue {
+    pub db: Database,
+}
+
+impl MySqlMessageQueue {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn reap_stale(&self) -> crate::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE shs_mq_messages
+            SET    status     = CASE WHEN attempts >= max_attempts THEN 'failed' ELSE 'pending' END,
+                   locked_at  = NULL,
+                   updated_at = CURRENT_TIMESTAMP(6)
+            WHERE  status    = 'processing'
+              AND  locked_at < UTC_TIMESTAMP(6) - INTERVAL 5 MINUTE
+            "#,
+        )
+        .execute(&self.db.inner)
+        .await
+        .map_err(db_err)?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(FromRow)]
+struct MqRecord {
+    id: Uuid,
+    topic: String,
+    payload: serde_json::Value,
+    created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl MessageQueue for MySqlMessageQueue {
+    async fn ack(&self, id: Uuid) -> crate::Result<()> {
+        sqlx::query(
+            "UPDATE shs_mq_messages SET status = 'done', done_at = CURRENT_TIMESTAMP(6), updated_at = CURRENT_TIMESTAMP(6) WHERE id = ?",
+        )
+        .bind(id)
+        .execute(&self.db.inner)
+        .await
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    async fn nack(&self, id: Uuid) -> crate::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE shs_mq_messages
+            SET    status     = CASE WHEN attempts >= max_attempts THEN 'failed' ELSE 'pending' END,
+                   locked_at  = NULL,
+                   updated_at = CURRENT_TIMESTAMP(6)
+            WHERE  id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&self.db.inner)
+        .await
+        .map_err(db_err)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Send + Sync + 'static> Publisher<T> for MySqlMessageQueue {
+    async fn publish(&self, topic: &str, payload: T) -> crate::Result<Uuid> {
+        let value = serde_json::to_value(&payload)
+            .map_err(|e| crate::Error::Internal(anyhow::anyhow!(e)))?;
+
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO shs_mq_messages (id, topic, payload) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(topic)
+            .bind(value)
+            .execute(&self.db.inner)
+            .await
+            .map_err(db_err)?;
+
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send + Sync + 'static> Consumer<T> for MySqlMessageQueue {
+    async fn poll(&self, topic: &str) -> crate::Result<Option<Message<T>>> {
+        let mut tx = self.db.inner.begin().await.map_err(db_err)?;
+
+        let row: Option<MqRecord> = sqlx::query_as(
+            r#"
+            SELECT id, topic, payload, created_at
+            FROM   shs_mq_messages
+            WHERE  topic    = ?
+              AND  status   = 'pending'
+              AND  attempts < max_attempts
+            ORDER BY created_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(topic)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_err)?;
+
+        let Some(r) = row else {
+            tx.commit().await.map_err(db_err)?;
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE shs_mq_messages
+            SET    status     = 'processing',
+                   locked_at  = CURRENT_TIMESTAMP(6),
+                   attempts   = attempts + 1,
+                   updated_at = CURRENT_TIMESTAMP(6)
+            WHERE  id = ?
+            "#,
+        )
+        .bind(r.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_err)?;
+        tx.commit().await.map_err(db_err)?;
+
+        let payload = serde_json::from_value(r.payload)
+            .map_err(|e| crate::Error::Internal(anyhow::anyhow!(e)))?;
+        Ok(Some(Message {
+            id: r.id,
+            topic: r.topic,
+            payload,
+            created_at: r.created_at,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestPayload {
+        value: String,
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_publish_poll_ack() {
+        let db = Database::new_dotenv().await.unwrap();
+        let mq = MySqlMessageQueue::new(db);
+
+        let id = mq
+            .publish(
+                "test-topic",
+                TestPayload {
+                    value: "hello".into(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let msg: Option<Message<TestPayload>> = mq.poll("test-topic").await.unwrap();
+        let msg = msg.expect("message should exist");
+        assert_eq!(msg.id, id);
+        assert_eq!(msg.payload.value, "hello");
+
+        mq.ack(msg.id).await.unwrap();
+
+        let next: Option<Message<TestPayload>> = mq.poll("test-topic").await.unwrap();
+        assert!(next.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_nack_retry_to_failed() {
+        let db = Database::new_dotenv().await.unwrap();
+        let mq = MySqlMessageQueue::new(db);
+
+        mq.publish(
+            "retry-topic",
+            TestPayload {
+                value: "retry".into(),
+            },
+        )
+        .await
+        .unwrap();
+
+        for _ in 0..3 {
+            let msg: Message<TestPayload> =
+                mq.poll("retry-topic").await.unwrap().expect("should exist");
+            mq.nack(msg.id).await.unwrap();
+        }
+
+        let next: Option<Message<TestPayload>> = mq.poll("retry-topic").await.unwrap();
+        assert!(next.is_none(), "exhausted message must not be polled");
+    }
+}